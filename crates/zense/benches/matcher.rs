@@ -0,0 +1,144 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Benchmark for resolving paths with matchers.
+//!
+//! This benchmark doesn't depend on a benchmarking framework, so it can be
+//! run with `cargo bench` on stable Rust. Each case is run for a number of
+//! iterations after a warm-up, and the mean time per iteration is reported,
+//! together with the mean number of heap allocations, which are counted by a
+//! global allocator wrapping the system allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use zense::handler::matcher::{Matcher, Route};
+
+// ----------------------------------------------------------------------------
+// Constants
+// ----------------------------------------------------------------------------
+
+/// Number of iterations per case.
+const ITERATIONS: u32 = 100_000;
+
+/// Numbers of routes.
+const ROUTES: [usize; 3] = [5, 50, 500];
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Allocator counting allocations.
+struct Counter;
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+unsafe impl GlobalAlloc for Counter {
+    /// Allocates memory, and counts the allocation.
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    /// Deallocates memory.
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Statics
+// ----------------------------------------------------------------------------
+
+/// Global allocator.
+#[global_allocator]
+static ALLOCATOR: Counter = Counter;
+
+/// Number of allocations.
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+// ----------------------------------------------------------------------------
+// Program
+// ----------------------------------------------------------------------------
+
+/// Runs all benchmarks.
+fn main() {
+    for routes in ROUTES {
+        let matcher = matcher(routes);
+
+        // Resolve path matching the route that was added last
+        let path = format!("/resource{}/42/items", routes - 1);
+        let (duration, allocations) = bench(|| {
+            let found = matcher.resolve(black_box(&path));
+            black_box(found.is_some());
+        });
+        let name = format!("resolve path with {routes} routes");
+        report(&name, duration, allocations);
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Creates a matcher with the given number of routes, each of which has a
+/// static segment, a parameter, and a trailing static segment.
+fn matcher(routes: usize) -> Matcher<usize> {
+    let mut matcher = Matcher::new();
+    for index in 0..routes {
+        let route = format!("/resource{index}/{{id}}/items");
+        let route = route.parse::<Route>().expect("invariant");
+        matcher.add(route, index).expect("invariant");
+    }
+    matcher
+}
+
+/// Runs the given function repeatedly, and returns the mean duration and
+/// number of allocations.
+#[allow(clippy::cast_precision_loss)]
+fn bench<F>(mut f: F) -> (Duration, f64)
+where
+    F: FnMut(),
+{
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let duration = start.elapsed() / ITERATIONS;
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    (duration, allocations as f64 / f64::from(ITERATIONS))
+}
+
+/// Prints the mean duration and number of allocations of the given case.
+fn report(name: &str, duration: Duration, allocations: f64) {
+    println!(
+        "{name:<40} {duration:>10.2?}/iter {allocations:>6.1} allocs/iter"
+    );
+}
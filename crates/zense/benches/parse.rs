@@ -0,0 +1,158 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Benchmark for parsing requests.
+//!
+//! This benchmark doesn't depend on a benchmarking framework, so it can be
+//! run with `cargo bench` on stable Rust. Each case is run for a number of
+//! iterations after a warm-up, and the mean time per iteration is reported,
+//! together with the mean number of heap allocations, which are counted by a
+//! global allocator wrapping the system allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use zense::http::Request;
+
+// ----------------------------------------------------------------------------
+// Constants
+// ----------------------------------------------------------------------------
+
+/// Number of iterations per case.
+const ITERATIONS: u32 = 100_000;
+
+/// Request without headers.
+const EMPTY: &[u8] = b"GET /posts/42 HTTP/1.1\r\n\r\n";
+
+/// Request with 20 common headers.
+const HEADERS: &[u8] = b"\
+    GET /posts/42?page=2 HTTP/1.1\r\n\
+    Host: example.com\r\n\
+    User-Agent: Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Firefox/128.0\r\n\
+    Accept: text/html,application/xhtml+xml,application/xml;q=0.9\r\n\
+    Accept-Language: en-US,en;q=0.5\r\n\
+    Accept-Encoding: gzip, deflate, br\r\n\
+    Accept-Charset: utf-8\r\n\
+    Connection: keep-alive\r\n\
+    Cookie: session=38afes7a8; theme=dark\r\n\
+    Referer: https://example.com/posts\r\n\
+    Origin: https://example.com\r\n\
+    Cache-Control: no-cache\r\n\
+    Pragma: no-cache\r\n\
+    If-None-Match: \"33a64df551425fcc55e4d42a148795d9f25f89d4\"\r\n\
+    If-Modified-Since: Wed, 21 Oct 2015 07:28:00 GMT\r\n\
+    Upgrade-Insecure-Requests: 1\r\n\
+    DNT: 1\r\n\
+    Authorization: Bearer 7f3c1a9e\r\n\
+    X-Requested-With: XMLHttpRequest\r\n\
+    X-Forwarded-For: 203.0.113.7\r\n\
+    X-Forwarded-Proto: https\r\n\
+    \r\n";
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Allocator counting allocations.
+struct Counter;
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+unsafe impl GlobalAlloc for Counter {
+    /// Allocates memory, and counts the allocation.
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    /// Deallocates memory.
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Statics
+// ----------------------------------------------------------------------------
+
+/// Global allocator.
+#[global_allocator]
+static ALLOCATOR: Counter = Counter;
+
+/// Number of allocations.
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+// ----------------------------------------------------------------------------
+// Program
+// ----------------------------------------------------------------------------
+
+/// Runs all benchmarks.
+fn main() {
+    // Parse request with common headers
+    let (duration, allocations) = bench(|| {
+        let req = Request::from_bytes(black_box(HEADERS));
+        black_box(req.ok());
+    });
+    report("parse request with 20 headers", duration, allocations);
+
+    // Parse request without headers, which must not allocate a header map
+    let (duration, allocations) = bench(|| {
+        let req = Request::from_bytes(black_box(EMPTY));
+        black_box(req.ok());
+    });
+    report("parse request without headers", duration, allocations);
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Runs the given function repeatedly, and returns the mean duration and
+/// number of allocations.
+#[allow(clippy::cast_precision_loss)]
+fn bench<F>(mut f: F) -> (Duration, f64)
+where
+    F: FnMut(),
+{
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let duration = start.elapsed() / ITERATIONS;
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    (duration, allocations as f64 / f64::from(ITERATIONS))
+}
+
+/// Prints the mean duration and number of allocations of the given case.
+fn report(name: &str, duration: Duration, allocations: f64) {
+    println!(
+        "{name:<40} {duration:>10.2?}/iter {allocations:>6.1} allocs/iter"
+    );
+}
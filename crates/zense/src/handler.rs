@@ -28,11 +28,16 @@ use crate::http::response::{IntoResponse, ResponseExt};
 use crate::http::{Request, Response, Status};
 
 mod error;
+mod fallback;
+mod function;
+pub mod health;
 pub mod matcher;
 mod scope;
 pub mod stack;
 
 pub use error::{Error, Result};
+pub use fallback::{or, Or};
+pub use function::{from_fn_with_error, FromFnWithError};
 pub use matcher::Matcher;
 pub use scope::Scope;
 pub use stack::Stack;
@@ -37,6 +37,15 @@ pub enum Error {
     /// Matcher error.
     #[error(transparent)]
     Matcher(#[from] matcher::Error),
+
+    /// Named middleware error.
+    #[error("middleware '{name}': {source}")]
+    Middleware {
+        /// Middleware name.
+        name: String,
+        /// Underlying error.
+        source: Box<Error>,
+    },
 }
 
 // ----------------------------------------------------------------------------
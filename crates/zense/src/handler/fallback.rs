@@ -0,0 +1,115 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Fallback handler.
+
+use crate::handler::Handler;
+use crate::http::{Request, Response, Status};
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Fallback handler.
+///
+/// This handler passes the request to the primary handler, and if it answers
+/// with "404 Not Found", to the fallback handler instead. Use [`or`] to create
+/// it.
+#[derive(Debug)]
+pub struct Or<A, B> {
+    /// Primary handler.
+    primary: A,
+    /// Fallback handler.
+    fallback: B,
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl<A, B> Handler for Or<A, B>
+where
+    A: Handler,
+    B: Handler,
+{
+    /// Handles the given request.
+    ///
+    /// Since the primary handler consumes the request, it's cloned before, so
+    /// it can be passed to the fallback handler. Note that this copies owned
+    /// bodies, while bodies borrowed from the parsed input are not copied.
+    fn handle(&self, req: Request) -> Response {
+        let res = self.primary.handle(req.clone());
+        if res.status == Status::NotFound {
+            self.fallback.handle(req)
+        } else {
+            res
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Creates a handler that falls back to another handler.
+///
+/// The fallback handler is invoked when the primary handler answers with "404
+/// Not Found", which allows to compose handlers that are only responsible for
+/// a part of the requests, e.g., an API router and a handler serving files.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{or, Handler, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Request, Response, Status};
+/// use zense::router::{Params, Router};
+///
+/// // Create router for API
+/// let api = Router::new("/api")
+///     .get("/coffee", |req: Request, params: Params| Response::text("api"))
+///     .try_into_handler()?;
+///
+/// // Create handler falling back to another handler
+/// let handler = or(api, |req: Request| Response::text("fallback"));
+///
+/// // Handle request with primary handler
+/// let res = handler.handle(Request::new().uri("/api/coffee"));
+/// assert_eq!(res.status, Status::Ok);
+/// assert_eq!(res.body, b"api");
+///
+/// // Handle request with fallback handler
+/// let res = handler.handle(Request::new().uri("/index.html"));
+/// assert_eq!(res.status, Status::Ok);
+/// assert_eq!(res.body, b"fallback");
+/// # Ok(())
+/// # }
+/// ```
+pub fn or<A, B>(primary: A, fallback: B) -> Or<A, B>
+where
+    A: Handler,
+    B: Handler,
+{
+    Or { primary, fallback }
+}
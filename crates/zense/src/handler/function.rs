@@ -0,0 +1,119 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Function handler.
+
+use crate::handler::Handler;
+use crate::http::response::IntoResponse;
+use crate::http::{Request, Response};
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Function handler with error mapper.
+///
+/// This handler wraps a fallible function, and maps the errors it returns to
+/// responses with the given error mapper, which allows to answer specific
+/// errors with specific status codes. Use [`from_fn_with_error`] to create it.
+pub struct FromFnWithError<F, M> {
+    /// Fallible function.
+    f: F,
+    /// Error mapper.
+    mapper: M,
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl<F, M, R, E> Handler for FromFnWithError<F, M>
+where
+    F: Fn(Request) -> Result<R, E>,
+    M: Fn(&E) -> Response,
+    R: IntoResponse,
+{
+    /// Handles the given request.
+    ///
+    /// The request is passed to the function, and if it returns an error, the
+    /// error mapper is invoked to convert the error into a response.
+    fn handle(&self, req: Request) -> Response {
+        match (self.f)(req) {
+            Ok(res) => res.into_response(),
+            Err(err) => (self.mapper)(&err),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Creates a handler from a fallible function and an error mapper.
+///
+/// While functions returning a [`Result`] can be used as handlers directly,
+/// all errors are answered with "500 Internal Server Error". This function
+/// allows to map errors to responses, e.g., to answer with specific statuses.
+///
+/// # Examples
+///
+/// ```
+/// use zense::handler::{from_fn_with_error, Handler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Request, Response, Status};
+///
+/// // Define errors
+/// #[derive(Debug)]
+/// enum Error {
+///     NotFound,
+///     Database,
+/// }
+///
+/// // Create handler with error mapper
+/// let handler = from_fn_with_error(
+///     |req: Request| match req.uri.path.as_ref() {
+///         "/" => Ok(Response::new()),
+///         "/coffee" => Err(Error::NotFound),
+///         _ => Err(Error::Database),
+///     },
+///     |err: &Error| match err {
+///         Error::NotFound => Response::from_status(Status::NotFound),
+///         _ => Response::from_status(Status::InternalServerError),
+///     },
+/// );
+///
+/// // Handle request with handler
+/// let res = handler.handle(Request::new().uri("/coffee"));
+/// assert_eq!(res.status, Status::NotFound);
+///
+/// // Handle request with handler
+/// let res = handler.handle(Request::new().uri("/tea"));
+/// assert_eq!(res.status, Status::InternalServerError);
+/// ```
+pub fn from_fn_with_error<F, M, R, E>(f: F, mapper: M) -> FromFnWithError<F, M>
+where
+    F: Fn(Request) -> Result<R, E>,
+    M: Fn(&E) -> Response,
+    R: IntoResponse,
+{
+    FromFnWithError { f, mapper }
+}
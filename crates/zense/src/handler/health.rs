@@ -0,0 +1,204 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Handler for health checks.
+
+use std::fmt;
+
+use crate::handler::Handler;
+use crate::http::response::ResponseExt;
+use crate::http::{Request, Response, Status};
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Handler for health checks.
+///
+/// This handler answers liveness checks with "200 OK" and a configurable body,
+/// which defaults to `ok`, and is usually mounted at `/healthz`. Optionally,
+/// a readiness check can be given, which returns the status to respond with,
+/// e.g., "503 Service Unavailable" while a dependency isn't reachable, so load
+/// balancers can take the instance out of rotation. If the status returned by
+/// the readiness check doesn't indicate success, the body is omitted.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+/// use zense::handler::health::Health;
+/// use zense::handler::{Handler, TryIntoHandler};
+/// use zense::http::{Request, Status};
+/// use zense::router::{Params, Router};
+///
+/// // Create handler with default body
+/// let health = Health::new();
+/// let res = health.handle(Request::new());
+/// assert_eq!(res.status, Status::Ok);
+/// assert_eq!(res.body, b"ok");
+///
+/// // Create handler with readiness check, and mount it
+/// let ready = Arc::new(AtomicBool::new(false));
+/// let health = Health::new().readiness({
+///     let ready = Arc::clone(&ready);
+///     move || {
+///         if ready.load(Ordering::Relaxed) {
+///             Status::Ok
+///         } else {
+///             Status::ServiceUnavailable
+///         }
+///     }
+/// });
+/// let router = Router::default()
+///     .get("/healthz", move |req: Request, _: Params| health.handle(req))
+///     .try_into_handler()?;
+///
+/// // Handle request while not ready
+/// let res = router.handle(Request::new().uri("/healthz"));
+/// assert_eq!(res.status, Status::ServiceUnavailable);
+///
+/// // Handle request while ready
+/// ready.store(true, Ordering::Relaxed);
+/// let res = router.handle(Request::new().uri("/healthz"));
+/// assert_eq!(res.status, Status::Ok);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Health {
+    /// Response body.
+    body: String,
+    /// Readiness check.
+    readiness: Option<Box<dyn Fn() -> Status>>,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Health {
+    /// Creates a handler for health checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::handler::health::Health;
+    ///
+    /// // Create handler
+    /// let handler = Health::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the response body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::handler::health::Health;
+    ///
+    /// // Create handler and set response body
+    /// let handler = Health::new().body("healthy");
+    /// ```
+    #[must_use]
+    pub fn body<B>(mut self, body: B) -> Self
+    where
+        B: Into<String>,
+    {
+        self.body = body.into();
+        self
+    }
+
+    /// Sets the readiness check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::handler::health::Health;
+    /// use zense::http::Status;
+    ///
+    /// // Create handler and set readiness check
+    /// let handler = Health::new().readiness(|| Status::Ok);
+    /// ```
+    #[must_use]
+    pub fn readiness<F>(mut self, f: F) -> Self
+    where
+        F: Fn() -> Status + 'static,
+    {
+        self.readiness = Some(Box::new(f));
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Handler for Health {
+    /// Handles the given request.
+    ///
+    /// The readiness check, if any, is invoked for every request, so it should
+    /// be cheap, e.g., reading a flag that is updated in the background.
+    fn handle(&self, _req: Request) -> Response {
+        let status = self.readiness.as_ref().map_or(Status::Ok, |f| f());
+        if status.is_success() {
+            Response::text(self.body.as_str()).status(status)
+        } else {
+            Response::from_status(status)
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Default for Health {
+    /// Creates a handler for health checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::handler::health::Health;
+    ///
+    /// // Create handler
+    /// let handler = Health::default();
+    /// ```
+    fn default() -> Self {
+        Self {
+            body: String::from("ok"),
+            readiness: None,
+        }
+    }
+}
+
+impl fmt::Debug for Health {
+    /// Formats the handler for debugging.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Health")
+            .field("body", &self.body)
+            .field("readiness", &self.readiness.is_some())
+            .finish()
+    }
+}
@@ -22,12 +22,16 @@
 
 //! Matcher.
 
+use matchit::InsertError;
 use std::str::FromStr;
 
+mod constraint;
 mod error;
 mod params;
 mod route;
 
+use constraint::Constraint;
+
 pub use error::{Error, Result};
 pub use params::Params;
 pub use route::Route;
@@ -41,11 +45,114 @@ pub use route::Route;
 /// This is a thin wrapper around the [`Router`][] data type of the [`matchit`]
 /// crate to shield against unforeseen changes in the crate's implementation.
 ///
+/// Routes are stored in a radix tree, with parameters and catch-all parameters
+/// as special edges, so lookup cost depends on the length of the path, not the
+/// number of routes.
+///
+/// Routes might end with an optional parameter, e.g., `/posts/{id?}`, which
+/// matches `/posts` as well as `/posts/{id}`. As both paths are added to the
+/// radix tree, routes and data are stored separately, and referenced by index.
+///
+/// Parameters might be constrained, e.g., `/users/{id:int}` only matches when
+/// `id` is numeric. Supported constraints are `int`, `uuid`, and character
+/// classes like `{slug:[a-z-]+}`. A constrained parameter still occupies its
+/// position in the radix tree, so a route with the same shape, e.g.,
+/// `/users/{name}`, is added to another radix tree, which is only consulted
+/// when the constraint isn't satisfied. Radix trees are tried in the order in
+/// which routes were added, so constrained routes must be added first. Paths
+/// matching no route fall through, which means that a router passes the
+/// request on, eventually leading to a 404.
+///
 /// [`Router`]: matchit::Router
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Request, Response, Status};
+/// use zense::router::{Params, Router};
+///
+/// // Create router with optional and constrained parameters
+/// let router = Router::default()
+///     .get("/posts/{id?}", |_: Request, params: Params| {
+///         Response::text(params.get("id").unwrap_or("all"))
+///     })
+///     .get("/users/{id:int}", |_: Request, params: Params| {
+///         Response::text(params.get("id").unwrap_or_default())
+///     })
+///     .get("/users/me", |_: Request, _: Params| Response::text("me"))
+///     .get("/users/{name}/posts", |_: Request, params: Params| {
+///         Response::text(params.get("name").unwrap_or_default())
+///     })
+///     .try_into_handler()?;
+///
+/// // Handle requests with and without optional parameter
+/// let res = router.handle(Request::new().uri("/posts/7"));
+/// assert_eq!(res.body, b"7");
+/// let res = router.handle(Request::new().uri("/posts"));
+/// assert_eq!(res.body, b"all");
+///
+/// // Handle requests with constrained parameter
+/// let res = router.handle(Request::new().uri("/users/42"));
+/// assert_eq!(res.body, b"42");
+/// let res = router.handle(Request::new().uri("/users/me"));
+/// assert_eq!(res.body, b"me");
+/// let res = router.handle(Request::new().uri("/users/abc"));
+/// assert_eq!(res.status, Status::NotFound);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Routes of the same shape as a constrained route are tried, when the
+/// constraint isn't satisfied, so a numeric identifier and a name can be
+/// told apart without resorting to different paths:
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Request, Response};
+/// use zense::router::{Params, Router};
+///
+/// // Create router with constrained and unconstrained parameters
+/// let router = Router::default()
+///     .get("/users/{id:int}", |_: Request, params: Params| {
+///         Response::text(format!("id {}", params.get("id").unwrap_or("")))
+///     })
+///     .get("/users/{name}", |_: Request, params: Params| {
+///         Response::text(format!("name {}", params.get("name").unwrap_or("")))
+///     })
+///     .try_into_handler()?;
+///
+/// // Handle requests matching either route
+/// let res = router.handle(Request::new().uri("/users/42"));
+/// assert_eq!(res.body, b"id 42");
+/// let res = router.handle(Request::new().uri("/users/abc"));
+/// assert_eq!(res.body, b"name abc");
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Debug, Default)]
 pub struct Matcher<T = ()> {
-    /// Matcher implementation.
-    inner: matchit::Router<T>,
+    /// Matcher implementations, tried in order.
+    inner: Vec<matchit::Router<usize>>,
+    /// List of entries.
+    routes: Vec<Entry<T>>,
+}
+
+/// Matcher entry.
+#[derive(Debug)]
+struct Entry<T> {
+    /// Route template.
+    route: Route,
+    /// Parameter constraints.
+    constraints: Vec<(String, Constraint)>,
+    /// Associated data.
+    data: T,
 }
 
 /// Match.
@@ -72,15 +179,21 @@ impl<T> Matcher<T> {
     /// ```
     #[must_use]
     pub fn new() -> Self {
-        Self { inner: matchit::Router::new() }
+        Self {
+            inner: Vec::new(),
+            routes: Vec::new(),
+        }
     }
 
     /// Adds a route to the matcher.
     ///
     /// # Errors
     ///
-    /// This method returns [`Error::Insert`], if the route could not be added
-    /// to the matcher, including the reason for the failure.
+    /// This method returns [`Error::Conflict`], if the route would match the
+    /// same paths as a previously added route, naming both routes, unless the
+    /// previously added route has constrained parameters, and returns
+    /// [`Error::Insert`], if the route could not be added for another reason,
+    /// e.g., because an optional parameter is not at the end of the route.
     ///
     /// # Examples
     ///
@@ -88,24 +201,63 @@ impl<T> Matcher<T> {
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// use std::str::FromStr;
-    /// use zense::handler::matcher::Route;
+    /// use zense::handler::matcher::{self, Route};
     /// use zense::handler::Matcher;
     ///
     /// // Create matcher and add route
     /// let mut matcher = Matcher::new();
     /// matcher.add(Route::from_str("/coffee/{kind}")?, ())?;
+    ///
+    /// // Add conflicting route
+    /// let res = matcher.add(Route::from_str("/coffee/{name}")?, ());
+    /// assert!(matches!(res, Err(matcher::Error::Conflict { .. })));
+    /// assert_eq!(
+    ///     res.unwrap_err().to_string(),
+    ///     "route '/coffee/{name}' conflicts with route '/coffee/{kind}'"
+    /// );
     /// # Ok(())
     /// # }
     /// ```
-    #[allow(clippy::needless_pass_by_value)]
     pub fn add(&mut self, route: Route, value: T) -> Result {
-        self.inner
-            .insert(route.to_string(), value)
-            .map_err(Into::into)
+        let path = route.to_string();
+        let index = self.routes.len();
+
+        // Extract constraints, since the radix tree only knows parameters
+        let (normal, constraints) = constraints(&path)?;
+
+        // Add route without and with optional parameter, if any - optional
+        // parameters are only allowed at the end of the route
+        if let Some((base, name)) = optional(&normal) {
+            let base = if base.is_empty() { "/" } else { base };
+            let layer = self.insert(&path, base, index)?;
+
+            // Remove base path again if the full path can't be added, so we
+            // don't keep a path referencing a route that was never stored
+            let full = format!("{}/{{{name}}}", base.trim_end_matches('/'));
+            if let Err(err) = self.insert(&path, &full, index) {
+                self.inner[layer].remove(base);
+                return Err(err);
+            }
+        } else if normal.contains("?}") {
+            return Err(InsertError::InvalidParam.into());
+        } else {
+            self.insert(&path, &normal, index)?;
+        }
+
+        // Store route, constraints and associated data
+        self.routes.push(Entry {
+            route,
+            constraints,
+            data: value,
+        });
+        Ok(())
     }
 
     /// Attempts to resolve and match the given path.
     ///
+    /// If the parameters of a matched route don't satisfy its constraints,
+    /// the route of the same shape that was added next is tried, if any.
+    ///
     /// # Examples
     ///
     /// ```
@@ -125,12 +277,101 @@ impl<T> Matcher<T> {
     /// # Ok(())
     /// # }
     /// ```
+    #[must_use]
     pub fn resolve<'v>(&self, path: &'v str) -> Option<Match<'_, 'v, &T>> {
-        self.inner.at(path).ok().map(|route| Match {
-            data: route.value,
-            params: Params::new(route.params),
+        self.inner.iter().find_map(|inner| {
+            let route = inner.at(path).ok()?;
+            let entry = &self.routes[*route.value];
+            let params = Params::new(route.params, entry.route.as_str());
+
+            // Ensure all parameters satisfy their constraints, if any -
+            // optional parameters might be absent, which is fine
+            let mut iter = entry.constraints.iter();
+            iter.all(|(name, constraint)| {
+                params
+                    .get(name)
+                    .map_or(true, |value| constraint.matches(value))
+            })
+            .then_some(Match { params, data: &entry.data })
         })
     }
+
+    /// Attempts to find the data and parameters for the given path.
+    ///
+    /// This method is equivalent to [`Matcher::resolve`], but returns a tuple,
+    /// which is convenient for introspection, e.g., testing routes in isolation
+    /// without the need to create a request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::str::FromStr;
+    /// use zense::handler::matcher::Route;
+    /// use zense::handler::Matcher;
+    ///
+    /// // Create matcher and add route
+    /// let mut matcher = Matcher::new();
+    /// matcher.add(Route::from_str("/users/{id}")?, "user")?;
+    ///
+    /// // Find data and parameters for path
+    /// let (data, params) = matcher.find("/users/7").unwrap();
+    /// assert_eq!(data, &"user");
+    /// assert_eq!(params.get("id"), Some("7"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn find<'v>(&self, path: &'v str) -> Option<(&T, Params<'_, 'v>)> {
+        self.resolve(path)
+            .map(|Match { params, data }| (data, params))
+    }
+}
+
+impl<T> Matcher<T> {
+    /// Inserts the given path, referencing the route at the given index.
+    ///
+    /// If the path conflicts with a constrained route, it's inserted into the
+    /// next radix tree, so it's tried when the constraints aren't satisfied.
+    /// Returns the index of the radix tree the path was inserted into.
+    fn insert(
+        &mut self, route: &str, path: &str, index: usize,
+    ) -> Result<usize> {
+        let mut layer = 0;
+        loop {
+            if layer == self.inner.len() {
+                self.inner.push(matchit::Router::new());
+            }
+
+            // Continue with next radix tree if the conflicting route has
+            // constraints, as it might not match all paths this route matches
+            match self.inner[layer].insert(path, index) {
+                Ok(()) => return Ok(layer),
+                Err(InsertError::Conflict { with }) => {
+                    let entry = self.entry(layer, &with);
+                    if entry.is_some_and(|entry| !entry.constraints.is_empty())
+                    {
+                        layer += 1;
+                        continue;
+                    }
+                    return Err(Error::Conflict {
+                        route: route.to_string(),
+                        with: entry
+                            .map_or(with, |entry| entry.route.to_string()),
+                    });
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Returns the entry of the route that the given path belongs to.
+    fn entry(&self, layer: usize, path: &str) -> Option<&Entry<T>> {
+        let route = self.inner[layer].at(path).ok()?;
+        Some(&self.routes[*route.value])
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -168,3 +409,57 @@ impl FromStr for Matcher {
             .map(|()| matcher)
     }
 }
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Splits a route ending with an optional parameter into base and name.
+fn optional(path: &str) -> Option<(&str, &str)> {
+    let (base, last) = path.rsplit_once('/')?;
+    let name = last.strip_prefix('{')?.strip_suffix("?}")?;
+    Some((base, name))
+}
+
+/// Extracts all parameter constraints from a route.
+///
+/// Returns the route without constraints, and the list of parameter names and
+/// constraints, which are checked after the route was matched.
+fn constraints(path: &str) -> Result<(String, Vec<(String, Constraint)>)> {
+    let mut normal = String::with_capacity(path.len());
+    let mut constraints = Vec::new();
+
+    // Copy route, omitting the constraints of all parameters
+    let mut rest = path;
+    while let Some((head, tail)) = rest.split_once('{') {
+        let Some((param, tail)) = tail.split_once('}') else {
+            break;
+        };
+
+        // Split off optional marker and constraint, if any
+        let (param, marker) = match param.strip_suffix('?') {
+            Some(param) => (param, "?"),
+            None => (param, ""),
+        };
+        let name = match param.split_once(':') {
+            Some((name, constraint)) => {
+                let key = name.trim_start_matches('*').to_string();
+                constraints.push((key, constraint.parse()?));
+                name
+            }
+            None => param,
+        };
+
+        // Append parameter without constraint
+        normal.push_str(head);
+        normal.push('{');
+        normal.push_str(name);
+        normal.push_str(marker);
+        normal.push('}');
+        rest = tail;
+    }
+
+    // Append remainder and return route and constraints
+    normal.push_str(rest);
+    Ok((normal, constraints))
+}
@@ -0,0 +1,117 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Matcher parameter constraint.
+
+use std::str::FromStr;
+
+use super::error::{Error, Result};
+
+// ----------------------------------------------------------------------------
+// Enums
+// ----------------------------------------------------------------------------
+
+/// Matcher parameter constraint.
+///
+/// Constraints are given after the name of a parameter, separated by `:`, and
+/// restrict the values a parameter matches. Besides the named constraints, a
+/// character class like `[a-z0-9-]+` is supported, which is a small subset of
+/// regular expressions, in order to avoid depending on a regex engine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    /// Decimal integer, e.g., `42`.
+    Int,
+    /// UUID in hyphenated form, e.g., `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+    Uuid,
+    /// One or more characters of the given ranges.
+    Class(Vec<(char, char)>),
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Constraint {
+    /// Returns whether the given value satisfies the constraint.
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            Constraint::Int => {
+                !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())
+            }
+            Constraint::Uuid => {
+                value.len() == 36
+                    && value.bytes().enumerate().all(|(i, b)| match i {
+                        8 | 13 | 18 | 23 => b == b'-',
+                        _ => b.is_ascii_hexdigit(),
+                    })
+            }
+            Constraint::Class(ranges) => {
+                !value.is_empty()
+                    && value.chars().all(|c| {
+                        ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&c))
+                    })
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl FromStr for Constraint {
+    type Err = Error;
+
+    /// Attempts to create a constraint from a string.
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "int" => return Ok(Constraint::Int),
+            "uuid" => return Ok(Constraint::Uuid),
+            _ => {}
+        }
+
+        // Parse character class, where a `-` between two characters denotes
+        // a range, and is treated as a verbatim character otherwise
+        let invalid = || Error::Constraint(value.to_string());
+        let class = value.strip_prefix('[').and_then(|v| v.strip_suffix("]+"));
+        let chars = class.ok_or_else(invalid)?.chars().collect::<Vec<_>>();
+        let mut ranges = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            if i + 2 < chars.len() && chars[i + 1] == '-' {
+                ranges.push((chars[i], chars[i + 2]));
+                i += 3;
+            } else {
+                ranges.push((chars[i], chars[i]));
+                i += 1;
+            }
+        }
+
+        // Ensure character class is not empty and ranges are ordered
+        if ranges.is_empty() || ranges.iter().any(|(lo, hi)| lo > hi) {
+            return Err(invalid());
+        }
+
+        // Return character class
+        Ok(Constraint::Class(ranges))
+    }
+}
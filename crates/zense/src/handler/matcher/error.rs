@@ -41,6 +41,19 @@ pub enum Error {
     /// Route insert error.
     #[error(transparent)]
     Insert(#[from] matchit::InsertError),
+
+    /// Route constraint error.
+    #[error("invalid route constraint: {0}")]
+    Constraint(String),
+
+    /// Route conflict error.
+    #[error("route '{route}' conflicts with route '{with}'")]
+    Conflict {
+        /// Route that was added.
+        route: String,
+        /// Route that was previously added.
+        with: String,
+    },
 }
 
 // ----------------------------------------------------------------------------
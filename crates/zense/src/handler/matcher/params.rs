@@ -44,6 +44,8 @@ use matchit::ParamsIter;
 pub struct Params<'k, 'v> {
     /// Parameter list implementation.
     inner: matchit::Params<'k, 'v>,
+    /// Route template.
+    route: &'k str,
 }
 
 // ----------------------------------------------------------------------------
@@ -54,12 +56,13 @@ impl<'k, 'v> Params<'k, 'v> {
     /// Creates matcher parameters.
     ///
     /// This method is used by the [`Matcher`][] to create matcher parameters
-    /// from the [`matchit::Params`] as returned by [`matchit`].
+    /// from the [`matchit::Params`] as returned by [`matchit`], together with
+    /// the template of the route that was matched.
     ///
     /// [`Matcher`]: crate::handler::Matcher
     #[inline]
-    pub(crate) fn new(inner: matchit::Params<'k, 'v>) -> Self {
-        Params { inner }
+    pub(crate) fn new(inner: matchit::Params<'k, 'v>, route: &'k str) -> Self {
+        Params { inner, route }
     }
 }
 
@@ -117,6 +120,40 @@ impl<'k, 'v> Params<'k, 'v> {
         self.inner.get(key).is_some()
     }
 
+    /// Returns the template of the matched route.
+    ///
+    /// The template is the full route, including the base path of all routers
+    /// it's nested in, which makes it ideal for labeling requests with a low
+    /// cardinality, e.g., for logging or metrics, as it's not the actual path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::{Handler, TryIntoHandler};
+    /// use zense::http::{Request, Response};
+    /// use zense::router::{Router, Params};
+    ///
+    /// // Create router and add route
+    /// let router = Router::new("/users")
+    ///     .get("/{id}", |req: Request, params: Params| {
+    ///         Response::new().body(params.route_template())
+    ///     })
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request with router
+    /// let res = router.handle(Request::new().uri("/users/42"));
+    /// assert_eq!(res.body, b"/users/{id}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn route_template(&self) -> &'k str {
+        self.route
+    }
+
     /// Returns an iterator over all parameters.
     ///
     /// # Examples
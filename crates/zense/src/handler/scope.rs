@@ -22,7 +22,12 @@
 
 //! Scope.
 
-use super::matcher::Route;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::http::Method;
+
+use super::matcher::{Matcher, Route};
 
 // ----------------------------------------------------------------------------
 // Structs
@@ -34,6 +39,8 @@ pub struct Scope {
     // Base path for routes.
     // pub path: Option<String>,
     pub route: Option<Route>,
+    /// Map methods to matchers for all routes of the enclosing router.
+    pub(crate) routes: Arc<BTreeMap<Method, Matcher>>,
 }
 
 // ----------------------------------------------------------------------------
@@ -53,7 +60,41 @@ impl Scope {
     /// ```
     #[must_use]
     pub fn new() -> Self {
-        Self { route: None }
+        Self {
+            route: None,
+            routes: Arc::default(),
+        }
+    }
+
+    /// Returns the route template of the scope, if any.
+    ///
+    /// Scopes are passed to [`TryIntoMiddleware`][], which allows middlewares
+    /// to capture the route template of the router they're added to, and use
+    /// it at request-handling time. Handlers for routes can obtain the route
+    /// template of the matched route via [`Params::route_template`][].
+    ///
+    /// [`Params::route_template`]: crate::router::Params::route_template
+    /// [`TryIntoMiddleware`]: crate::middleware::TryIntoMiddleware
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::str::FromStr;
+    /// use zense::handler::matcher::Route;
+    /// use zense::handler::Scope;
+    ///
+    /// // Create scope from route
+    /// let scope = Scope::from(Route::from_str("/users/{id}")?);
+    /// assert_eq!(scope.route_template(), Some("/users/{id}"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn route_template(&self) -> Option<&str> {
+        self.route.as_ref().map(Route::as_str)
     }
 
     /// Joins the scope with another scope.
@@ -73,8 +114,11 @@ impl Scope {
             (None, None) => None,
         };
 
-        // Return scope
-        Scope { route }
+        // Return scope, retaining the routes of the enclosing router
+        Scope {
+            route,
+            routes: Arc::clone(&self.routes),
+        }
     }
 }
 
@@ -101,6 +145,9 @@ impl From<Route> for Scope {
     /// # }
     /// ```
     fn from(route: Route) -> Self {
-        Scope { route: Some(route) }
+        Scope {
+            route: Some(route),
+            routes: Arc::default(),
+        }
     }
 }
@@ -22,11 +22,13 @@
 
 //! Stack.
 
+use std::{fmt, mem};
+
 use crate::handler::{Handler, NotFound};
 use crate::http::{Request, Response};
 use crate::middleware::Middleware;
 
-use super::matcher::Matcher;
+use super::matcher::{Match, Matcher, Route};
 
 mod builder;
 mod factory;
@@ -87,7 +89,7 @@ pub(crate) use builder::Builder;
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct Stack {
     /// Middlewares.
     middlewares: Vec<Box<dyn Middleware>>,
@@ -100,6 +102,10 @@ pub struct Stack {
     ///
     /// [`Router`]: crate::router::Router
     matcher: Option<Matcher>,
+    /// Base route, if the stack is part of a router.
+    route: Option<Route>,
+    /// Whether only the first matching middleware is invoked.
+    first_match: bool,
 }
 
 /// Stack handler.
@@ -113,6 +119,8 @@ struct StackHandler<'a> {
     middlewares: &'a [Box<dyn Middleware>],
     /// Next handler.
     next: &'a dyn Handler,
+    /// Mount point to restore before invoking the next handler, if changed.
+    mount: Option<usize>,
 }
 
 // ----------------------------------------------------------------------------
@@ -139,6 +147,49 @@ impl Stack {
         // implementors that convert into stacks.
         Builder::new()
     }
+
+    /// Creates a stack that only invokes the first matching middleware.
+    ///
+    /// Instead of passing the request from one middleware to the next, the
+    /// middlewares are checked in order via [`Middleware::matches`], and the
+    /// first one that matches handles the request exclusively. If it defers,
+    /// the request is passed to the next handler after the stack, skipping the
+    /// remaining middlewares. This is useful for mounting multiple scoped apps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::{Handler, Stack, TryIntoHandler};
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Request, Response, Status};
+    /// use zense::router::Router;
+    ///
+    /// // Create stack with scoped apps
+    /// let stack = Stack::first_match()
+    ///     .with(Router::new("/api").with(|req: Request, next: &dyn Handler| {
+    ///         next.handle(req)
+    ///     }))
+    ///     .with(Router::new("/").with(|_: Request, _: &dyn Handler| {
+    ///         Response::text("app")
+    ///     }))
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request with first app, which defers
+    /// let res = stack.handle(Request::new().uri("/api/users"));
+    /// assert_eq!(res.status, Status::NotFound);
+    ///
+    /// // Handle request with second app
+    /// let res = stack.handle(Request::new().uri("/about"));
+    /// assert_eq!(res.body, b"app");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn first_match() -> Builder {
+        Builder::new().first_match()
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -156,9 +207,12 @@ impl Middleware for Stack {
     /// In case the stack is used as part of a [`Router`][], prior to invoking
     /// the first middleware, we check if the router's base path matches the
     /// request path as a prefix. If it doesn't, the request is passed to the
-    /// next handler immediately.
+    /// next handler immediately. The matcher is built exactly once, when the
+    /// stack is created via [`TryIntoMiddleware`][], so matching doesn't
+    /// allocate, which keeps the hot path of request processing lean.
     ///
     /// [`Router`]: crate::router::Router
+    /// [`TryIntoMiddleware`]: crate::middleware::TryIntoMiddleware
     ///
     /// # Examples
     ///
@@ -194,25 +248,61 @@ impl Middleware for Stack {
     /// # Ok(())
     /// # }
     /// ```
-    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+    fn process(&self, mut req: Request, next: &dyn Handler) -> Response {
+        let mut mount = None;
         if let Some(matcher) = &self.matcher {
             let path = req.uri.path.trim_end_matches('/');
 
-            // Forward to next handler if path doesn't match
-            if matcher.resolve(path).is_none() {
-                return next.handle(req);
-            }
+            // Forward to next handler if path doesn't match, or record the
+            // matched prefix, so that handlers can obtain the path relative to
+            // the base path - note that the wildcard excludes the slash
+            let prefix = match matcher.resolve(path) {
+                Some(Match { params, .. }) => params
+                    .get("rest")
+                    .map_or(path, |rest| &path[..path.len() - rest.len() - 1]),
+                None => return next.handle(req),
+            };
+            mount = Some(mem::replace(&mut req.mount, prefix.len()));
+        }
+
+        // Only invoke the first matching middleware, if configured
+        if self.first_match {
+            let next = StackHandler { middlewares: &[], next, mount };
+            let mut iter = self.middlewares.iter();
+            return match iter.find(|middleware| middleware.matches(&req)) {
+                Some(middleware) => middleware.process(req, &next),
+                None => next.handle(req),
+            };
         }
 
         // Create stack handler
         let handler = StackHandler {
             middlewares: &self.middlewares,
             next,
+            mount,
         };
 
         // Handle request
         handler.handle(req)
     }
+
+    /// Returns whether the stack applies to the given request.
+    ///
+    /// If the stack is part of a router, the router's base path must match
+    /// the request path as a prefix. Otherwise, the stack applies if any of
+    /// its middlewares applies to the request.
+    fn matches(&self, req: &Request) -> bool {
+        match &self.matcher {
+            Some(matcher) => {
+                let path = req.uri.path.trim_end_matches('/');
+                matcher.resolve(path).is_some()
+            }
+            None => self
+                .middlewares
+                .iter()
+                .any(|middleware| middleware.matches(req)),
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -220,13 +310,16 @@ impl Middleware for Stack {
 impl Handler for Stack {
     /// Handles the given request, passing it through the entire stack.
     ///
+    /// As the stack is the entry point for request processing, the version of
+    /// the response is set to the version of the request.
+    ///
     /// # Examples
     ///
     /// ```
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// use zense::handler::{Handler, Stack, TryIntoHandler};
-    /// use zense::http::{Method, Request, Response, Status};
+    /// use zense::http::{Method, Request, Response, Status, Version};
     ///
     /// // Create stack with middleware
     /// let stack = Stack::new()
@@ -247,12 +340,19 @@ impl Handler for Stack {
     /// // Handle request with stack
     /// let res = stack.handle(req);
     /// assert_eq!(res.status, Status::ImATeapot);
+    ///
+    /// // Handle HTTP/1.0 request with stack
+    /// let res = stack.handle(Request::new().version(Version::Http10));
+    /// assert_eq!(res.version, Version::Http10);
     /// # Ok(())
     /// # }
     /// ```
-    #[inline]
     fn handle(&self, req: Request) -> Response {
-        self.process(req, &NotFound)
+        let version = req.version;
+
+        // Ensure the response version matches the request version
+        let res = self.process(req, &NotFound);
+        res.version(version)
     }
 }
 
@@ -262,12 +362,24 @@ impl Handler for StackHandler<'_> {
     /// This method is called by the stack to process the request. It checks
     /// if there are any middlewares left, and if so, it removes the first one,
     /// creates a new stack handler with the remaining middlewares, and invokes
-    /// it. If no middlewares are left, the next handler is invoked.
+    /// it. If no middlewares are left, the mount point of the request is reset
+    /// to the value it had when entering the stack, and the next handler is
+    /// invoked, so the request leaves the stack as it was passed in.
     fn handle(&self, req: Request) -> Response {
         match self.middlewares {
-            [] => self.next.handle(req),
+            [] => {
+                let mut req = req;
+                if let Some(mount) = self.mount {
+                    req.mount = mount;
+                }
+                self.next.handle(req)
+            }
             [middleware, middlewares @ ..] => {
-                let next = StackHandler { middlewares, next: self.next };
+                let next = StackHandler {
+                    middlewares,
+                    next: self.next,
+                    mount: self.mount,
+                };
                 middleware.process(req, &next)
             }
         }
@@ -276,6 +388,52 @@ impl Handler for StackHandler<'_> {
 
 // ----------------------------------------------------------------------------
 
+impl fmt::Debug for Stack {
+    /// Formats the stack for debugging.
+    ///
+    /// Middlewares are type-erased, so instead of listing them, we report the
+    /// number of middlewares and the base route the stack is bound to, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::str::FromStr;
+    /// use zense::handler::matcher::Route;
+    /// use zense::handler::{Handler, Scope, Stack};
+    /// use zense::http::Request;
+    /// use zense::middleware::TryIntoMiddleware;
+    ///
+    /// // Create scope from route
+    /// let scope = Scope::from(Route::from_str("/users")?);
+    ///
+    /// // Create stack with middlewares
+    /// let stack = Stack::new()
+    ///     .with(|req: Request, next: &dyn Handler| next.handle(req))
+    ///     .with(|req: Request, next: &dyn Handler| next.handle(req))
+    ///     .try_into_middleware(&scope)?;
+    ///
+    /// // Obtain debug representation
+    /// assert_eq!(
+    ///     format!("{stack:?}"),
+    ///     concat!(
+    ///         r#"Stack { middlewares: 2, route: Some("/users"), "#,
+    ///         "first_match: false, .. }"
+    ///     )
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stack")
+            .field("middlewares", &self.middlewares.len())
+            .field("route", &self.route.as_ref().map(Route::as_str))
+            .field("first_match", &self.first_match)
+            .finish_non_exhaustive()
+    }
+}
+
 impl FromIterator<Box<dyn Middleware>> for Stack {
     /// Creates a stack from an iterator.
     ///
@@ -288,6 +446,8 @@ impl FromIterator<Box<dyn Middleware>> for Stack {
         Self {
             middlewares: Vec::from_iter(iter),
             matcher: None,
+            route: None,
+            first_match: false,
         }
     }
 }
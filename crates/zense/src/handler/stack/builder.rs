@@ -22,6 +22,7 @@
 
 //! Stack builder.
 
+use std::fmt;
 use std::str::FromStr;
 
 use crate::handler::matcher::{Matcher, Route};
@@ -36,10 +37,11 @@ use super::Stack;
 // ----------------------------------------------------------------------------
 
 /// Stack builder.
-#[derive(Debug)]
 pub struct Builder {
     /// Middleware factories.
     middlewares: Vec<Box<dyn Factory>>,
+    /// Whether only the first matching middleware is invoked.
+    first_match: bool,
 }
 
 // ----------------------------------------------------------------------------
@@ -49,7 +51,19 @@ pub struct Builder {
 impl Builder {
     /// Creates a stack builder.
     pub(crate) fn new() -> Self {
-        Self { middlewares: Vec::new() }
+        Self {
+            middlewares: Vec::new(),
+            first_match: false,
+        }
+    }
+
+    /// Configures the stack to only invoke the first matching middleware.
+    ///
+    /// Note that [`Stack::first_match`] is the canonical way to create such
+    /// stacks. This method is solely used internally.
+    pub(crate) fn first_match(mut self) -> Self {
+        self.first_match = true;
+        self
     }
 
     /// Adds a middleware to the stack.
@@ -106,6 +120,56 @@ impl Builder {
         self.push(middleware);
         self
     }
+
+    /// Adds a named middleware to the stack.
+    ///
+    /// This method is equivalent to [`Builder::with`], but tags the middleware
+    /// with the given name, so errors that occur during conversion are wrapped
+    /// in [`Error::Middleware`], which allows to identify the culprit. The
+    /// conversion happens when the stack is converted into a handler, so the
+    /// errors returned by [`TryIntoMiddleware`] are wrapped with the name then.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::handler::{Stack, TryIntoHandler};
+    /// use zense::http::{Request, Response};
+    /// use zense::router::{Params, Router};
+    ///
+    /// // Create router with conflicting routes
+    /// let router = Router::default()
+    ///     .get("/{id}", |_: Request, _: Params| Response::new())
+    ///     .get("/{name}", |_: Request, _: Params| Response::new());
+    ///
+    /// // Create stack with named middleware
+    /// let err = Stack::new()
+    ///     .with_named("users", router)
+    ///     .try_into_handler()
+    ///     .unwrap_err();
+    ///
+    /// // Ensure error contains name
+    /// assert!(err.to_string().starts_with("middleware 'users': "));
+    /// ```
+    #[must_use]
+    pub fn with_named<N, M>(mut self, name: N, middleware: M) -> Self
+    where
+        N: Into<String>,
+        M: TryIntoMiddleware,
+    {
+        let name = name.into();
+        self.middlewares.push(Box::new(move |scope: &Scope| {
+            middleware
+                .try_into_middleware(scope)
+                .map(|middleware| Box::new(middleware) as Box<dyn Middleware>)
+                .map_err(|err| Error::Middleware {
+                    name,
+                    source: Box::new(err),
+                })
+        }));
+
+        // Return self for chaining
+        self
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -152,15 +216,22 @@ impl TryIntoMiddleware for Builder {
         let route = scope.route.as_ref();
 
         // If the stack is part of a router, we create a matcher that checks if
-        // the router's base path matches the request path as a prefix
+        // the router's base path matches the request path as a prefix. Stacks
+        // scoped to the root path match all requests, so they need no matcher.
         let matcher = route
+            .filter(|base| base.as_str() != "/")
             .map(|base| -> Result<_> {
                 let mut matcher = Matcher::new();
                 let rest = Route::from_str("/{*rest}")
                     .map_err(|err| Error::Matcher(err.into()))?;
 
                 // Middlewares do not receive path parameters, which is why we
-                // just use a wildcard to implement prefix matching on paths
+                // just use a wildcard to implement prefix matching on paths.
+                // Since wildcards don't match empty segments, the base path is
+                // added as well. Base paths of nested routers are composed by
+                // joining scopes, so adding fails for malformed combinations,
+                // e.g., when the base path already ends with a wildcard.
+                matcher.add(base.clone(), ())?;
                 matcher
                     .add(base.append(rest), ())
                     .map_err(Into::into)
@@ -170,8 +241,12 @@ impl TryIntoMiddleware for Builder {
 
         // Create and collect middlewares into a stack
         let iter = self.middlewares.into_iter().map(|f| f(scope));
-        iter.collect::<Result<_>>()
-            .map(|middlewares| Stack { middlewares, matcher })
+        iter.collect::<Result<_>>().map(|middlewares| Stack {
+            middlewares,
+            matcher,
+            route: route.cloned(),
+            first_match: self.first_match,
+        })
     }
 }
 
@@ -215,3 +290,32 @@ impl TryIntoHandler for Builder {
         self.try_into_middleware(&scope)
     }
 }
+
+// ----------------------------------------------------------------------------
+
+impl fmt::Debug for Builder {
+    /// Formats the stack builder for debugging.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::handler::{Handler, Stack};
+    /// use zense::http::Request;
+    ///
+    /// // Create stack with middleware
+    /// let stack = Stack::new()
+    ///     .with(|req: Request, next: &dyn Handler| next.handle(req));
+    ///
+    /// // Obtain debug representation
+    /// assert_eq!(
+    ///     format!("{stack:?}"),
+    ///     "Builder { middlewares: 1, first_match: false }"
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("middlewares", &self.middlewares.len())
+            .field("first_match", &self.first_match)
+            .finish()
+    }
+}
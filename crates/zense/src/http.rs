@@ -22,10 +22,17 @@
 
 //! HTTP protocol.
 
+pub mod accept;
+pub mod cache_control;
 pub mod component;
+pub mod encoding_negotiation;
+pub mod forwarded;
+#[cfg(feature = "serde")]
+pub mod problem;
 pub mod request;
 pub mod response;
+pub mod upgrade;
 
-pub use component::{Header, Method, Status};
+pub use component::{Header, Method, Status, Version};
 pub use request::{Query, Request, Uri};
 pub use response::Response;
@@ -0,0 +1,234 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! HTTP content negotiation.
+
+use std::cmp::Reverse;
+use std::fmt;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// HTTP media range.
+///
+/// Media ranges are the items of the [`Header::Accept`][] header, consisting
+/// of a type and subtype, both of which might be the wildcard `*`, as well as
+/// a quality value, which is stored in thousandths, as the specification only
+/// allows for up to three decimal places. This allows for exact comparison.
+///
+/// [`Header::Accept`]: crate::http::Header::Accept
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MediaRange<'a> {
+    /// Media type.
+    pub kind: &'a str,
+    /// Media subtype.
+    pub subtype: &'a str,
+    /// Quality value in thousandths.
+    pub quality: u16,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl MediaRange<'_> {
+    /// Returns whether the media range matches the given media type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::accept::parse;
+    ///
+    /// // Parse media ranges
+    /// let ranges = parse("text/*");
+    /// assert!(ranges[0].matches("text/html"));
+    /// assert!(!ranges[0].matches("application/json"));
+    /// ```
+    #[must_use]
+    pub fn matches(&self, media: &str) -> bool {
+        let Some((kind, subtype)) = media.split_once('/') else {
+            return false;
+        };
+
+        // Wildcards match anything on their level
+        (self.kind == "*" || self.kind.eq_ignore_ascii_case(kind.trim()))
+            && (self.subtype == "*"
+                || self.subtype.eq_ignore_ascii_case(subtype.trim()))
+    }
+
+    /// Returns the specificity of the media range.
+    ///
+    /// More specific media ranges take precedence over less specific ones, so
+    /// `text/html` overrides `text/*`, which in turn overrides `*/*`.
+    #[must_use]
+    pub fn specificity(&self) -> u8 {
+        match (self.kind, self.subtype) {
+            ("*", _) => 0,
+            (_, "*") => 1,
+            _ => 2,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl fmt::Display for MediaRange<'_> {
+    /// Formats the media range for display.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.kind, self.subtype)?;
+        if self.quality < 1000 {
+            let value = format!("{:.3}", f32::from(self.quality) / 1000.0);
+            let value = value.trim_end_matches('0').trim_end_matches('.');
+            write!(f, ";q={value}")?;
+        }
+
+        // No errors occurred
+        Ok(())
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Parses the value of an [`Header::Accept`][] header.
+///
+/// The returned media ranges are ordered by quality value, and then by their
+/// specificity, while media ranges that are equal in both keep their original
+/// order. Media ranges without a quality value default to `q=1`, and invalid
+/// media ranges are skipped, since there's nothing meaningful to negotiate.
+///
+/// [`Header::Accept`]: crate::http::Header::Accept
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::accept::parse;
+///
+/// // Parse media ranges
+/// let ranges = parse("text/*;q=0.5, text/html;q=0.9, application/json");
+/// assert_eq!(ranges[0].to_string(), "application/json");
+/// assert_eq!(ranges[1].to_string(), "text/html;q=0.9");
+/// assert_eq!(ranges[2].to_string(), "text/*;q=0.5");
+/// ```
+#[must_use]
+pub fn parse(value: &str) -> Vec<MediaRange<'_>> {
+    let mut ranges =
+        value.split(',').filter_map(parse_range).collect::<Vec<_>>();
+
+    // Order by quality value first, then by specificity - the sort is stable,
+    // so equal media ranges retain the order in which they were given
+    ranges.sort_by_key(|range| Reverse((range.quality, range.specificity())));
+    ranges
+}
+
+/// Returns the best match among the given media types.
+///
+/// For each of the available media types, the most specific matching media
+/// range determines its quality. The media type with the highest quality is
+/// returned, while ties are resolved in favor of the order of the available
+/// media types, i.e., the server's preference. Media types with a quality of
+/// zero are explicitly marked as unacceptable, and never returned.
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::accept::{best_match, parse};
+///
+/// // Parse media ranges
+/// let ranges = parse("text/html;q=0.9, */*;q=0.1, image/png;q=0");
+///
+/// // Obtain best match
+/// let media = best_match(&ranges, &["application/json", "text/html"]);
+/// assert_eq!(media, Some("text/html"));
+///
+/// // Obtain best match, excluding unacceptable types
+/// let media = best_match(&ranges, &["image/png"]);
+/// assert_eq!(media, None);
+/// ```
+#[must_use]
+pub fn best_match<'m>(
+    ranges: &[MediaRange], available: &[&'m str],
+) -> Option<&'m str> {
+    let mut best: Option<(&'m str, u16)> = None;
+    for &media in available {
+        let quality = ranges
+            .iter()
+            .filter(|range| range.matches(media))
+            .max_by_key(|range| range.specificity())
+            .map_or(0, |range| range.quality);
+
+        // Only replace the current best match if the quality is higher
+        if quality > 0 && best.map_or(true, |(_, q)| quality > q) {
+            best = Some((media, quality));
+        }
+    }
+
+    // Return best match
+    best.map(|(media, _)| media)
+}
+
+// ----------------------------------------------------------------------------
+
+/// Parses a media range.
+fn parse_range(value: &str) -> Option<MediaRange<'_>> {
+    let mut iter = value.split(';');
+    let (kind, subtype) = iter.next()?.trim().split_once('/')?;
+
+    // Extract quality value, ignoring all other parameters
+    let mut quality = 1000;
+    for param in iter {
+        if let Some((key, value)) = param.split_once('=') {
+            if key.trim().eq_ignore_ascii_case("q") {
+                quality = parse_quality(value.trim())?;
+            }
+        }
+    }
+
+    // Ensure neither type nor subtype are empty
+    let kind = kind.trim();
+    let subtype = subtype.trim();
+    (!kind.is_empty() && !subtype.is_empty()).then_some(MediaRange {
+        kind,
+        subtype,
+        quality,
+    })
+}
+
+/// Parses a quality value into thousandths.
+pub(crate) fn parse_quality(value: &str) -> Option<u16> {
+    let (int, frac) = value.split_once('.').unwrap_or((value, ""));
+    if frac.len() > 3 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    // Compute quality value from integer and fractional part
+    let frac = format!("{frac:0<3}").parse::<u16>().ok()?;
+    match int {
+        "0" => Some(frac),
+        "1" if frac == 0 => Some(1000),
+        _ => None,
+    }
+}
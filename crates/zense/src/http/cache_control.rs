@@ -0,0 +1,99 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! HTTP cache directives.
+
+use std::time::Duration;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// HTTP cache directives.
+///
+/// Cache directives are the items of the [`Header::CacheControl`][] header,
+/// which can be sent with requests and responses alike. Only the directives
+/// that are relevant for caching in memory are retained, while all others are
+/// ignored, as they're not meaningful to us.
+///
+/// [`Header::CacheControl`]: crate::http::Header::CacheControl
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CacheControl {
+    /// Whether the cache must revalidate before reuse.
+    pub no_cache: bool,
+    /// Whether the cache must not store anything.
+    pub no_store: bool,
+    /// Whether the response is intended for a single user.
+    pub private: bool,
+    /// Maximum age of the response.
+    pub max_age: Option<Duration>,
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Parses the value of a [`Header::CacheControl`][] header.
+///
+/// Directive names are matched case-insensitively, and unknown directives are
+/// skipped. Directives that carry invalid arguments, e.g., a `max-age` that is
+/// not a number, are skipped as well, as there's nothing meaningful to apply.
+///
+/// [`Header::CacheControl`]: crate::http::Header::CacheControl
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use zense::http::cache_control::parse;
+///
+/// // Parse cache directives
+/// let directives = parse("public, max-age=60, no-cache");
+/// assert!(directives.no_cache);
+/// assert!(!directives.no_store);
+/// assert_eq!(directives.max_age, Some(Duration::from_secs(60)));
+/// ```
+#[must_use]
+pub fn parse(value: &str) -> CacheControl {
+    let mut directives = CacheControl::default();
+    for directive in value.split(',') {
+        let (name, arg) = directive.split_once('=').unwrap_or((directive, ""));
+        let name = name.trim();
+
+        // Match directive, ignoring unknown ones
+        if name.eq_ignore_ascii_case("no-cache") {
+            directives.no_cache = true;
+        } else if name.eq_ignore_ascii_case("no-store") {
+            directives.no_store = true;
+        } else if name.eq_ignore_ascii_case("private") {
+            directives.private = true;
+        } else if name.eq_ignore_ascii_case("max-age") {
+            let arg = arg.trim().trim_matches('"');
+            if let Ok(secs) = arg.parse() {
+                directives.max_age = Some(Duration::from_secs(secs));
+            }
+        }
+    }
+
+    // Return cache directives
+    directives
+}
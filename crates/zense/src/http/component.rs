@@ -26,8 +26,11 @@ mod error;
 mod header;
 mod method;
 mod status;
+mod version;
 
 pub use error::{Error, Result};
+pub(crate) use header::sanitize;
 pub use header::Header;
 pub use method::Method;
 pub use status::Status;
+pub use version::Version;
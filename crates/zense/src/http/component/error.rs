@@ -39,6 +39,10 @@ pub enum Error {
     /// Invalid header.
     #[error("invalid header: {0}")]
     Header(String),
+
+    /// Invalid version.
+    #[error("invalid version: {0}")]
+    Version(String),
 }
 
 // ----------------------------------------------------------------------------
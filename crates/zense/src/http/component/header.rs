@@ -22,6 +22,7 @@
 
 //! HTTP header.
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
@@ -29,6 +30,37 @@ use std::sync::LazyLock;
 
 use super::error::{Error, Result};
 
+// ----------------------------------------------------------------------------
+// Constants
+// ----------------------------------------------------------------------------
+
+/// Maximum length of known header names.
+const HEADER_MAX_LENGTH: usize = 64;
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Header {
+    /// Headers carrying credentials, which are redacted in logs by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Header;
+    ///
+    /// // Check whether header is sensitive
+    /// assert!(Header::SENSITIVE.contains(&Header::Authorization));
+    /// assert!(!Header::SENSITIVE.contains(&Header::ContentType));
+    /// ```
+    pub const SENSITIVE: [Header; 4] = [
+        Header::Authorization,
+        Header::Cookie,
+        Header::ProxyAuthorization,
+        Header::SetCookie,
+    ];
+}
+
 // ----------------------------------------------------------------------------
 // Trait implementations
 // ----------------------------------------------------------------------------
@@ -45,11 +77,59 @@ impl AsRef<str> for Header {
 
 impl fmt::Display for Header {
     /// Formats the header for display.
+    ///
+    /// Headers are always displayed with their canonical casing, regardless
+    /// of the casing they were parsed from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::Header;
+    ///
+    /// // Parse headers from lowercase names, and display them
+    /// for name in [
+    ///     "X-Request-Id",
+    ///     "X-Forwarded-Proto",
+    ///     "Strict-Transport-Security",
+    ///     "Content-Security-Policy",
+    ///     "Cache-Control",
+    ///     "ETag",
+    ///     "If-None-Match",
+    ///     "Range",
+    ///     "Content-Range",
+    ///     "Accept-Ranges",
+    /// ] {
+    ///     let header: Header = name.to_ascii_lowercase().parse()?;
+    ///     assert_eq!(header.to_string(), name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(self.name())
     }
 }
 
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Sanitizes a header value.
+///
+/// Carriage returns, line feeds and null characters are removed from the given
+/// value, as they would allow to inject headers or split the message when the
+/// value is serialized. Values without such characters are returned as is.
+pub(crate) fn sanitize(value: Cow<'_, str>) -> Cow<'_, str> {
+    let check = |c: char| matches!(c, '\r' | '\n' | '\0');
+    if value.contains(check) {
+        Cow::Owned(value.replace(check, ""))
+    } else {
+        value
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Macros
 // ----------------------------------------------------------------------------
@@ -88,6 +168,11 @@ macro_rules! define_and_impl {
         /// While the HTTP specification allows those specific headers to appear
         /// multiple times, our implementation only supports setting them once.
         ///
+        /// Headers that are not part of this enum can't be represented, which
+        /// is why they're ignored when parsing requests. Header names are
+        /// always written with their canonical casing, which is accepted by
+        /// all compliant peers, as header names are case-insensitive.
+        ///
         /// [`Request`]: crate::connection::request::Request
         /// [`Response`]: crate::connection::response::Response
         #[allow(dead_code)]
@@ -128,17 +213,93 @@ macro_rules! define_and_impl {
         }
 
         /// Lookup table for HTTP headers (case-insensitive).
-        static HEADER_LOOKUP_TABLE: LazyLock<HashMap<String, Header>> =
+        static HEADER_LOOKUP_TABLE: LazyLock<HashMap<Vec<u8>, Header>> =
             LazyLock::new(|| {
                 HashMap::from_iter([
                     $(
                         $(
-                            ($header.to_lowercase(), Header::$name),
+                            (
+                                $header.to_ascii_lowercase().into_bytes(),
+                                Header::$name
+                            ),
                         )+
                     )+
                 ])
             });
 
+        impl Header {
+            /// Attempts to create a header from bytes.
+            ///
+            /// Header names are matched case-insensitively. The given bytes are
+            /// lowercased into a buffer on the stack, so no allocation happens
+            /// for known headers, and no UTF-8 validation is necessary. This is
+            /// the method used for parsing requests.
+            ///
+            /// # Errors
+            ///
+            /// This method returns [`Error::Header`], if the bytes do not match
+            /// one of the known headers.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// # use std::error::Error;
+            /// # fn main() -> Result<(), Box<dyn Error>> {
+            /// use zense::http::Header;
+            ///
+            /// // Create header from bytes
+            /// let header = Header::from_bytes(b"content-type")?;
+            /// assert_eq!(header, Header::ContentType);
+            /// # Ok(())
+            /// # }
+            /// ```
+            pub fn from_bytes(value: &[u8]) -> Result<Self> {
+                let mut buffer = [0; HEADER_MAX_LENGTH];
+
+                // Header names that exceed the maximum length of all known
+                // headers can't be known, so we can skip the lookup
+                let header = buffer.get_mut(..value.len()).and_then(|name| {
+                    name.copy_from_slice(value);
+                    name.make_ascii_lowercase();
+                    HEADER_LOOKUP_TABLE.get(&*name).copied()
+                });
+
+                // Return header or error
+                header.ok_or_else(|| {
+                    Error::Header(String::from_utf8_lossy(value).into_owned())
+                })
+            }
+        }
+
+        impl TryFrom<&[u8]> for Header {
+            type Error = Error;
+
+            /// Attempts to create a header from bytes.
+            ///
+            /// # Errors
+            ///
+            /// This method returns [`Error::Header`], if the bytes do not match
+            /// one of the known headers.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// # use std::error::Error;
+            /// # fn main() -> Result<(), Box<dyn Error>> {
+            /// use zense::http::Header;
+            ///
+            /// // Create header from bytes
+            /// let header = Header::try_from(&b"Content-Type"[..])?;
+            /// assert_eq!(header, Header::ContentType);
+            /// # Ok(())
+            /// # }
+            /// ```
+            #[inline]
+            fn try_from(value: &[u8]) -> Result<Self> {
+                Self::from_bytes(value)
+            }
+        }
+
         impl FromStr for Header {
             type Err = Error;
 
@@ -161,11 +322,9 @@ macro_rules! define_and_impl {
             /// # Ok(())
             /// # }
             /// ```
+            #[inline]
             fn from_str(value: &str) -> Result<Self> {
-                HEADER_LOOKUP_TABLE
-                    .get(&value.to_lowercase())
-                    .copied()
-                    .ok_or_else(|| Error::Header(value.to_string()))
+                Self::from_bytes(value.as_bytes())
             }
         }
     }
@@ -219,7 +378,7 @@ define_and_impl! {
         Cookie = "Cookie",
         /// Date
         Date = "Date",
-        /// ETag
+        /// `ETag`
         ETag = "ETag",
         /// Expect
         Expect = "Expect",
@@ -337,6 +496,8 @@ define_and_impl! {
         XForwardedHost = "X-Forwarded-Host",
         /// X-Forwarded-Proto
         XForwardedProto = "X-Forwarded-Proto",
+        /// X-Real-IP
+        XRealIp = "X-Real-IP",
     }
 
     /// Fetch headers
@@ -381,6 +542,10 @@ define_and_impl! {
 
     /// Miscellaneous headers
     Miscellaneous: {
+        /// X-CSRF-Token
+        XCsrfToken = "X-CSRF-Token",
+        /// X-Request-Id
+        XRequestId = "X-Request-Id",
         /// X-Requested-With
         XRequestedWith = "X-Requested-With",
     }
@@ -29,6 +29,75 @@ use std::sync::LazyLock;
 
 use super::error::{Error, Result};
 
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+#[allow(clippy::must_use_candidate)]
+impl Method {
+    /// Returns whether the method is safe, i.e., read-only.
+    ///
+    /// As defined in [RFC 7231], safe methods are not expected to change the
+    /// state of the server, which are `GET`, `HEAD`, `OPTIONS` and `TRACE`.
+    ///
+    /// [RFC 7231]: https://www.rfc-editor.org/rfc/rfc7231#section-4.2.1
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Method;
+    ///
+    /// // Ensure method is safe
+    /// assert!(Method::Get.is_safe());
+    /// assert!(Method::Head.is_safe());
+    /// assert!(Method::Options.is_safe());
+    /// assert!(Method::Trace.is_safe());
+    ///
+    /// // Ensure method is not safe
+    /// assert!(!Method::Post.is_safe());
+    /// assert!(!Method::Put.is_safe());
+    /// assert!(!Method::Delete.is_safe());
+    /// assert!(!Method::Patch.is_safe());
+    /// ```
+    #[inline]
+    pub const fn is_safe(&self) -> bool {
+        matches!(
+            self,
+            Method::Get | Method::Head | Method::Options | Method::Trace
+        )
+    }
+
+    /// Returns whether the method is idempotent.
+    ///
+    /// As defined in [RFC 7231], idempotent methods have the same effect when
+    /// repeated, which are all safe methods, as well as `PUT` and `DELETE`, so
+    /// requests using them can be retried safely.
+    ///
+    /// [RFC 7231]: https://www.rfc-editor.org/rfc/rfc7231#section-4.2.2
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Method;
+    ///
+    /// // Ensure method is idempotent
+    /// assert!(Method::Get.is_idempotent());
+    /// assert!(Method::Head.is_idempotent());
+    /// assert!(Method::Options.is_idempotent());
+    /// assert!(Method::Trace.is_idempotent());
+    /// assert!(Method::Put.is_idempotent());
+    /// assert!(Method::Delete.is_idempotent());
+    ///
+    /// // Ensure method is not idempotent
+    /// assert!(!Method::Post.is_idempotent());
+    /// assert!(!Method::Patch.is_idempotent());
+    /// ```
+    #[inline]
+    pub const fn is_idempotent(&self) -> bool {
+        self.is_safe() || matches!(self, Method::Put | Method::Delete)
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Trait implementations
 // ----------------------------------------------------------------------------
@@ -66,7 +135,7 @@ macro_rules! define_and_impl {
     ) => {
         /// HTTP method.
         #[allow(dead_code)]
-        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
         pub enum Method {
             $(
                 $(#[$comment])*
@@ -131,8 +200,14 @@ macro_rules! define_and_impl {
             /// # }
             /// ```
             fn from_str(value: &str) -> Result<Self> {
-                METHOD_LOOKUP_TABLE
-                    .get(&value.to_uppercase())
+                // Methods are almost always sent in uppercase, so we try the
+                // lookup first, before allocating an uppercase copy
+                let method = METHOD_LOOKUP_TABLE.get(value).or_else(|| {
+                    METHOD_LOOKUP_TABLE.get(&value.to_uppercase())
+                });
+
+                // Return method or error
+                method
                     .copied()
                     .ok_or_else(|| Error::Method(value.to_string()))
             }
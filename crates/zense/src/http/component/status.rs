@@ -24,6 +24,113 @@
 
 use std::fmt;
 
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+#[allow(clippy::must_use_candidate)]
+impl Status {
+    /// Returns the class of the status, i.e., the leading digit of its code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Status;
+    ///
+    /// // Obtain status classes
+    /// assert_eq!(Status::Continue.class(), 1);
+    /// assert_eq!(Status::Ok.class(), 2);
+    /// assert_eq!(Status::Found.class(), 3);
+    /// assert_eq!(Status::NotFound.class(), 4);
+    /// assert_eq!(Status::BadGateway.class(), 5);
+    /// ```
+    #[allow(clippy::cast_possible_truncation)]
+    #[inline]
+    pub const fn class(&self) -> u8 {
+        (*self as u16 / 100) as u8
+    }
+
+    /// Returns whether the status is informational, i.e., `1xx`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Status;
+    ///
+    /// // Ensure status is informational
+    /// assert!(Status::SwitchingProtocols.is_informational());
+    /// assert!(!Status::Ok.is_informational());
+    /// ```
+    #[inline]
+    pub const fn is_informational(&self) -> bool {
+        self.class() == 1
+    }
+
+    /// Returns whether the status indicates success, i.e., `2xx`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Status;
+    ///
+    /// // Ensure status indicates success
+    /// assert!(Status::NoContent.is_success());
+    /// assert!(!Status::NotModified.is_success());
+    /// ```
+    #[inline]
+    pub const fn is_success(&self) -> bool {
+        self.class() == 2
+    }
+
+    /// Returns whether the status indicates redirection, i.e., `3xx`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Status;
+    ///
+    /// // Ensure status indicates redirection
+    /// assert!(Status::MovedPermanently.is_redirection());
+    /// assert!(!Status::Ok.is_redirection());
+    /// ```
+    #[inline]
+    pub const fn is_redirection(&self) -> bool {
+        self.class() == 3
+    }
+
+    /// Returns whether the status indicates a client error, i.e., `4xx`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Status;
+    ///
+    /// // Ensure status indicates client error
+    /// assert!(Status::Forbidden.is_client_error());
+    /// assert!(!Status::InternalServerError.is_client_error());
+    /// ```
+    #[inline]
+    pub const fn is_client_error(&self) -> bool {
+        self.class() == 4
+    }
+
+    /// Returns whether the status indicates a server error, i.e., `5xx`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Status;
+    ///
+    /// // Ensure status indicates server error
+    /// assert!(Status::ServiceUnavailable.is_server_error());
+    /// assert!(!Status::NotFound.is_server_error());
+    /// ```
+    #[inline]
+    pub const fn is_server_error(&self) -> bool {
+        self.class() == 5
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Trait implementations
 // ----------------------------------------------------------------------------
@@ -40,6 +147,37 @@ impl AsRef<str> for Status {
 
 impl fmt::Display for Status {
     /// Formats the status for display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Status;
+    ///
+    /// // Ensure codes and reason phrases of statuses
+    /// for (status, code, reason) in [
+    ///     (Status::Continue, 100, "Continue"),
+    ///     (Status::SwitchingProtocols, 101, "Switching Protocols"),
+    ///     (Status::PartialContent, 206, "Partial Content"),
+    ///     (Status::NotModified, 304, "Not Modified"),
+    ///     (Status::PermanentRedirect, 308, "Permanent Redirect"),
+    ///     (Status::PayloadTooLarge, 413, "Payload Too Large"),
+    ///     (Status::UriTooLong, 414, "URI Too Long"),
+    ///     (Status::UnsupportedMediaType, 415, "Unsupported Media Type"),
+    ///     (Status::RangeNotSatisfiable, 416, "Range Not Satisfiable"),
+    ///     (Status::TooManyRequests, 429, "Too Many Requests"),
+    ///     (
+    ///         Status::RequestHeaderFieldsTooLarge,
+    ///         431,
+    ///         "Request Header Fields Too Large",
+    ///     ),
+    ///     (Status::ServiceUnavailable, 503, "Service Unavailable"),
+    ///     (Status::GatewayTimeout, 504, "Gateway Timeout"),
+    /// ] {
+    ///     assert_eq!(status as u16, code);
+    ///     assert_eq!(status.name(), reason);
+    ///     assert_eq!(status.to_string(), format!("{code} {reason}"));
+    /// }
+    /// ```
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let code = *self as u16;
         f.write_str(code.to_string().as_str())?;
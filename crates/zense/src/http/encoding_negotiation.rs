@@ -0,0 +1,207 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! HTTP content coding negotiation.
+
+use std::cmp::Reverse;
+use std::fmt;
+
+use super::accept::parse_quality;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// HTTP content coding.
+///
+/// Content codings are the items of the [`Header::AcceptEncoding`][] header,
+/// consisting of a name, e.g., `gzip`, `br` or `identity`, which might be the
+/// wildcard `*`, and a quality value, which is stored in thousandths, like for
+/// [`MediaRange`][]. Note that this is unrelated to percent-encoding.
+///
+/// [`Header::AcceptEncoding`]: crate::http::Header::AcceptEncoding
+/// [`MediaRange`]: crate::http::accept::MediaRange
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Coding<'a> {
+    /// Coding name.
+    pub name: &'a str,
+    /// Quality value in thousandths.
+    pub quality: u16,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Coding<'_> {
+    /// Returns whether the coding matches the given coding name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::encoding_negotiation::parse;
+    ///
+    /// // Parse codings
+    /// let codings = parse("*, gzip");
+    /// assert!(codings[0].matches("GZIP"));
+    /// assert!(codings[1].matches("br"));
+    /// ```
+    #[must_use]
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == "*" || self.name.eq_ignore_ascii_case(name.trim())
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl fmt::Display for Coding<'_> {
+    /// Formats the coding for display.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name)?;
+        if self.quality < 1000 {
+            let value = format!("{:.3}", f32::from(self.quality) / 1000.0);
+            let value = value.trim_end_matches('0').trim_end_matches('.');
+            write!(f, ";q={value}")?;
+        }
+
+        // No errors occurred
+        Ok(())
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Parses the value of an [`Header::AcceptEncoding`][] header.
+///
+/// The returned codings are ordered by quality value, and then by specificity,
+/// so the wildcard `*` comes after named codings of the same quality, while
+/// codings that are equal in both keep their original order. Codings without
+/// a quality value default to `q=1`, and invalid codings are skipped.
+///
+/// [`Header::AcceptEncoding`]: crate::http::Header::AcceptEncoding
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::encoding_negotiation::parse;
+///
+/// // Parse codings
+/// let codings = parse("gzip;q=0.8, *;q=0.1, br, deflate;q=0.8");
+/// assert_eq!(codings[0].to_string(), "br");
+/// assert_eq!(codings[1].to_string(), "gzip;q=0.8");
+/// assert_eq!(codings[2].to_string(), "deflate;q=0.8");
+/// assert_eq!(codings[3].to_string(), "*;q=0.1");
+/// ```
+#[must_use]
+pub fn parse(value: &str) -> Vec<Coding<'_>> {
+    let mut codings = value
+        .split(',')
+        .filter_map(parse_coding)
+        .collect::<Vec<_>>();
+
+    // Order by quality value first, then by specificity - the sort is stable,
+    // so equal codings retain the order in which they were given
+    codings.sort_by_key(|coding| Reverse((coding.quality, coding.name != "*")));
+    codings
+}
+
+/// Returns the best coding among the given coding names.
+///
+/// For each of the available codings, a coding with the same name determines
+/// its quality, or the wildcard `*`, if there's none. If neither is present,
+/// the coding is unacceptable, except for `identity`, which is acceptable with
+/// the lowest quality, unless excluded with `identity;q=0`, or `*;q=0`. The
+/// coding with
+/// the highest quality is returned, while ties are resolved in favor of the
+/// order of the available codings, i.e., the server's preference. If no coding
+/// is acceptable, [`None`] is returned, which should be answered with
+/// "406 Not Acceptable", or by sending the content without coding anyway.
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::encoding_negotiation::{best_encoding, parse};
+///
+/// // Obtain best coding by preference of client
+/// let codings = parse("gzip;q=0.5, br");
+/// let coding = best_encoding(&codings, &["gzip", "br", "identity"]);
+/// assert_eq!(coding, Some("br"));
+///
+/// // Obtain best coding, falling back to identity
+/// let codings = parse("deflate");
+/// let coding = best_encoding(&codings, &["gzip", "identity"]);
+/// assert_eq!(coding, Some("identity"));
+///
+/// // Obtain best coding, with identity being forbidden
+/// let codings = parse("deflate, identity;q=0");
+/// let coding = best_encoding(&codings, &["gzip", "identity"]);
+/// assert_eq!(coding, None);
+/// ```
+#[must_use]
+pub fn best_encoding<'m>(
+    codings: &[Coding], available: &[&'m str],
+) -> Option<&'m str> {
+    let mut best: Option<(&'m str, u16)> = None;
+    for &name in available {
+        let quality = codings
+            .iter()
+            .filter(|coding| coding.matches(name))
+            .max_by_key(|coding| coding.name != "*")
+            .map_or_else(
+                || u16::from(name.eq_ignore_ascii_case("identity")),
+                |coding| coding.quality,
+            );
+
+        // Only replace the current best match if the quality is higher
+        if quality > 0 && best.map_or(true, |(_, q)| quality > q) {
+            best = Some((name, quality));
+        }
+    }
+
+    // Return best match
+    best.map(|(name, _)| name)
+}
+
+// ----------------------------------------------------------------------------
+
+/// Parses a coding.
+fn parse_coding(value: &str) -> Option<Coding<'_>> {
+    let mut iter = value.split(';');
+    let name = iter.next()?.trim();
+
+    // Extract quality value, ignoring all other parameters
+    let mut quality = 1000;
+    for param in iter {
+        if let Some((key, value)) = param.split_once('=') {
+            if key.trim().eq_ignore_ascii_case("q") {
+                quality = parse_quality(value.trim())?;
+            }
+        }
+    }
+
+    // Ensure name is not empty
+    (!name.is_empty()).then_some(Coding { name, quality })
+}
@@ -0,0 +1,163 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! HTTP forwarded elements.
+
+// ----------------------------------------------------------------------------
+// Enums
+// ----------------------------------------------------------------------------
+
+/// Trusted forwarded header.
+///
+/// Proxies announce the protocol of the original request in either the
+/// [`Header::Forwarded`][] or the [`Header::XForwardedProto`][] header, but
+/// clients can send both, and a proxy usually only sets or overwrites one of
+/// them, passing on the other unchanged. Thus, only the header that is set by
+/// the proxy in front of this crate must be trusted.
+///
+/// [`Header::Forwarded`]: crate::http::Header::Forwarded
+/// [`Header::XForwardedProto`]: crate::http::Header::XForwardedProto
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Trust {
+    /// No header is trusted.
+    #[default]
+    None,
+    /// [`Header::Forwarded`][] header.
+    ///
+    /// [`Header::Forwarded`]: crate::http::Header::Forwarded
+    Forwarded,
+    /// [`Header::XForwardedProto`][] header.
+    ///
+    /// [`Header::XForwardedProto`]: crate::http::Header::XForwardedProto
+    XForwardedProto,
+}
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// HTTP forwarded element.
+///
+/// Forwarded elements are the items of the [`Header::Forwarded`][] header, as
+/// defined in [RFC 7239], each of which is added by a proxy, and describes the
+/// hop it forwarded the request for. Values are unquoted, but not validated,
+/// as their format is up to the proxy, e.g., `for` can hold an obfuscated
+/// identifier like `_hidden`, or `unknown`, instead of an address. Note that
+/// `for` is a keyword, so the field must be accessed as `r#for`.
+///
+/// [`Header::Forwarded`]: crate::http::Header::Forwarded
+/// [RFC 7239]: https://www.rfc-editor.org/rfc/rfc7239
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ForwardedElement<'a> {
+    /// Interface where the request came in to the proxy.
+    pub by: Option<&'a str>,
+    /// Node making the request to the proxy.
+    pub r#for: Option<&'a str>,
+    /// Host header as received by the proxy.
+    pub host: Option<&'a str>,
+    /// Protocol used to make the request.
+    pub proto: Option<&'a str>,
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Parses the value of a [`Header::Forwarded`][] header.
+///
+/// Elements are separated by commas, and their pairs by semicolons, both of
+/// which are ignored inside of quoted strings. Parameter names are matched
+/// case-insensitively, and unknown parameters and malformed pairs are skipped.
+///
+/// [`Header::Forwarded`]: crate::http::Header::Forwarded
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::forwarded::parse;
+///
+/// // Parse forwarded elements
+/// let elements = parse("for=192.0.2.60;proto=http;by=203.0.113.43");
+/// assert_eq!(elements.len(), 1);
+/// assert_eq!(elements[0].r#for, Some("192.0.2.60"));
+/// assert_eq!(elements[0].proto, Some("http"));
+/// assert_eq!(elements[0].by, Some("203.0.113.43"));
+/// assert_eq!(elements[0].host, None);
+///
+/// // Parse forwarded elements of multiple proxies
+/// let elements = parse(r#"for="[2001:db8::1]:4711", For=198.51.100.17"#);
+/// assert_eq!(elements.len(), 2);
+/// assert_eq!(elements[0].r#for, Some("[2001:db8::1]:4711"));
+/// assert_eq!(elements[1].r#for, Some("198.51.100.17"));
+/// ```
+#[must_use]
+pub fn parse(value: &str) -> Vec<ForwardedElement<'_>> {
+    let mut elements = Vec::new();
+    for element in split(value, ',') {
+        let mut forwarded = ForwardedElement::default();
+        for pair in split(element, ';') {
+            let Some((name, value)) = pair.split_once('=') else {
+                continue;
+            };
+
+            // Unquote value, and match parameter, ignoring unknown ones
+            let name = name.trim();
+            let value = unquote(value.trim());
+            if name.eq_ignore_ascii_case("by") {
+                forwarded.by = Some(value);
+            } else if name.eq_ignore_ascii_case("for") {
+                forwarded.r#for = Some(value);
+            } else if name.eq_ignore_ascii_case("host") {
+                forwarded.host = Some(value);
+            } else if name.eq_ignore_ascii_case("proto") {
+                forwarded.proto = Some(value);
+            }
+        }
+
+        // Skip empty elements
+        if forwarded != ForwardedElement::default() {
+            elements.push(forwarded);
+        }
+    }
+
+    // Return forwarded elements
+    elements
+}
+
+/// Splits the given value at the given separator outside of quoted strings.
+fn split(value: &str, separator: char) -> impl Iterator<Item = &str> {
+    let mut quoted = false;
+    value.split(move |char| {
+        if char == '"' {
+            quoted = !quoted;
+        }
+        char == separator && !quoted
+    })
+}
+
+/// Removes enclosing quotes from the given value, if any.
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
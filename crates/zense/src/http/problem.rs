@@ -0,0 +1,186 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! HTTP problem details.
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use super::Status;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// HTTP problem details.
+///
+/// Problem details are a standardized format for error responses of APIs, as
+/// defined in [RFC 7807], and are sent with [`ResponseExt::problem`][]. The
+/// status is set when creating the problem, and used for the response as
+/// well, so both can never diverge. The type defaults to `about:blank`, and
+/// the title to the name of the status, which is what the RFC recommends for
+/// problems that don't need further semantics than the status.
+///
+/// [`ResponseExt::problem`]: crate::http::response::ResponseExt::problem
+/// [RFC 7807]: https://www.rfc-editor.org/rfc/rfc7807
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::problem::Problem;
+/// use zense::http::Status;
+///
+/// // Create problem
+/// let problem = Problem::new(Status::Forbidden)
+///     .type_("https://example.com/probs/out-of-credit")
+///     .title("You do not have enough credit")
+///     .detail("Your current balance is 30, but that costs 50")
+///     .instance("/account/12345/msgs/abc");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// Problem type, a URI reference.
+    kind: String,
+    /// Short, human-readable summary of the problem type.
+    title: String,
+    /// Status.
+    status: Status,
+    /// Human-readable explanation of the occurrence of the problem.
+    detail: Option<String>,
+    /// URI reference of the occurrence of the problem.
+    instance: Option<String>,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Problem {
+    /// Creates problem details for the given status.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::problem::Problem;
+    /// use zense::http::Status;
+    ///
+    /// // Create problem
+    /// let problem = Problem::new(Status::NotFound);
+    /// assert_eq!(problem.status(), Status::NotFound);
+    /// ```
+    #[must_use]
+    pub fn new(status: Status) -> Self {
+        Self {
+            kind: String::from("about:blank"),
+            title: status.name().to_string(),
+            status,
+            detail: None,
+            instance: None,
+        }
+    }
+
+    /// Sets the problem type.
+    ///
+    /// Note that `type` is a keyword, which is why the method is suffixed with
+    /// an underscore, in line with [`ResponseExt::continue_`][].
+    ///
+    /// [`ResponseExt::continue_`]: crate::http::response::ResponseExt::continue_
+    #[inline]
+    #[must_use]
+    pub fn type_<T>(mut self, kind: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.kind = kind.into();
+        self
+    }
+
+    /// Sets the problem title.
+    #[inline]
+    #[must_use]
+    pub fn title<T>(mut self, title: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.title = title.into();
+        self
+    }
+
+    /// Sets the problem detail.
+    #[inline]
+    #[must_use]
+    pub fn detail<D>(mut self, detail: D) -> Self
+    where
+        D: Into<String>,
+    {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Sets the problem instance.
+    #[inline]
+    #[must_use]
+    pub fn instance<I>(mut self, instance: I) -> Self
+    where
+        I: Into<String>,
+    {
+        self.instance = Some(instance.into());
+        self
+    }
+}
+
+#[allow(clippy::must_use_candidate)]
+impl Problem {
+    /// Returns the status.
+    #[inline]
+    pub fn status(&self) -> Status {
+        self.status
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Serialize for Problem {
+    /// Serializes the problem details, omitting absent members.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let len = 3
+            + usize::from(self.detail.is_some())
+            + usize::from(self.instance.is_some());
+
+        // Serialize members in the order they're defined in RFC 7807
+        let mut state = serializer.serialize_struct("Problem", len)?;
+        state.serialize_field("type", &self.kind)?;
+        state.serialize_field("title", &self.title)?;
+        state.serialize_field("status", &(self.status as u16))?;
+        if let Some(detail) = &self.detail {
+            state.serialize_field("detail", detail)?;
+        }
+        if let Some(instance) = &self.instance {
+            state.serialize_field("instance", instance)?;
+        }
+        state.end()
+    }
+}
@@ -25,17 +25,28 @@
 use httparse::Status;
 use std::borrow::Cow;
 use std::fmt;
-use std::str::{self, FromStr};
+use std::io::{self, Read, Write};
+use std::mem;
+use std::net::IpAddr;
+use std::str;
 
-use super::component::{Header, Method};
+use super::accept::{self, MediaRange};
+use super::component::{Header, Method, Version};
+use super::encoding_negotiation;
+use super::forwarded::{self, ForwardedElement, Trust};
 
+mod config;
 mod error;
 mod headers;
+mod multipart;
+mod reader;
 mod uri;
 
+pub use config::Config;
 pub use error::{Error, Result};
-pub use headers::Headers;
-pub use uri::{Query, Uri};
+pub use headers::{Headers, Redacted};
+pub use multipart::{Multipart, Part};
+pub use uri::{encoding, Query, Uri};
 
 // ----------------------------------------------------------------------------
 // Structs
@@ -46,7 +57,12 @@ pub use uri::{Query, Uri};
 /// The regular way to create a [`Request`] is to use [`Request::from_bytes`],
 /// which parses a given slice of bytes. The returned [`Request`] is bound to
 /// the lifetime of the byte slice, avoiding unnecessary allocations where
-/// possible, except for the [`BTreeMap`][] used for headers.
+/// possible, except for the [`BTreeMap`][] used for headers, which allocates
+/// lazily, i.e., only if the request contains at least one known header.
+///
+/// When formatted for display or debugging, the values of headers listed in
+/// [`Header::SENSITIVE`] are redacted, so credentials don't leak into logs.
+/// Use [`Headers::redacted`] to redact a different set of headers.
 ///
 /// [`BTreeMap`]: std::collections::BTreeMap
 ///
@@ -60,16 +76,51 @@ pub use uri::{Query, Uri};
 ///     .method(Method::Get)
 ///     .uri("/");
 /// ```
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Request<'a> {
     /// Request method.
     pub method: Method,
     /// Request URI.
     pub uri: Uri<'a>,
+    /// Request version.
+    pub version: Version,
     /// Request headers.
     pub headers: Headers<'a>,
     /// Request body.
+    ///
+    /// Requests are moved, not cloned, when passed from one middleware to the
+    /// next, so a [`Stack`][] doesn't copy the body. However, middlewares and
+    /// handlers that pass a request on more than once must clone it, e.g.,
+    /// [`Retry`][] for each attempt, and [`Or`][] for the fallback. When the
+    /// request was parsed with [`Request::from_bytes`], the body borrows from
+    /// the input, so cloning it only copies the reference. When it was read
+    /// with [`Request::from_reader`] or its variants, which is the case for
+    /// all requests served by the server, the body is owned, so every clone
+    /// copies the entire body.
+    ///
+    /// [`Or`]: crate::handler::Or
+    /// [`Retry`]: crate::middleware::retry::Retry
+    /// [`Stack`]: crate::handler::Stack
     pub body: Cow<'a, [u8]>,
+    /// Request peer.
+    ///
+    /// This is the address of the immediate peer, i.e., the client or proxy
+    /// the connection was accepted from. It can't be derived from the request
+    /// itself, so it must be set by the server, and is [`None`] otherwise.
+    pub peer: Option<IpAddr>,
+    /// Request mount point.
+    ///
+    /// This is the length of the path prefix that was matched by the base path
+    /// of the innermost [`Router`][] the request was passed to, which is set by
+    /// its [`Stack`][], and used to compute [`Request::relative_path`]. It's a
+    /// byte offset into the path, so middlewares that rewrite the path should
+    /// reset it to `0`, unless the matched prefix is retained, which is what
+    /// [`Rewrite`][] does.
+    ///
+    /// [`Rewrite`]: crate::middleware::rewrite::Rewrite
+    /// [`Router`]: crate::router::Router
+    /// [`Stack`]: crate::handler::Stack
+    pub mount: usize,
 }
 
 // ----------------------------------------------------------------------------
@@ -82,97 +133,1206 @@ impl<'a> Request<'a> {
     /// # Examples
     ///
     /// ```
-    /// use zense::http::Request;
+    /// use zense::http::Request;
+    ///
+    /// // Create request
+    /// let req = Request::new();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a request from the given bytes.
+    ///
+    /// HTTP requests are parsed using the [`httparse`] crate, which is one of
+    /// the few dependencies that we rely on as it provides an efficient, fast,
+    /// and well-tested parser. The returned [`Request`] will be bound to the
+    /// lifetime of the input, avoiding allocations where possible.
+    ///
+    /// After parsing, basic validation is performed on the request path to
+    /// ensure it doesn't exceed 4kb in size and doesn't attempt traversal.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::Incomplete`], if the given buffer contained
+    /// insufficient data to provide a meaningful answer, [`Error::Parser`], if
+    /// the buffer contained invalid data, and [`Error::Component`], when the
+    /// parsed request contains an invalid [`Method`] or [`Header`].
+    ///
+    /// [`Error::Security`] is returned, if the request path is too long or
+    /// attempts traversal, the request target is in asterisk-form, but the
+    /// method isn't `OPTIONS`, or the request is ambiguous with respect to its
+    /// body, i.e., carries both a [`Header::ContentLength`] and a
+    /// [`Header::TransferEncoding`] header, differing content lengths, or a
+    /// content length that isn't a plain decimal number, which could all be
+    /// exploited for request smuggling.
+    ///
+    /// Headers with obsolete line folding, i.e., continuation lines starting
+    /// with whitespace, are rejected with [`Error::Parser`], as recommended by
+    /// [RFC 9112], and optional whitespace, i.e., spaces and tabs, around header
+    /// values is removed, so values can be compared without trimming.
+    ///
+    /// [RFC 9112]: https://www.rfc-editor.org/rfc/rfc9112#section-5.2
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::{request, Header, Method, Request, Version};
+    ///
+    /// // Create request from bytes
+    /// let req = Request::from_bytes(b"GET / HTTP/1.1\r\n\r\n")?;
+    /// assert_eq!(req.method, Method::Get);
+    /// assert_eq!(req.uri.path, "/");
+    /// assert_eq!(req.version, Version::Http11);
+    ///
+    /// // Create request from bytes with HTTP/1.0 and no host
+    /// let req = Request::from_bytes(b"GET / HTTP/1.0\r\n\r\n")?;
+    /// assert_eq!(req.version, Version::Http10);
+    ///
+    /// // Create request from bytes with conflicting headers
+    /// let res = Request::from_bytes(concat!(
+    ///     "POST / HTTP/1.1\r\n",
+    ///     "Content-Length: 4\r\n",
+    ///     "Transfer-Encoding: chunked\r\n\r\n",
+    /// ).as_bytes());
+    /// assert!(matches!(res, Err(request::Error::Security(_))));
+    ///
+    /// // Create request from bytes with differing content lengths
+    /// let res = Request::from_bytes(concat!(
+    ///     "POST / HTTP/1.1\r\n",
+    ///     "Content-Length: 4\r\n",
+    ///     "Content-Length: 5\r\n\r\n",
+    /// ).as_bytes());
+    /// assert!(matches!(res, Err(request::Error::Security(_))));
+    ///
+    /// // Create request from bytes with invalid content lengths
+    /// for value in ["abc", "+5"] {
+    ///     let head = format!("POST / HTTP/1.1\r\nContent-Length: {value}");
+    ///     let head = format!("{head}\r\n\r\n");
+    ///     let res = Request::from_bytes(head.as_bytes());
+    ///     assert!(matches!(res, Err(request::Error::Security(_))));
+    /// }
+    ///
+    /// // Create request from bytes with obsolete line folding
+    /// let res = Request::from_bytes(concat!(
+    ///     "GET / HTTP/1.1\r\n",
+    ///     "Accept: text/html,\r\n",
+    ///     "\ttext/plain\r\n\r\n",
+    /// ).as_bytes());
+    /// assert!(matches!(res, Err(request::Error::Parser(_))));
+    ///
+    /// // Create request from bytes with whitespace around header values
+    /// let req = Request::from_bytes(concat!(
+    ///     "GET / HTTP/1.1\r\n",
+    ///     "Accept: \t text/html \t\r\n",
+    ///     "Accept-Language:en\t\r\n\r\n",
+    /// ).as_bytes())?;
+    /// assert_eq!(req.headers.get(Header::Accept), Some("text/html"));
+    /// assert_eq!(req.headers.get(Header::AcceptLanguage), Some("en"));
+    ///
+    /// // Create request from bytes with padded content type
+    /// let req = Request::from_bytes(concat!(
+    ///     "POST / HTTP/1.1\r\n",
+    ///     "Content-Type:  text/html \r\n\r\n",
+    /// ).as_bytes())?;
+    /// assert_eq!(req.headers.get(Header::ContentType), Some("text/html"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
+        Self::from_bytes_with(bytes, &Config::default())
+    }
+
+    /// Creates a request from the given bytes with the given configuration.
+    ///
+    /// This method is identical to [`Request::from_bytes`], but additionally
+    /// enforces the limits of the given [`Config`] while parsing.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`Request::from_bytes`], this method
+    /// returns [`Error::BodyTooLarge`], if the body exceeds the configured
+    /// maximum length, which should be answered with "413 Content Too Large",
+    /// and [`Error::HeadersTooLarge`], if the head exceeds the configured
+    /// maximum size, which should be answered with "431 Request Header Fields
+    /// Too Large".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::{self, Config};
+    /// use zense::http::Request;
+    ///
+    /// // Create configuration with maximum body length
+    /// let config = Config::new()
+    ///     .max_body_len(4);
+    ///
+    /// // Create request from bytes with oversized body
+    /// let res = Request::from_bytes_with(concat!(
+    ///     "POST / HTTP/1.1\r\n",
+    ///     "Content-Length: 11\r\n\r\n",
+    ///     "Hello world",
+    /// ).as_bytes(), &config);
+    /// assert!(matches!(res, Err(request::Error::BodyTooLarge)));
+    ///
+    /// // Create configuration with maximum head size
+    /// let config = Config::new()
+    ///     .max_header_bytes(64);
+    ///
+    /// // Create request from bytes with oversized head
+    /// let cookie = "a".repeat(64);
+    /// let data = format!("GET / HTTP/1.1\r\nCookie: {cookie}\r\n\r\n");
+    /// let res = Request::from_bytes_with(data.as_bytes(), &config);
+    /// assert!(matches!(res, Err(request::Error::HeadersTooLarge)));
+    /// ```
+    #[inline]
+    pub fn from_bytes_with(bytes: &'a [u8], config: &Config) -> Result<Self> {
+        parse(bytes, config).map(|(req, _)| req)
+    }
+
+    /// Creates a request from the given bytes, returning the consumed length.
+    ///
+    /// In contrast to [`Request::from_bytes`], which treats all bytes after the
+    /// head as the body, this method determines the end of the request from
+    /// its framing, i.e., the content length or chunked transfer encoding, and
+    /// returns the number of bytes consumed, i.e., the length of head and body.
+    /// This allows to parse pipelined requests that share a buffer, continuing
+    /// with the remaining bytes. Chunked bodies are replaced with their decoded
+    /// contents, and the headers are adjusted accordingly.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`Request::from_bytes`], this method
+    /// returns [`Error::Incomplete`], if the buffer doesn't contain the entire
+    /// body, and [`Error::Security`], if the transfer encoding is unsupported
+    /// or invalid. Only the chunked transfer coding is supported, as other
+    /// codings, e.g., `gzip`, would be lost when the chunks are decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::{request, Request};
+    ///
+    /// // Create buffer with two pipelined requests
+    /// let bytes = concat!(
+    ///     "POST /a HTTP/1.1\r\nContent-Length: 5\r\n\r\nHello",
+    ///     "GET /b HTTP/1.1\r\n\r\n",
+    /// ).as_bytes();
+    ///
+    /// // Create requests from buffer
+    /// let (req, n) = Request::from_bytes_partial(bytes)?;
+    /// assert_eq!(req.uri.path, "/a");
+    /// assert_eq!(req.body.as_ref(), b"Hello");
+    /// let (req, m) = Request::from_bytes_partial(&bytes[n..])?;
+    /// assert_eq!(req.uri.path, "/b");
+    /// assert!(req.body.is_empty());
+    /// assert_eq!(n + m, bytes.len());
+    ///
+    /// // Create request from buffer with oversized chunk size
+    /// let bytes = concat!(
+    ///     "POST /a HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n",
+    ///     "FFFFFFFFFFFFFFFF\r\nHello\r\n0\r\n\r\n",
+    /// ).as_bytes();
+    /// let res = Request::from_bytes_partial(bytes);
+    /// assert!(matches!(res, Err(request::Error::Security(_))));
+    ///
+    /// // Create request from buffer with invalid content length
+    /// let bytes = concat!(
+    ///     "POST /a HTTP/1.1\r\nContent-Length: abc\r\n\r\n",
+    ///     "GET /smuggled HTTP/1.1\r\n\r\n",
+    /// ).as_bytes();
+    /// let res = Request::from_bytes_partial(bytes);
+    /// assert!(matches!(res, Err(request::Error::Security(_))));
+    ///
+    /// // Create request from buffer with unsupported transfer coding
+    /// let bytes = concat!(
+    ///     "POST /a HTTP/1.1\r\nTransfer-Encoding: gzip, chunked\r\n\r\n",
+    ///     "5\r\nHello\r\n0\r\n\r\n",
+    /// ).as_bytes();
+    /// let res = Request::from_bytes_partial(bytes);
+    /// assert!(matches!(res, Err(request::Error::Security(_))));
+    ///
+    /// // Create request from buffer with transfer coding split across lines
+    /// let bytes = concat!(
+    ///     "POST /a HTTP/1.1\r\n",
+    ///     "Transfer-Encoding: gzip\r\n",
+    ///     "Transfer-Encoding: chunked\r\n\r\n",
+    ///     "5\r\nHello\r\n0\r\n\r\n",
+    /// ).as_bytes();
+    /// let res = Request::from_bytes_partial(bytes);
+    /// assert!(matches!(res, Err(request::Error::Security(_))));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_bytes_partial(bytes: &'a [u8]) -> Result<(Self, usize)> {
+        let (mut req, n) = parse(bytes, &Config::default())?;
+
+        // Determine the end of the body - the transfer encoding takes
+        // precedence, as both must not be present, which we checked
+        let encoding = req.headers.get(Header::TransferEncoding);
+        if let Some(value) = encoding {
+            check_transfer_encoding(value)?;
+
+            // Decode chunked body, and adjust headers, since the body is not
+            // chunked anymore after decoding
+            let data = bytes[n..].to_vec();
+            let (body, rest) =
+                reader::read_chunked(&mut io::empty(), data, None)?;
+            let len = body.len().to_string();
+            req.headers.remove(Header::TransferEncoding);
+            req.headers.put(Header::ContentLength, len);
+            req.body = Cow::Owned(body);
+            Ok((req, bytes.len() - rest.len()))
+        } else {
+            let len = body_len(&req.headers)?;
+            if req.body.len() < len {
+                return Err(Error::Incomplete);
+            }
+
+            // Limit body to content length, as the rest is the next request
+            req.body = Cow::Borrowed(&bytes[n..n + len]);
+            Ok((req, n + len))
+        }
+    }
+
+    /// Creates a request from the given reader.
+    ///
+    /// In contrast to [`Request::from_bytes`], this method reads from the given
+    /// reader until the request head is complete, handling partial reads, and
+    /// then reads exactly as many bytes as specified by the content length,
+    /// or decodes a chunked body. As the request outlives the buffer it was
+    /// read into, it owns its data. Chunked bodies are replaced with their
+    /// decoded contents, and the headers are adjusted accordingly.
+    ///
+    /// Note that bytes read beyond the end of the request are discarded, so
+    /// pipelined requests are not supported, which is what the method
+    /// [`Request::from_reader_buffered`] is for.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors returned by [`Request::from_bytes`], this
+    /// method returns [`Error::Io`], if reading fails, [`Error::Incomplete`],
+    /// if the reader is exhausted before the request is complete,
+    /// [`Error::HeadersTooLarge`], if the head exceeds 8kb, and
+    /// [`Error::Security`], if the transfer encoding is unsupported or
+    /// invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::collections::VecDeque;
+    /// use std::io::{self, Read};
+    /// use zense::http::{request, Method, Request};
+    ///
+    /// // Define reader that returns one part per read
+    /// struct Parts(VecDeque<&'static [u8]>);
+    ///
+    /// // Create reader implementation
+    /// impl Read for Parts {
+    ///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    ///         let part = self.0.pop_front().unwrap_or_default();
+    ///         buf[..part.len()].copy_from_slice(part);
+    ///         Ok(part.len())
+    ///     }
+    /// }
+    ///
+    /// // Create request from reader with two parts
+    /// let reader = Parts(VecDeque::from([
+    ///     &b"POST /coffee HTTP/1.1\r\nContent-Le"[..],
+    ///     &b"ngth: 11\r\n\r\nHello world"[..],
+    /// ]));
+    /// let req = Request::from_reader(reader)?;
+    /// assert_eq!(req.method, Method::Post);
+    /// assert_eq!(req.body.as_ref(), b"Hello world");
+    ///
+    /// // Create request from reader with chunked body
+    /// let reader = Parts(VecDeque::from([
+    ///     &b"POST /coffee HTTP/1.1\r\nTransfer-Encoding: chunked\r\n"[..],
+    ///     &b"\r\n5\r\nHello\r\n6\r\n world\r\n0\r\n\r\n"[..],
+    /// ]));
+    /// let req = Request::from_reader(reader)?;
+    /// assert_eq!(req.body.as_ref(), b"Hello world");
+    ///
+    /// // Create request from reader with oversized chunk size
+    /// let reader = Parts(VecDeque::from([
+    ///     &b"POST /coffee HTTP/1.1\r\nTransfer-Encoding: chunked\r\n"[..],
+    ///     &b"\r\nFFFFFFFFFFFFFFFF\r\nHello\r\n0\r\n\r\n"[..],
+    /// ]));
+    /// let res = Request::from_reader(reader);
+    /// assert!(matches!(res, Err(request::Error::Security(_))));
+    ///
+    /// // Create request from reader with mismatched chunk size
+    /// let reader = Parts(VecDeque::from([
+    ///     &b"POST /coffee HTTP/1.1\r\nTransfer-Encoding: chunked\r\n"[..],
+    ///     &b"\r\n3\r\nHello\r\n0\r\n\r\n"[..],
+    /// ]));
+    /// let res = Request::from_reader(reader);
+    /// assert!(matches!(res, Err(request::Error::Security(_))));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn from_reader<R>(reader: R) -> Result<Request<'static>>
+    where
+        R: Read,
+    {
+        Self::from_reader_buffered(reader, &mut Vec::new())
+    }
+
+    /// Creates a request from the given reader, retaining excess bytes.
+    ///
+    /// This method is identical to [`Request::from_reader`], but starts with
+    /// the bytes in the given buffer, before reading from the reader, and puts
+    /// the bytes read beyond the end of the request back into the buffer. This
+    /// allows to read pipelined requests, i.e., requests that are sent on the
+    /// same connection without waiting for the response of the previous one.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Request::from_reader`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::Request;
+    ///
+    /// // Create reader with two pipelined requests
+    /// let mut reader = concat!(
+    ///     "POST /a HTTP/1.1\r\nContent-Length: 5\r\n\r\nHello",
+    ///     "GET /b HTTP/1.1\r\n\r\n",
+    /// ).as_bytes();
+    ///
+    /// // Create requests from reader
+    /// let mut buffer = Vec::new();
+    /// let req = Request::from_reader_buffered(&mut reader, &mut buffer)?;
+    /// assert_eq!(req.uri.path, "/a");
+    /// assert_eq!(req.body.as_ref(), b"Hello");
+    /// let req = Request::from_reader_buffered(&mut reader, &mut buffer)?;
+    /// assert_eq!(req.uri.path, "/b");
+    /// assert!(buffer.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn from_reader_buffered<R>(
+        reader: R, buffer: &mut Vec<u8>,
+    ) -> Result<Request<'static>>
+    where
+        R: Read,
+    {
+        Self::from_reader_buffered_with(reader, buffer, &Config::default())
+    }
+
+    /// Creates a request from the given reader with the given configuration,
+    /// retaining excess bytes.
+    ///
+    /// This method is identical to [`Request::from_reader_buffered`], but
+    /// additionally enforces the limits of the given [`Config`] while reading.
+    /// The maximum size of the head defaults to 8kb, unless configured, and
+    /// announced content lengths are checked before the body is read, while
+    /// chunked bodies are checked as each chunk is decoded.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`Request::from_reader`], this method
+    /// returns the errors of [`Request::from_bytes_with`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::{self, Config};
+    /// use zense::http::Request;
+    ///
+    /// // Create configuration with maximum head size
+    /// let config = Config::new()
+    ///     .max_header_bytes(64);
+    ///
+    /// // Create reader with oversized head
+    /// let cookie = "a".repeat(64);
+    /// let data = format!("GET / HTTP/1.1\r\nCookie: {cookie}\r\n\r\n");
+    ///
+    /// // Create request from reader
+    /// let mut buffer = Vec::new();
+    /// let res = Request::from_reader_buffered_with(
+    ///     data.as_bytes(), &mut buffer, &config,
+    /// );
+    /// assert!(matches!(res, Err(request::Error::HeadersTooLarge)));
+    ///
+    /// // Create configuration with maximum body length
+    /// let config = Config::new()
+    ///     .max_body_len(8);
+    ///
+    /// // Create reader with oversized chunked body
+    /// let data = concat!(
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n",
+    ///     "5\r\nHello\r\n6\r\n world\r\n0\r\n\r\n",
+    /// );
+    ///
+    /// // Create request from reader
+    /// let mut buffer = Vec::new();
+    /// let res = Request::from_reader_buffered_with(
+    ///     data.as_bytes(), &mut buffer, &config,
+    /// );
+    /// assert!(matches!(res, Err(request::Error::BodyTooLarge)));
+    ///
+    /// // Create reader with endless chunk extension
+    /// let head = "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n";
+    /// let ext = "a".repeat(16 * 1024);
+    /// let data = format!("{head}5;{ext}");
+    ///
+    /// // Create request from reader - rejected without maximum body length
+    /// let mut buffer = Vec::new();
+    /// let res = Request::from_reader_buffered_with(
+    ///     data.as_bytes(), &mut buffer, &Config::new(),
+    /// );
+    /// assert!(matches!(res, Err(request::Error::Security(_))));
+    ///
+    /// // Create reader with endless trailers
+    /// let trailer = format!("X-Padding: {}\r\n", "a".repeat(64)).repeat(256);
+    /// let data = format!("{head}0\r\n{trailer}");
+    ///
+    /// // Create request from reader - rejected without maximum body length
+    /// let mut buffer = Vec::new();
+    /// let res = Request::from_reader_buffered_with(
+    ///     data.as_bytes(), &mut buffer, &Config::new(),
+    /// );
+    /// assert!(matches!(res, Err(request::Error::HeadersTooLarge)));
+    ///
+    /// // Create reader with invalid content length, and a request in the body
+    /// let data = concat!(
+    ///     "POST / HTTP/1.1\r\nContent-Length: abc\r\n\r\n",
+    ///     "GET /smuggled HTTP/1.1\r\n\r\n",
+    /// );
+    ///
+    /// // Create request from reader
+    /// let mut buffer = Vec::new();
+    /// let res = Request::from_reader_buffered_with(
+    ///     data.as_bytes(), &mut buffer, &config,
+    /// );
+    /// assert!(matches!(res, Err(request::Error::Security(_))));
+    /// ```
+    pub fn from_reader_buffered_with<R>(
+        reader: R, buffer: &mut Vec<u8>, config: &Config,
+    ) -> Result<Request<'static>>
+    where
+        R: Read,
+    {
+        Self::from_reader_buffered_with_hook(reader, buffer, config, |_| Ok(()))
+    }
+
+    /// Creates a request from the given reader, retaining excess bytes, and
+    /// invokes the given function after the head is parsed.
+    ///
+    /// The function is invoked before the body is read, unless the request
+    /// doesn't have a body, or its announced length exceeds the maximum, in
+    /// which case the body is rejected right away. This allows the server to
+    /// answer "100 Continue" to clients that wait for it before sending the
+    /// body, but only when the body is going to be read.
+    pub(crate) fn from_reader_buffered_with_hook<R, F>(
+        mut reader: R, buffer: &mut Vec<u8>, config: &Config, hook: F,
+    ) -> Result<Request<'static>>
+    where
+        R: Read,
+        F: FnOnce(&Request) -> io::Result<()>,
+    {
+        let max = config.max_header_bytes.unwrap_or(reader::MAX_HEAD_SIZE);
+        let (mut head, n) =
+            reader::read_head(&mut reader, mem::take(buffer), max)?;
+        let data = head.split_off(n);
+
+        // Parse request head, which also performs all necessary validations,
+        // and convert it into an owned request, as it outlives the buffer
+        let mut req = Request::from_bytes_with(&head, config)?.into_owned();
+
+        // Determine how to read the body - the transfer encoding takes
+        // precedence, as both must not be present, which we checked
+        let encoding = req.headers.get(Header::TransferEncoding);
+        let (body, rest) = if let Some(value) = encoding {
+            check_transfer_encoding(value)?;
+
+            // Read and decode chunked body, and adjust headers, since the body
+            // is not chunked anymore after decoding
+            hook(&req)?;
+            let max = config.max_body_len;
+            let (body, rest) = reader::read_chunked(&mut reader, data, max)?;
+            let len = body.len().to_string();
+            req.headers.remove(Header::TransferEncoding);
+            req.headers.put(Header::ContentLength, len);
+            (body, rest)
+        } else {
+            let len = body_len(&req.headers)?;
+            if len > 0 && len <= config.max_body_len.unwrap_or(usize::MAX) {
+                hook(&req)?;
+            }
+            reader::read_body(&mut reader, data, len, config.max_body_len)?
+        };
+
+        // Retain excess bytes for the next request, and return request
+        *buffer = rest;
+        req.body = Cow::Owned(body);
+        Ok(req)
+    }
+
+    /// Converts the request into bytes.
+    ///
+    /// This is the serialization format for sending the request over a socket,
+    /// e.g., when using this crate as a client - the request line and headers
+    /// are followed by the raw body. If the request has a body, but doesn't
+    /// contain a [`Header::ContentLength`] header, it's computed from the body.
+    /// The request target is percent-encoded, as explained in [`Uri`], so
+    /// paths given to the builder never produce an invalid request line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::{Header, Method, Request};
+    ///
+    /// // Create request with space in path, and convert it into bytes
+    /// let req = Request::new()
+    ///     .uri("/path with spaces");
+    /// let bytes = req.to_bytes();
+    /// assert!(bytes.starts_with(b"GET /path%20with%20spaces HTTP/1.1\r\n"));
+    ///
+    /// // Create request with JSON body
+    /// let req = Request::new()
+    ///     .method(Method::Post)
+    ///     .uri("/coffee")
+    ///     .header(Header::ContentType, "application/json")
+    ///     .body(r#"{"milk":true}"#);
+    ///
+    /// // Convert request into bytes and parse them again
+    /// let bytes = req.to_bytes();
+    /// let req = Request::from_bytes(&bytes)?;
+    /// assert_eq!(req.method, Method::Post);
+    /// assert_eq!(req.uri.path, "/coffee");
+    /// assert_eq!(req.content_length(), Some(13));
+    /// assert_eq!(req.body.as_ref(), br#"{"milk":true}"#);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Compute an estimate for the request size, assuming an average size
+        // of 64 bytes for the request line and each header, see the response
+        let capacity = (self.headers.len() + 1) * 64 + self.body.len();
+        let mut buffer = Vec::with_capacity(capacity);
+
+        // Write request line and headers - writing to a vector is infallible,
+        // so we can safely ignore the results
+        let _ =
+            write!(buffer, "{} {} {}\r\n", self.method, self.uri, self.version);
+        let _ = write!(buffer, "{}", self.headers);
+
+        // Write content length, if not given, and the request has a body
+        let contains = self.headers.contains(Header::ContentLength);
+        if !contains && !self.body.is_empty() {
+            let name = Header::ContentLength.name();
+            let _ = write!(buffer, "{name}: {}\r\n", self.body.len());
+        }
+
+        // Write empty line and body
+        buffer.extend_from_slice(b"\r\n");
+        buffer.extend_from_slice(&self.body);
+        buffer
+    }
+
+    /// Returns the content length of the request.
+    ///
+    /// This method parses the [`Header::ContentLength`] header, and returns
+    /// [`None`] if the header is absent or doesn't contain a valid length.
+    /// Valid lengths consist of digits only, so signs are rejected, as when
+    /// reading the body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create request and add header
+    /// let req = Request::new()
+    ///     .header(Header::ContentLength, 11);
+    ///
+    /// // Obtain content length
+    /// assert_eq!(req.content_length(), Some(11));
+    ///
+    /// // Create request with signed content length
+    /// let req = Request::new()
+    ///     .header(Header::ContentLength, "+5");
+    ///
+    /// // Obtain content length - sign is rejected
+    /// assert_eq!(req.content_length(), None);
+    /// ```
+    #[must_use]
+    pub fn content_length(&self) -> Option<u64> {
+        let value = self.headers.get(Header::ContentLength)?.trim();
+        if !is_valid_length(value.as_bytes()) {
+            return None;
+        }
+        value.parse().ok()
+    }
+
+    /// Returns the content type of the request.
+    ///
+    /// This method splits the [`Header::ContentType`] header into the media
+    /// type and its parameters, e.g., `charset` or `boundary`, if any. Both
+    /// parts are trimmed, and [`None`] is returned if the header is absent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create request and add header
+    /// let req = Request::new()
+    ///     .header(Header::ContentType, "text/html; charset=utf-8");
+    ///
+    /// // Obtain media type and parameters
+    /// let (media, params) = req.content_type().unwrap();
+    /// assert_eq!(media, "text/html");
+    /// assert_eq!(params, Some("charset=utf-8"));
+    /// ```
+    #[must_use]
+    pub fn content_type(&self) -> Option<(&str, Option<&str>)> {
+        let value = self.headers.get(Header::ContentType)?;
+        match value.split_once(';') {
+            Some((media, params)) => Some((media.trim(), Some(params.trim()))),
+            None => Some((value.trim(), None)),
+        }
+    }
+
+    /// Returns an iterator over the parts of a multipart body.
+    ///
+    /// The boundary is obtained from the `boundary` parameter of the
+    /// [`Header::ContentType`] header, which must be `multipart/form-data`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::Multipart`], if the request doesn't have
+    /// a multipart body, or the boundary is missing or invalid. Errors while
+    /// parsing the parts are returned by the iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create request with multipart body
+    /// let req = Request::new()
+    ///     .header(Header::ContentType, "multipart/form-data; boundary=x")
+    ///     .body(concat!(
+    ///         "--x\r\n",
+    ///         "Content-Disposition: form-data; name=\"title\"\r\n",
+    ///         "\r\n",
+    ///         "Hello world\r\n",
+    ///         "--x\r\n",
+    ///         "Content-Disposition: form-data; name=\"file\"; ",
+    ///         "filename=\"hello.txt\"\r\n",
+    ///         "Content-Type: text/plain\r\n",
+    ///         "\r\n",
+    ///         "Hello\r\nworld\r\n",
+    ///         "--x--\r\n",
+    ///     ));
+    ///
+    /// // Obtain parts of multipart body
+    /// let parts = req.multipart()?.collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(parts.len(), 2);
+    ///
+    /// // Ensure text field was parsed
+    /// assert_eq!(parts[0].name, Some("title"));
+    /// assert_eq!(parts[0].body, b"Hello world");
+    ///
+    /// // Ensure file field was parsed
+    /// assert_eq!(parts[1].name, Some("file"));
+    /// assert_eq!(parts[1].filename, Some("hello.txt"));
+    /// assert_eq!(parts[1].content_type, Some("text/plain"));
+    /// assert_eq!(parts[1].body, b"Hello\r\nworld");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn multipart(&self) -> Result<Multipart<'_>> {
+        let params = match self.content_type() {
+            Some((media, Some(params)))
+                if media.eq_ignore_ascii_case("multipart/form-data") =>
+            {
+                params
+            }
+            _ => return Err(Error::Multipart("invalid content type")),
+        };
+
+        // Extract boundary from parameters and remove quotes, if any
+        let boundary = params
+            .split(';')
+            .filter_map(|param| param.split_once('='))
+            .find_map(|(key, value)| {
+                (key.trim() == "boundary")
+                    .then(|| value.trim().trim_matches('"'))
+            })
+            .ok_or(Error::Multipart("missing boundary"))?;
+
+        // Create multipart body
+        Multipart::new(&self.body, boundary)
+    }
+
+    /// Returns whether the client expects a "100 Continue" interim response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create request and add header
+    /// let req = Request::new()
+    ///     .header(Header::Expect, "100-continue");
+    ///
+    /// // Ensure client expects interim response
+    /// assert!(req.expects_continue());
+    /// ```
+    #[must_use]
+    pub fn expects_continue(&self) -> bool {
+        self.headers.get(Header::Expect).is_some_and(|value| {
+            value.trim().eq_ignore_ascii_case("100-continue")
+        })
+    }
+
+    /// Returns whether the connection should be kept alive.
+    ///
+    /// Connections are persistent by default in HTTP/1.1, which is why this
+    /// method returns `true`, unless the [`Header::Connection`] header lists
+    /// the `close` option. In HTTP/1.0, connections are closed by default,
+    /// unless the `keep-alive` option is listed. Options are compared
+    /// case-insensitively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Request, Version};
+    ///
+    /// // Create request and check default
+    /// let req = Request::new();
+    /// assert!(req.keep_alive());
+    ///
+    /// // Create request with explicit keep-alive
+    /// let req = Request::new()
+    ///     .header(Header::Connection, "keep-alive");
+    /// assert!(req.keep_alive());
+    ///
+    /// // Create request with explicit close
+    /// let req = Request::new()
+    ///     .header(Header::Connection, "Close");
+    /// assert!(!req.keep_alive());
+    ///
+    /// // Create request with HTTP/1.0
+    /// let req = Request::new()
+    ///     .version(Version::Http10);
+    /// assert!(!req.keep_alive());
+    ///
+    /// // Create request with HTTP/1.0 and explicit keep-alive
+    /// let req = Request::new()
+    ///     .version(Version::Http10)
+    ///     .header(Header::Connection, "Keep-Alive");
+    /// assert!(req.keep_alive());
+    /// ```
+    #[must_use]
+    pub fn keep_alive(&self) -> bool {
+        match self.version {
+            Version::Http10 => self
+                .headers
+                .contains_token(Header::Connection, "keep-alive"),
+            Version::Http11 => {
+                !self.headers.contains_token(Header::Connection, "close")
+            }
+        }
+    }
+
+    /// Returns the protocol the client requests to upgrade to, if any.
+    ///
+    /// Clients request an upgrade by listing the `upgrade` option in the
+    /// [`Header::Connection`] header, compared case-insensitively, and the
+    /// protocols in the [`Header::Upgrade`] header, in order of preference, of
+    /// which the first is returned. If no upgrade is requested, [`None`] is
+    /// returned. See [`ResponseExt::switching_protocols`][] for the response.
+    ///
+    /// [`ResponseExt::switching_protocols`]: crate::http::response::ResponseExt::switching_protocols
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create request for upgrade
+    /// let req = Request::new()
+    ///     .header(Header::Connection, "Upgrade, HTTP2-Settings")
+    ///     .header(Header::Upgrade, "h2c");
+    /// assert_eq!(req.upgrade_target(), Some("h2c"));
+    ///
+    /// // Create request without upgrade option
+    /// let req = Request::new()
+    ///     .header(Header::Upgrade, "h2c");
+    /// assert_eq!(req.upgrade_target(), None);
+    /// ```
+    #[must_use]
+    pub fn upgrade_target(&self) -> Option<&str> {
+        if !self.headers.contains_token(Header::Connection, "upgrade") {
+            return None;
+        }
+
+        // Return first protocol, if any
+        let value = self.headers.get(Header::Upgrade)?;
+        let protocol = value.split(',').next().map(str::trim);
+        protocol.filter(|protocol| !protocol.is_empty())
+    }
+
+    /// Returns the media ranges the client accepts.
+    ///
+    /// The media ranges are parsed from the [`Header::Accept`] header, and
+    /// ordered by preference, which is why the first media range is the most
+    /// preferred one. See [`accept::parse`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create request and add header
+    /// let req = Request::new()
+    ///     .header(Header::Accept, "text/html;q=0.9, application/json");
+    ///
+    /// // Obtain media ranges
+    /// let ranges = req.accepts();
+    /// assert_eq!(ranges[0].to_string(), "application/json");
+    /// assert_eq!(ranges[1].to_string(), "text/html;q=0.9");
+    /// ```
+    #[must_use]
+    pub fn accepts(&self) -> Vec<MediaRange<'_>> {
+        self.headers
+            .get(Header::Accept)
+            .map(accept::parse)
+            .unwrap_or_default()
+    }
+
+    /// Returns the best match among the given media types.
+    ///
+    /// If the client didn't send an [`Header::Accept`] header, it's assumed to
+    /// accept any media type, so the first of the given media types is chosen.
+    /// See [`accept::best_match`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create request and add header
+    /// let req = Request::new()
+    ///     .header(Header::Accept, "text/*;q=0.5, application/json");
+    ///
+    /// // Obtain best match
+    /// let media = req.best_match(&["text/html", "application/json"]);
+    /// assert_eq!(media, Some("application/json"));
+    /// ```
+    #[must_use]
+    pub fn best_match<'m>(&self, available: &[&'m str]) -> Option<&'m str> {
+        match self.headers.get(Header::Accept) {
+            Some(value) => accept::best_match(&accept::parse(value), available),
+            None => available.first().copied(),
+        }
+    }
+
+    /// Returns the best content coding among the given coding names.
+    ///
+    /// If the client didn't send an [`Header::AcceptEncoding`] header, it's
+    /// assumed to accept any coding, so the first of the given codings is
+    /// chosen. See [`encoding_negotiation::best_encoding`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create request and add header
+    /// let req = Request::new()
+    ///     .header(Header::AcceptEncoding, "gzip;q=0.5, br, identity;q=0");
+    ///
+    /// // Obtain best coding
+    /// let coding = req.best_encoding(&["gzip", "br", "identity"]);
+    /// assert_eq!(coding, Some("br"));
+    /// ```
+    #[must_use]
+    pub fn best_encoding<'m>(&self, available: &[&'m str]) -> Option<&'m str> {
+        match self.headers.get(Header::AcceptEncoding) {
+            Some(value) => {
+                let codings = encoding_negotiation::parse(value);
+                encoding_negotiation::best_encoding(&codings, available)
+            }
+            None => available.first().copied(),
+        }
+    }
+
+    /// Returns the forwarded elements of the request.
+    ///
+    /// This method parses the [`Header::Forwarded`] header, as explained in
+    /// [`forwarded::parse`][], and returns an empty vector if it's absent.
+    /// Note that the header can be sent by any client, so it should only be
+    /// trusted if the request was received from a trusted proxy.
+    ///
+    /// [`forwarded::parse`]: crate::http::forwarded::parse
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Request};
     ///
-    /// // Create request
-    /// let req = Request::new();
+    /// // Create request and add header
+    /// let value = "for=192.0.2.60;proto=http;by=203.0.113.43";
+    /// let req = Request::new()
+    ///     .header(Header::Forwarded, value);
+    ///
+    /// // Obtain forwarded elements
+    /// let elements = req.forwarded();
+    /// assert_eq!(elements[0].r#for, Some("192.0.2.60"));
+    /// assert_eq!(elements[0].proto, Some("http"));
     /// ```
-    #[inline]
     #[must_use]
-    pub fn new() -> Self {
-        Self::default()
+    pub fn forwarded(&self) -> Vec<ForwardedElement<'_>> {
+        let value = self.headers.get(Header::Forwarded);
+        value.map(forwarded::parse).unwrap_or_default()
     }
 
-    /// Creates a request from the given bytes.
+    /// Returns whether the request was originally made via HTTPS.
     ///
-    /// HTTP requests are parsed using the [`httparse`] crate, which is one of
-    /// the few dependencies that we rely on as it provides an efficient, fast,
-    /// and well-tested parser. The returned [`Request`] will be bound to the
-    /// lifetime of the input, avoiding allocations where possible.
+    /// TLS is terminated in front of this crate, e.g., by a reverse proxy, so
+    /// the original scheme can only be determined from the header set by the
+    /// proxy, which is why it must be explicitly trusted. Depending on the
+    /// given [`Trust`], either the protocol of the last [`Header::Forwarded`]
+    /// element, or the last value of the [`Header::XForwardedProto`] header is
+    /// checked, and the other header is ignored, as it might be forged by the
+    /// client. If no header is trusted, `false` is returned.
     ///
-    /// After parsing, basic validation is performed on the request path to
-    /// ensure it doesn't exceed 4kb in size and doesn't attempt traversal.
+    /// Like in [`Request::client_ip`], the last value is used, as each proxy
+    /// appends to the headers, so the last value was set by the proxy in front
+    /// of this crate, while all other values might be forged by the client.
     ///
-    /// # Errors
+    /// # Examples
     ///
-    /// This method returns [`Error::Incomplete`], if the given buffer contained
-    /// insufficient data to provide a meaningful answer, [`Error::Parser`], if
-    /// the buffer contained invalid data, and [`Error::Component`], when the
-    /// parsed request contains an invalid [`Method`] or [`Header`].
+    /// ```
+    /// use zense::http::forwarded::Trust;
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create request and add header
+    /// let req = Request::new()
+    ///     .header(Header::XForwardedProto, "https");
+    ///
+    /// // Check whether request was made via HTTPS
+    /// assert!(req.is_secure(Trust::XForwardedProto));
+    /// assert!(!req.is_secure(Trust::Forwarded));
+    /// assert!(!req.is_secure(Trust::None));
+    ///
+    /// // Create request with forwarded element
+    /// let req = Request::new()
+    ///     .header(Header::Forwarded, "for=192.0.2.60;proto=https");
+    /// assert!(req.is_secure(Trust::Forwarded));
+    /// assert!(!req.is_secure(Trust::XForwardedProto));
+    ///
+    /// // Create request with forged value, followed by the proxy's value
+    /// let req = Request::new()
+    ///     .header(Header::XForwardedProto, "https, http");
+    /// assert!(!req.is_secure(Trust::XForwardedProto));
+    /// ```
+    #[must_use]
+    pub fn is_secure(&self, trust: Trust) -> bool {
+        // Obtain protocol from the trusted header, using the last value, which
+        // was added by the closest proxy
+        let proto = match trust {
+            Trust::None => None,
+            Trust::Forwarded => {
+                self.forwarded().last().and_then(|element| element.proto)
+            }
+            Trust::XForwardedProto => {
+                let value = self.headers.get(Header::XForwardedProto);
+                value.and_then(|value| value.rsplit(',').next().map(str::trim))
+            }
+        };
+
+        // Check whether protocol is HTTPS
+        proto.is_some_and(|proto| proto.eq_ignore_ascii_case("https"))
+    }
+
+    /// Returns the IP address of the client.
+    ///
+    /// Behind proxies, the peer of the request is the last proxy, and not the
+    /// client, which is why proxies announce the addresses they forward for in
+    /// the [`Header::XForwardedFor`] or [`Header::XRealIp`] header. However,
+    /// those headers are only trusted if the peer is one of the given trusted
+    /// proxies, as clients can send arbitrary values to spoof their address.
+    ///
+    /// Each proxy appends the address it received the request from, so the
+    /// chain is walked from right to left, skipping trusted proxies, and the
+    /// first address that isn't trusted is returned, since all addresses left
+    /// of it might have been spoofed. If all addresses are trusted, the left-
+    /// most address is returned. If the peer isn't set, [`None`] is returned.
+    ///
+    /// If an address that is reached during the walk can't be parsed, [`None`]
+    /// is returned, as the client can't be determined - falling back to the
+    /// peer would identify all clients as the proxy. Trusted peers that don't
+    /// send any forwarded addresses are considered to be the client.
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// use zense::http::{Method, Request};
+    /// use std::net::IpAddr;
+    /// use zense::http::{Header, Request};
     ///
-    /// // Create request from bytes
-    /// let req = Request::from_bytes(b"GET / HTTP/1.1\r\n\r\n")?;
-    /// assert_eq!(req.method, Method::Get);
-    /// assert_eq!(req.uri.path, "/");
+    /// // Create list of trusted proxies
+    /// let proxy: IpAddr = "10.0.0.1".parse()?;
+    /// let trusted = [proxy];
+    ///
+    /// // Create request with forwarded address from trusted proxy
+    /// let req = Request::new()
+    ///     .header(Header::XForwardedFor, "203.0.113.7")
+    ///     .peer(proxy);
+    /// assert_eq!(req.client_ip(&trusted), Some("203.0.113.7".parse()?));
+    ///
+    /// // Create request with spoofed chain, passed through trusted proxies
+    /// let req = Request::new()
+    ///     .header(Header::XForwardedFor, "1.2.3.4, 203.0.113.7, 10.0.0.1")
+    ///     .peer(proxy);
+    /// assert_eq!(req.client_ip(&trusted), Some("203.0.113.7".parse()?));
+    ///
+    /// // Create request with forwarded address from untrusted peer
+    /// let req = Request::new()
+    ///     .header(Header::XForwardedFor, "1.2.3.4")
+    ///     .peer("198.51.100.1".parse()?);
+    /// assert_eq!(req.client_ip(&trusted), Some("198.51.100.1".parse()?));
+    ///
+    /// // Create request with invalid forwarded address from trusted proxy
+    /// let req = Request::new()
+    ///     .header(Header::XForwardedFor, "203.0.113.7, unknown")
+    ///     .peer(proxy);
+    /// assert_eq!(req.client_ip(&trusted), None);
     /// # Ok(())
     /// # }
     /// ```
-    #[allow(clippy::missing_panics_doc)]
-    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
-        let mut headers = [httparse::EMPTY_HEADER; 64];
-        let mut req = httparse::Request::new(&mut headers);
-
-        // Parse request using the `httparse` crate, and create a new request
-        // from the parsed data. Note that we only use the `httparse` crate and
-        // not the `http` crate, as the later provides a rather inconvenient
-        // interface for writing middlewares comfortably.
-        match req.parse(bytes).map_err(Error::from)? {
-            Status::Partial => Err(Error::Incomplete),
-            Status::Complete(n) => {
-                let body = Cow::Borrowed(&bytes[n..]);
-
-                // Unpack request method and URI - if parsing succeeded, we can
-                // be confident that method and path, both options, must exist
-                let method = req.method.expect("invariant").parse()?;
-                let uri = Uri::from(req.path.expect("invariant"));
-
-                // Unpack request headers - ignore header parsing errors and
-                // unknown headers, as it doesn't matter for request handling
-                let iter = req.headers.iter();
-                let headers = iter
-                    .take_while(|header| !header.name.is_empty())
-                    .filter_map(|header| {
-                        str::from_utf8(header.value).ok().and_then(|value| {
-                            Header::from_str(header.name)
-                                .map(|name| (name, value))
-                                .ok()
-                        })
-                    })
-                    .collect();
-
-                // Ensure request path doesn't exceed 4kb - most web servers
-                // allow up to 4-8kb, so 4kb should be more than enough for us
-                if uri.path.len() > 4 * 1024 {
-                    return Err(Error::Security("exceeds size of 4kb"));
-                }
+    #[must_use]
+    pub fn client_ip(&self, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+        let peer = self.peer?;
+        if !trusted_proxies.contains(&peer) {
+            return Some(peer);
+        }
 
-                // Ensure request path doesn't attempt traversal - a quick and
-                // dirty check, and yes, there might be false positives
-                if uri.path.contains("..") {
-                    return Err(Error::Security("path traversal"));
-                }
+        // Obtain forwarded addresses, preferring the standard header
+        let Some(value) = self
+            .headers
+            .get(Header::XForwardedFor)
+            .or_else(|| self.headers.get(Header::XRealIp))
+        else {
+            return Some(peer);
+        };
 
-                // Return request
-                Ok(Request { method, uri, headers, body })
+        // Walk chain from right to left, and return the first address that is
+        // not trusted - invalid addresses end the walk without a result
+        let mut client = peer;
+        for value in value.rsplit(',') {
+            client = value.trim().parse().ok()?;
+            if !trusted_proxies.contains(&client) {
+                break;
             }
         }
+
+        // Return client address
+        Some(client)
+    }
+
+    /// Returns the path relative to the base path the request is mounted at.
+    ///
+    /// When a [`Router`][] with a base path is mounted, e.g., at `/api`, the
+    /// prefix matched by the base path is stripped, similar to `PATH_INFO` in
+    /// CGI and WSGI, so mounted handlers can route on the relative path. The
+    /// relative path always starts with `/`, and if the request isn't mounted,
+    /// or its path was rewritten to a path outside of the base path, the full
+    /// path is returned.
+    ///
+    /// [`Router`]: crate::router::Router
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::{Handler, TryIntoHandler};
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Request, Response};
+    /// use zense::router::Router;
+    ///
+    /// // Create router with handler mounted at base path
+    /// let router = Router::new("/api")
+    ///     .with(|req: Request, _: &dyn Handler| {
+    ///         Response::text(req.relative_path().to_string())
+    ///     })
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle requests with relative paths
+    /// let res = router.handle(Request::new().uri("/api/users"));
+    /// assert_eq!(res.body, b"/users");
+    /// let res = router.handle(Request::new().uri("/api"));
+    /// assert_eq!(res.body, b"/");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// When the path is rewritten after the base path was matched, the relative
+    /// path is computed from the rewritten path:
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::{Handler, TryIntoHandler};
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Request, Response};
+    /// use zense::middleware::rewrite::Rewrite;
+    /// use zense::router::Router;
+    ///
+    /// // Create router with rewrites and handler mounted at base path
+    /// let router = Router::new("/api")
+    ///     .with(Rewrite::new()
+    ///         .internal("/old", "/api/new")
+    ///         .internal("/moved", "/foo/bar"))
+    ///     .with(|req: Request, _: &dyn Handler| {
+    ///         Response::text(req.relative_path().to_string())
+    ///     })
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle requests with rewritten paths
+    /// let res = router.handle(Request::new().uri("/api/old"));
+    /// assert_eq!(res.body, b"/new");
+    /// let res = router.handle(Request::new().uri("/api/moved"));
+    /// assert_eq!(res.body, b"/foo/bar");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn relative_path(&self) -> &str {
+        let path = self.uri.path.as_ref();
+        match path.get(self.mount..) {
+            Some("") => "/",
+            Some(rest) if rest.starts_with('/') => rest,
+            _ => path,
+        }
     }
 }
 
@@ -216,8 +1376,87 @@ impl<'a> Request<'a> {
         self
     }
 
+    /// Sets the version of the request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Request, Version};
+    ///
+    /// // Create request and set version
+    /// let req = Request::new()
+    ///     .version(Version::Http10);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn version(mut self, version: Version) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Adds a query string parameter to the URI of the request.
+    ///
+    /// Parameters are stored decoded, and percent-encoded when the URI of the
+    /// request is formatted, so the given key and value must not be encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Request;
+    ///
+    /// // Create request and add query string parameters
+    /// let req = Request::new()
+    ///     .uri("/search")
+    ///     .query("q", "hello world")
+    ///     .query("page", "2");
+    ///
+    /// // Obtain string representation
+    /// assert_eq!(req.uri.to_string(), "/search?q=hello%20world&page=2");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn query<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        self.uri.query.add(key, value);
+        self
+    }
+
+    /// Adds multiple query string parameters to the URI of the request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Request;
+    ///
+    /// // Create request and add query string parameters
+    /// let req = Request::new()
+    ///     .uri("/search")
+    ///     .query_pairs([("q", "hello world"), ("page", "2")]);
+    ///
+    /// // Obtain string representation
+    /// assert_eq!(req.uri.to_string(), "/search?q=hello%20world&page=2");
+    /// ```
+    #[must_use]
+    pub fn query_pairs<T, K, V>(mut self, iter: T) -> Self
+    where
+        T: IntoIterator<Item = (K, V)>,
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        for (key, value) in iter {
+            self.uri.query.add(key, value);
+        }
+        self
+    }
+
     /// Adds a header to the request.
     ///
+    /// Carriage returns, line feeds and null characters are removed from the
+    /// value, so they can't be used to inject headers when serializing.
+    ///
     /// # Examples
     ///
     /// ```
@@ -227,6 +1466,24 @@ impl<'a> Request<'a> {
     /// let req = Request::new()
     ///     .header(Header::Accept, "text/plain");
     /// ```
+    ///
+    /// Values containing line breaks don't inject headers:
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create request and add header with line break
+    /// let req = Request::new()
+    ///     .header(Header::Accept, "text/plain\r\nSet-Cookie: evil");
+    ///
+    /// // Ensure no header was injected
+    /// let head = String::from_utf8(req.to_bytes())?;
+    /// assert!(!head.contains("\r\nSet-Cookie"));
+    /// # Ok(())
+    /// # }
+    /// ```
     #[allow(clippy::needless_pass_by_value)]
     #[inline]
     #[must_use]
@@ -258,6 +1515,72 @@ impl<'a> Request<'a> {
         self.body = Cow::Owned(body.into());
         self
     }
+
+    /// Sets the peer of the request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::{IpAddr, Ipv4Addr};
+    /// use zense::http::Request;
+    ///
+    /// // Create request and set peer
+    /// let req = Request::new()
+    ///     .peer(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn peer(mut self, peer: IpAddr) -> Self {
+        self.peer = Some(peer);
+        self
+    }
+}
+
+impl Request<'_> {
+    /// Converts the request into an owned request.
+    ///
+    /// Requests parsed with [`Request::from_bytes`] borrow from the input,
+    /// which is great for efficiency, but makes it impossible to store them
+    /// beyond the lifetime of the input, e.g., to move them between threads.
+    /// This method copies all borrowed data, returning a `'static` request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::thread;
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create buffer
+    /// let mut bytes = b"GET /coffee HTTP/1.1\r\n".to_vec();
+    /// bytes.extend_from_slice(b"Accept: text/plain\r\n\r\n");
+    ///
+    /// // Create request from bytes and convert into owned request
+    /// let req = Request::from_bytes(&bytes)?.into_owned();
+    /// drop(bytes);
+    ///
+    /// // Move request to another thread
+    /// let handle = thread::spawn(move || {
+    ///     assert_eq!(req.uri.path, "/coffee");
+    ///     assert_eq!(req.headers.get(Header::Accept), Some("text/plain"));
+    /// });
+    /// # handle.join().unwrap();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn into_owned(self) -> Request<'static> {
+        Request {
+            method: self.method,
+            uri: self.uri.into_owned(),
+            version: self.version,
+            headers: self.headers.into_owned(),
+            body: Cow::Owned(self.body.into_owned()),
+            peer: self.peer,
+            mount: self.mount,
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -280,19 +1603,268 @@ impl Default for Request<'_> {
         Self {
             method: Method::Get,
             uri: Uri::default(),
+            version: Version::default(),
             headers: Headers::default(),
             body: Cow::Borrowed(&[]),
+            peer: None,
+            mount: 0,
         }
     }
 }
 
 // ----------------------------------------------------------------------------
 
+impl<'a, U> From<(Method, U)> for Request<'a>
+where
+    U: Into<Uri<'a>>,
+{
+    /// Creates a request from a method and URI.
+    ///
+    /// This is a convenient shortcut for creating requests in tests, and can
+    /// be combined with all other builder methods, e.g., to set a body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Method, Request};
+    ///
+    /// // Create request from method and URI
+    /// let req = Request::from((Method::Post, "/coffee?kind=espresso"))
+    ///     .header(Header::ContentType, "text/plain")
+    ///     .body("Hello world");
+    ///
+    /// // Ensure method and URI are set
+    /// assert_eq!(req.method, Method::Post);
+    /// assert_eq!(req.uri.path, "/coffee");
+    /// assert_eq!(req.uri.query.get("kind"), Some("espresso"));
+    /// ```
+    #[inline]
+    fn from((method, uri): (Method, U)) -> Self {
+        Self::new().method(method).uri(uri)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl fmt::Debug for Request<'_> {
+    /// Formats the request for debugging, redacting sensitive headers.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Request")
+            .field("method", &self.method)
+            .field("uri", &self.uri)
+            .field("version", &self.version)
+            .field("headers", &self.headers.redacted(&Header::SENSITIVE))
+            .field("body", &self.body)
+            .field("peer", &self.peer)
+            .field("mount", &self.mount)
+            .finish()
+    }
+}
+
 impl fmt::Display for Request<'_> {
-    /// Formats the response for display.
+    /// Formats the request for display, redacting sensitive headers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Request};
+    ///
+    /// // Create request with credentials
+    /// let req = Request::new()
+    ///     .header(Header::Authorization, "Bearer secret")
+    ///     .header(Header::Accept, "text/plain");
+    ///
+    /// // Ensure credentials are redacted
+    /// let value = req.to_string();
+    /// assert!(value.contains("Authorization: <redacted>\r\n"));
+    /// assert!(value.contains("Accept: text/plain\r\n"));
+    /// assert!(!value.contains("secret"));
+    ///
+    /// // Ensure credentials are redacted for debugging
+    /// assert!(!format!("{req:?}").contains("secret"));
+    /// ```
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {} HTTP/1.1\r\n", self.method, self.uri)?;
-        write!(f, "{}\r\n", self.headers)?;
+        let headers = self.headers.redacted(&Header::SENSITIVE);
+        write!(f, "{} {} {}\r\n", self.method, self.uri, self.version)?;
+        write!(f, "{headers}\r\n")?;
         write!(f, "[Body: {} bytes]\r\n", self.body.len())
     }
 }
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Parses a request from the given bytes, returning the length of the head.
+///
+/// All bytes after the head are treated as the body, which the caller might
+/// limit according to the framing of the request.
+fn parse<'a>(bytes: &'a [u8], config: &Config) -> Result<(Request<'a>, usize)> {
+    let mut headers = [httparse::EMPTY_HEADER; 64];
+    let mut req = httparse::Request::new(&mut headers);
+
+    // Parse request using the `httparse` crate, and create a new request
+    // from the parsed data. Note that we only use the `httparse` crate and
+    // not the `http` crate, as the later provides a rather inconvenient
+    // interface for writing middlewares comfortably.
+    //
+    // If the size of the head is limited, only the allowed number of bytes is
+    // parsed, so parsing stops early when the head is excessively large
+    let max = config.max_header_bytes.unwrap_or(usize::MAX);
+    match req
+        .parse(&bytes[..bytes.len().min(max)])
+        .map_err(Error::from)?
+    {
+        Status::Partial if bytes.len() > max => Err(Error::HeadersTooLarge),
+        Status::Partial => Err(Error::Incomplete),
+        Status::Complete(n) => {
+            let body = &bytes[n..];
+
+            // Unpack request method and URI - if parsing succeeded, we can
+            // be confident that method and path, both options, must exist
+            let method = req.method.expect("invariant").parse()?;
+            let uri = Uri::from(req.path.expect("invariant"));
+            let version = match req.version.expect("invariant") {
+                0 => Version::Http10,
+                _ => Version::Http11,
+            };
+
+            // Unpack request headers - ignore header parsing errors and
+            // unknown headers, as it doesn't matter for request handling
+            let iter = req.headers.iter();
+            let headers: Headers = iter
+                .take_while(|header| !header.name.is_empty())
+                .filter_map(|header| {
+                    let name = Header::from_bytes(header.name.as_bytes());
+                    let value = str::from_utf8(header.value)
+                        .map(|value| value.trim_matches([' ', '\t']));
+                    name.ok().zip(value.ok())
+                })
+                .collect();
+
+            // Ensure request can't be smuggled through ambiguous framing
+            check_framing(req.headers)?;
+
+            // Ensure request path doesn't exceed 4kb - most web servers
+            // allow up to 4-8kb, so 4kb should be more than enough for us
+            if uri.path.len() > 4 * 1024 {
+                return Err(Error::Security("exceeds size of 4kb"));
+            }
+
+            // Ensure request target is only in asterisk-form for requests
+            // with the `OPTIONS` method, which is the only one allowing it
+            if uri.is_asterisk() && method != Method::Options {
+                return Err(Error::Security("invalid asterisk-form"));
+            }
+
+            // Ensure request path doesn't attempt traversal - a quick and
+            // dirty check, and yes, there might be false positives
+            if uri.path.contains("..") {
+                return Err(Error::Security("path traversal"));
+            }
+
+            // Ensure request body doesn't exceed the configured maximum
+            // length, checking both the announced and the actual length
+            if let Some(max) = config.max_body_len {
+                let value = headers.get(Header::ContentLength);
+                let len = value.and_then(|value| value.trim().parse().ok());
+                if len.unwrap_or(0).max(body.len()) > max {
+                    return Err(Error::BodyTooLarge);
+                }
+            }
+
+            // Return request and length of head
+            let req = Request {
+                method,
+                uri,
+                version,
+                headers,
+                body: Cow::Borrowed(body),
+                peer: None,
+                mount: 0,
+            };
+            Ok((req, n))
+        }
+    }
+}
+
+/// Ensures that the framing of the request body is unambiguous.
+///
+/// Both [`Header::ContentLength`] and [`Header::TransferEncoding`] must not be
+/// present, content lengths must only consist of digits and fit into a usize,
+/// and multiple content lengths must be identical, as proxies might otherwise
+/// disagree on where the request ends, allowing request smuggling. Transfer
+/// encodings must be sent in a single line, as only the last line is retained
+/// in the header map, so codings of earlier lines would be silently dropped.
+/// Names are compared directly, as looking up each header is comparatively
+/// expensive, and this function is called for every parsed request.
+fn check_framing(headers: &[httparse::Header]) -> Result {
+    let content_length_name = Header::ContentLength.name();
+    let transfer_encoding_name = Header::TransferEncoding.name();
+
+    // Collect content length, and check for transfer encoding
+    let mut content_length = None;
+    let mut transfer_encoding = false;
+    for header in headers {
+        if header.name.eq_ignore_ascii_case(content_length_name) {
+            let value = header.value.trim_ascii();
+            if !is_valid_length(value) {
+                return Err(Error::Security("invalid content length"));
+            }
+            if content_length.is_some_and(|prev| prev != value) {
+                return Err(Error::Security("conflicting content length"));
+            }
+            content_length = Some(value);
+        } else if header.name.eq_ignore_ascii_case(transfer_encoding_name) {
+            if transfer_encoding {
+                return Err(Error::Security("multiple transfer encodings"));
+            }
+            transfer_encoding = true;
+        }
+    }
+
+    // Ensure content length and transfer encoding don't coexist
+    if content_length.is_some() && transfer_encoding {
+        Err(Error::Security("content length with transfer encoding"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Ensures that the given transfer encoding is supported.
+///
+/// Only the chunked transfer coding is supported, as other codings, e.g.,
+/// `gzip`, would need to be retained when the chunks are decoded, which is
+/// why requests using them are rejected, instead of silently altering them.
+fn check_transfer_encoding(value: &str) -> Result {
+    if value.trim().eq_ignore_ascii_case("chunked") {
+        Ok(())
+    } else {
+        Err(Error::Security("unsupported transfer encoding"))
+    }
+}
+
+/// Returns the length of the body, as announced by the content length.
+///
+/// Requests without a [`Header::ContentLength`] header don't have a body, but
+/// invalid content lengths are rejected, and never treated as zero, as the
+/// body would otherwise be interpreted as the next request.
+fn body_len(headers: &Headers) -> Result<usize> {
+    let Some(value) = headers.get(Header::ContentLength) else {
+        return Ok(0);
+    };
+
+    // Ensure content length is a decimal number fitting into a usize
+    let len = value.parse().ok();
+    len.filter(|_| is_valid_length(value.as_bytes()))
+        .ok_or(Error::Security("invalid content length"))
+}
+
+/// Returns whether the given content length is a decimal number fitting into
+/// a usize - signs, which [`str::parse`] would accept, are not allowed.
+fn is_valid_length(value: &[u8]) -> bool {
+    !value.is_empty()
+        && value.iter().all(u8::is_ascii_digit)
+        && str::from_utf8(value)
+            .is_ok_and(|value| value.parse::<usize>().is_ok())
+}
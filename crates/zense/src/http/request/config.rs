@@ -0,0 +1,119 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! HTTP request parser configuration.
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// HTTP request parser configuration.
+///
+/// The configuration is passed to [`Request::from_bytes_with`][], and allows
+/// to enforce limits during parsing, before the request is constructed, as a
+/// defense in depth, complementing the limits enforced by middlewares. By
+/// default, no limits are enforced, which is what [`Request::from_bytes`][]
+/// uses.
+///
+/// [`Request::from_bytes`]: crate::http::Request::from_bytes
+/// [`Request::from_bytes_with`]: crate::http::Request::from_bytes_with
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::request::Config;
+///
+/// // Create configuration
+/// let config = Config::new()
+///     .max_body_len(1024 * 1024);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// Maximum body length in bytes.
+    pub(crate) max_body_len: Option<usize>,
+    /// Maximum head size in bytes.
+    pub(crate) max_header_bytes: Option<usize>,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Config {
+    /// Creates a configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::Config;
+    ///
+    /// // Create configuration
+    /// let config = Config::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum body length in bytes.
+    ///
+    /// Both the [`Header::ContentLength`][] header and the number of bytes
+    /// following the request head are checked against the limit.
+    ///
+    /// [`Header::ContentLength`]: crate::http::Header::ContentLength
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::Config;
+    ///
+    /// // Create configuration with maximum body length
+    /// let config = Config::new()
+    ///     .max_body_len(1024 * 1024);
+    /// ```
+    #[must_use]
+    pub fn max_body_len(mut self, max_body_len: usize) -> Self {
+        self.max_body_len = Some(max_body_len);
+        self
+    }
+
+    /// Sets the maximum size of the request head in bytes.
+    ///
+    /// The request head consists of the request line and the header fields,
+    /// and parsing stops as soon as the limit is exceeded, which bounds the
+    /// memory and work spent on a request with an excessive head.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::Config;
+    ///
+    /// // Create configuration with maximum head size
+    /// let config = Config::new()
+    ///     .max_header_bytes(4 * 1024);
+    /// ```
+    #[must_use]
+    pub fn max_header_bytes(mut self, max_header_bytes: usize) -> Self {
+        self.max_header_bytes = Some(max_header_bytes);
+        self
+    }
+}
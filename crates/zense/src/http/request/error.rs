@@ -22,7 +22,7 @@
 
 //! HTTP request error.
 
-use std::result;
+use std::{io, result};
 use thiserror::Error;
 
 use crate::http::component;
@@ -42,13 +42,33 @@ pub enum Error {
     #[error(transparent)]
     Component(#[from] component::Error),
 
+    /// I/O error.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
     /// HTTP request incomplete.
     #[error("request incomplete")]
     Incomplete,
 
+    /// HTTP multipart body invalid.
+    #[error("invalid multipart body: {0}")]
+    Multipart(&'static str),
+
+    /// HTTP request body too large.
+    #[error("request body too large")]
+    BodyTooLarge,
+
+    /// HTTP request header fields too large.
+    #[error("request header fields too large")]
+    HeadersTooLarge,
+
     /// HTTP request rejected.
     #[error("request rejected: {0}")]
     Security(&'static str),
+
+    /// HTTP query string invalid.
+    #[error("invalid query: {0}")]
+    Query(String),
 }
 
 // ----------------------------------------------------------------------------
@@ -23,9 +23,11 @@
 //! HTTP request headers.
 
 use std::borrow::Cow;
+use std::collections::btree_map::{Entry, Iter};
 use std::collections::BTreeMap;
 use std::fmt;
 
+use crate::http::component::sanitize;
 use crate::http::Header;
 
 // ----------------------------------------------------------------------------
@@ -40,7 +42,12 @@ use crate::http::Header;
 /// allocations to the case where headers are added or modified.
 ///
 /// As keys are integers, it's better to use a [`BTreeMap`] than a [`HashMap`],
-/// because the latter is 3x slower for integer keys.
+/// because the latter is 3x slower for integer keys. Note that an empty map
+/// doesn't allocate, as the [`BTreeMap`] only allocates its first node when
+/// the first header is inserted, so requests without headers are free.
+///
+/// Header maps compare equal if they contain the same headers with the same
+/// values, regardless of whether values are borrowed or owned.
 ///
 /// [`HashMap`]: std::collections::HashMap
 /// [`Request`]: crate::http::Request
@@ -57,13 +64,30 @@ use crate::http::Header;
 ///
 /// // Obtain string representation
 /// println!("{headers}");
+///
+/// // Compare with equivalent header map
+/// let mut other = Headers::new();
+/// other.put(Header::Accept, String::from("text/plain"));
+/// assert_eq!(headers, other);
 /// ```
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Headers<'a> {
     /// Ordered map of headers.
     inner: BTreeMap<Header, Cow<'a, str>>,
 }
 
+/// HTTP request headers, formatted with redacted values.
+///
+/// This type is returned by [`Headers::redacted`], and formats the header map
+/// for display or debugging, replacing the values of the given headers with
+/// `<redacted>`, so credentials don't leak into logs.
+pub struct Redacted<'h, 'a> {
+    /// Header map.
+    headers: &'h Headers<'a>,
+    /// Headers to redact.
+    sensitive: &'h [Header],
+}
+
 // ----------------------------------------------------------------------------
 // Implementations
 // ----------------------------------------------------------------------------
@@ -128,8 +152,41 @@ impl<'a> Headers<'a> {
         self.inner.contains_key(&header)
     }
 
+    /// Returns whether the header lists the given token.
+    ///
+    /// The value is split into a comma-separated list, and each element is
+    /// trimmed and compared case-insensitively, which is how options of, e.g.,
+    /// [`Header::Connection`] or [`Header::Upgrade`] must be compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and add header
+    /// let mut headers = Headers::new();
+    /// headers.put(Header::Connection, "keep-alive, Upgrade");
+    ///
+    /// // Ensure presence of tokens
+    /// assert!(headers.contains_token(Header::Connection, "keep-alive"));
+    /// assert!(headers.contains_token(Header::Connection, "upgrade"));
+    /// assert!(!headers.contains_token(Header::Connection, "close"));
+    /// ```
+    #[must_use]
+    pub fn contains_token(&self, header: Header, token: &str) -> bool {
+        self.get(header).is_some_and(|value| {
+            value
+                .split(',')
+                .any(|option| option.trim().eq_ignore_ascii_case(token))
+        })
+    }
+
     /// Updates the given header.
     ///
+    /// Carriage returns, line feeds and null characters are removed from the
+    /// value, as they would allow to inject headers when serializing.
+    ///
     /// # Examples
     ///
     /// ```
@@ -140,12 +197,63 @@ impl<'a> Headers<'a> {
     /// let mut headers = Headers::new();
     /// headers.put(Header::Accept, "text/plain");
     /// ```
+    ///
+    /// Values containing line breaks are sanitized:
+    ///
+    /// ```
+    /// use zense::http::request::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and add header with line break
+    /// let mut headers = Headers::new();
+    /// headers.put(Header::Accept, "text/plain\r\nCookie: evil");
+    ///
+    /// // Ensure header value was sanitized
+    /// let value = headers.get(Header::Accept);
+    /// assert_eq!(value, Some("text/plainCookie: evil"));
+    /// ```
     #[inline]
     pub fn put<V>(&mut self, header: Header, value: V)
     where
         V: Into<Cow<'a, str>>,
     {
-        self.inner.insert(header, value.into());
+        self.inner.insert(header, sanitize(value.into()));
+    }
+
+    /// Adds the given header, if it's absent.
+    ///
+    /// This returns whether the header was added, and leaves present values
+    /// untouched, which is useful for middlewares that set default headers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and add header
+    /// let mut headers = Headers::new();
+    /// headers.put(Header::Accept, "text/plain");
+    ///
+    /// // Add header if absent - present value is preserved
+    /// assert!(!headers.put_if_absent(Header::Accept, "text/html"));
+    /// assert_eq!(headers.get(Header::Accept), Some("text/plain"));
+    ///
+    /// // Add header if absent - missing value is added
+    /// assert!(headers.put_if_absent(Header::UserAgent, "zense"));
+    /// assert_eq!(headers.get(Header::UserAgent), Some("zense"));
+    /// ```
+    pub fn put_if_absent<V>(&mut self, header: Header, value: V) -> bool
+    where
+        V: Into<Cow<'a, str>>,
+    {
+        match self.inner.entry(header) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(sanitize(value.into()));
+                true
+            }
+        }
     }
 
     /// Removes the given header.
@@ -167,6 +275,52 @@ impl<'a> Headers<'a> {
     pub fn remove(&mut self, header: Header) {
         self.inner.remove(&header);
     }
+
+    /// Returns an iterator over the header map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and add header
+    /// let mut headers = Headers::new();
+    /// headers.put(Header::Accept, "text/plain");
+    ///
+    /// // Iterate over header map
+    /// for (header, value) in headers.iter() {
+    ///    println!("{header}: {value}");
+    /// }
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, Header, Cow<'a, str>> {
+        self.inner.iter()
+    }
+
+    /// Returns the header map for formatting, redacting the given headers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and add headers
+    /// let mut headers = Headers::new();
+    /// headers.put(Header::Accept, "text/plain");
+    /// headers.put(Header::Authorization, "Bearer secret");
+    ///
+    /// // Obtain string representation with redacted headers
+    /// let value = headers.redacted(&Header::SENSITIVE).to_string();
+    /// assert!(value.contains("Accept: text/plain\r\n"));
+    /// assert!(value.contains("Authorization: <redacted>\r\n"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn redacted<'h>(&'h self, sensitive: &'h [Header]) -> Redacted<'h, 'a> {
+        Redacted { headers: self, sensitive }
+    }
 }
 
 #[allow(clippy::must_use_candidate)]
@@ -182,12 +336,78 @@ impl Headers<'_> {
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
     }
+
+    /// Converts the header map into an owned header map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and convert into owned header map
+    /// let headers = Headers::from_iter([(Header::Accept, "text/plain")]);
+    /// let headers = headers.into_owned();
+    /// ```
+    pub fn into_owned(self) -> Headers<'static> {
+        let iter = self
+            .inner
+            .into_iter()
+            .map(|(header, value)| (header, Cow::Owned(value.into_owned())));
+
+        // Collect headers into owned header map
+        Headers { inner: iter.collect() }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Redacted<'_, '_> {
+    /// Returns an iterator over the header map with redacted values.
+    fn iter(&self) -> impl Iterator<Item = (&Header, &str)> {
+        self.headers.iter().map(|(header, value)| {
+            if self.sensitive.contains(header) {
+                (header, "<redacted>")
+            } else {
+                (header, value.as_ref())
+            }
+        })
+    }
 }
 
 // ----------------------------------------------------------------------------
 // Trait implementations
 // ----------------------------------------------------------------------------
 
+impl<'h, 'a> IntoIterator for &'h Headers<'a> {
+    type Item = (&'h Header, &'h Cow<'a, str>);
+    type IntoIter = Iter<'h, Header, Cow<'a, str>>;
+
+    /// Creates an iterator over the header map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and add header
+    /// let mut headers = Headers::new();
+    /// headers.put(Header::Accept, "text/plain");
+    ///
+    /// // Iterate over header map
+    /// for (header, value) in &headers {
+    ///    println!("{header}: {value}");
+    /// }
+    /// ```
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 impl<'a> FromIterator<(Header, &'a str)> for Headers<'a> {
     /// Creates a header map from an iterator.
     ///
@@ -219,8 +439,18 @@ impl<'a> FromIterator<(Header, &'a str)> for Headers<'a> {
 
 impl fmt::Display for Headers<'_> {
     /// Formats the header map for display.
+    #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for (header, value) in &self.inner {
+        self.redacted(&[]).fmt(f)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl fmt::Display for Redacted<'_, '_> {
+    /// Formats the header map for display, redacting values.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (header, value) in self.iter() {
             f.write_str(header.name())?;
             f.write_str(": ")?;
             f.write_str(value)?;
@@ -231,3 +461,10 @@ impl fmt::Display for Headers<'_> {
         Ok(())
     }
 }
+
+impl fmt::Debug for Redacted<'_, '_> {
+    /// Formats the header map for debugging, redacting values.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! HTTP multipart body.
+
+use httparse::Status;
+use std::str;
+
+use super::error::{Error, Result};
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// HTTP multipart body.
+///
+/// This is an iterator over the parts of a `multipart/form-data` body, which
+/// is usually obtained through [`Request::multipart`][]. Parts are parsed on
+/// demand and borrow from the body, so no part is copied during iteration.
+///
+/// [`Request::multipart`]: crate::http::Request::multipart
+#[derive(Clone, Debug)]
+pub struct Multipart<'a> {
+    /// Remaining body.
+    rest: &'a [u8],
+    /// Boundary delimiter.
+    delimiter: Vec<u8>,
+    /// Whether iteration is done.
+    done: bool,
+}
+
+/// HTTP multipart body part.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Part<'a> {
+    /// Field name.
+    pub name: Option<&'a str>,
+    /// File name.
+    pub filename: Option<&'a str>,
+    /// Content type.
+    pub content_type: Option<&'a str>,
+    /// Part body.
+    pub body: &'a [u8],
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl<'a> Multipart<'a> {
+    /// Creates a multipart body from the given body and boundary.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::Multipart`], if the boundary is invalid,
+    /// or if the body doesn't contain the boundary delimiter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::request::Multipart;
+    ///
+    /// // Create multipart body
+    /// let body = b"--x\r\n\r\nHello world\r\n--x--\r\n";
+    /// let multipart = Multipart::new(body, "x")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(body: &'a [u8], boundary: &str) -> Result<Self> {
+        if boundary.is_empty() || boundary.len() > 70 {
+            return Err(Error::Multipart("invalid boundary"));
+        }
+
+        // Create delimiter, and skip the preamble before the first delimiter
+        let delimiter = [b"--", boundary.as_bytes()].concat();
+        match find(body, &delimiter) {
+            None => Err(Error::Multipart("missing boundary")),
+            Some(n) => Ok(Self {
+                rest: &body[n + delimiter.len()..],
+                delimiter,
+                done: false,
+            }),
+        }
+    }
+
+    /// Parses the next part.
+    fn parse(&mut self) -> Result<Option<Part<'a>>> {
+        let rest = self.rest;
+        if rest.starts_with(b"--") {
+            return Ok(None);
+        }
+
+        // Ensure delimiter is followed by a line break
+        let rest = rest
+            .strip_prefix(b"\r\n")
+            .ok_or(Error::Multipart("invalid delimiter"))?;
+
+        // Parse part headers, which are terminated by an empty line
+        let mut headers = [httparse::EMPTY_HEADER; 16];
+        let (n, headers) = match httparse::parse_headers(rest, &mut headers)? {
+            Status::Complete(value) => value,
+            Status::Partial => return Err(Error::Incomplete),
+        };
+
+        // Extract part body, which is terminated by a line break, followed by
+        // the delimiter, which is either the next or the closing delimiter
+        let rest = &rest[n..];
+        let mut part = Part::default();
+        let n = find_delimiter(rest, &self.delimiter)
+            .ok_or(Error::Multipart("missing boundary"))?;
+        part.body = &rest[..n];
+        self.rest = &rest[n + 2 + self.delimiter.len()..];
+
+        // Extract field name, file name and content type from headers
+        for header in headers {
+            let value = str::from_utf8(header.value)
+                .map_err(|_| Error::Multipart("invalid header"))?;
+            if header.name.eq_ignore_ascii_case("Content-Disposition") {
+                for param in value.split(';').skip(1) {
+                    let Some((key, value)) = param.split_once('=') else {
+                        continue;
+                    };
+
+                    // Remove surrounding quotes from value, if any
+                    let value = value.trim().trim_matches('"');
+                    match key.trim() {
+                        "name" => part.name = Some(value),
+                        "filename" => part.filename = Some(value),
+                        _ => {}
+                    }
+                }
+            } else if header.name.eq_ignore_ascii_case("Content-Type") {
+                part.content_type = Some(value.trim());
+            }
+        }
+
+        // Return part
+        Ok(Some(part))
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl<'a> Iterator for Multipart<'a> {
+    type Item = Result<Part<'a>>;
+
+    /// Returns the next part.
+    ///
+    /// Iteration stops after the closing delimiter or the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::request::Multipart;
+    ///
+    /// // Create multipart body
+    /// let body = b"--x\r\n\r\nHello world\r\n--x--\r\n";
+    /// let mut multipart = Multipart::new(body, "x")?;
+    ///
+    /// // Obtain first part
+    /// let part = multipart.next().unwrap()?;
+    /// assert_eq!(part.body, b"Hello world");
+    /// assert!(multipart.next().is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        // Stop iteration after the closing delimiter or errors
+        let result = self.parse().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Returns the position of the first occurrence of the needle.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Returns the position of the line break preceding the delimiter.
+fn find_delimiter(haystack: &[u8], delimiter: &[u8]) -> Option<usize> {
+    let mut start = 0;
+    while let Some(n) = find(&haystack[start..], delimiter) {
+        let n = start + n;
+        if haystack[..n].ends_with(b"\r\n") {
+            return Some(n - 2);
+        }
+        start = n + 1;
+    }
+    None
+}
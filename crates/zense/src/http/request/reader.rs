@@ -0,0 +1,205 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! HTTP request reader.
+
+use httparse::Status;
+use std::io::Read;
+
+use super::error::{Error, Result};
+
+// ----------------------------------------------------------------------------
+// Constants
+// ----------------------------------------------------------------------------
+
+/// Default maximum size of the request head, i.e., request line and headers.
+pub const MAX_HEAD_SIZE: usize = 8 * 1024;
+
+/// Size of the buffer used for reading.
+const CHUNK_SIZE: usize = 1024;
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Reads from the given reader until the request head is complete.
+///
+/// The given buffer might already contain bytes that were read previously,
+/// e.g., when requests are pipelined. The returned buffer contains the request
+/// head, and might also contain the beginning of the body, which is why the
+/// length of the head is returned. Reading stops, once the buffer exceeds the
+/// given maximum size without containing a complete head.
+pub fn read_head<R>(
+    reader: &mut R, mut buffer: Vec<u8>, max: usize,
+) -> Result<(Vec<u8>, usize)>
+where
+    R: Read,
+{
+    loop {
+        // Check whether the request head is complete - note that we parse the
+        // request twice, but parsing is fast, and this keeps things simple
+        let mut headers = [httparse::EMPTY_HEADER; 64];
+        let mut req = httparse::Request::new(&mut headers);
+        if let Status::Complete(n) = req.parse(&buffer)? {
+            return Ok((buffer, n));
+        }
+
+        // Ensure request head doesn't exceed maximum size
+        if buffer.len() > max {
+            return Err(Error::HeadersTooLarge);
+        }
+
+        // Read more data
+        fill(reader, &mut buffer)?;
+    }
+}
+
+/// Reads from the given reader until the body has the given length.
+///
+/// The given body might already contain bytes that were read together with
+/// the request head. Excess bytes are returned separately, as they belong to
+/// the next request. If the length exceeds the given maximum, reading fails
+/// before the body is read.
+pub fn read_body<R>(
+    reader: &mut R, mut body: Vec<u8>, len: usize, max: Option<usize>,
+) -> Result<(Vec<u8>, Vec<u8>)>
+where
+    R: Read,
+{
+    if len > max.unwrap_or(usize::MAX) {
+        return Err(Error::BodyTooLarge);
+    }
+
+    // Read until the body is complete
+    while body.len() < len {
+        fill(reader, &mut body)?;
+    }
+
+    // Split off excess bytes and return body
+    let rest = body.split_off(len);
+    Ok((body, rest))
+}
+
+/// Reads from the given reader until the chunked body is complete.
+///
+/// The given data might already contain bytes that were read together with
+/// the request head. Chunks are decoded into a contiguous body, and trailers
+/// are discarded, as they're not supported by the header map. Excess bytes
+/// are returned separately, as they belong to the next request.
+///
+/// If the decoded body exceeds the given maximum, reading fails as soon as
+/// the size of the offending chunk is known. Chunk size lines and trailers
+/// are limited to [`MAX_HEAD_SIZE`], whether a maximum is given or not, so
+/// they can't grow indefinitely, e.g., through endless chunk extensions.
+pub fn read_chunked<R>(
+    reader: &mut R, mut data: Vec<u8>, max: Option<usize>,
+) -> Result<(Vec<u8>, Vec<u8>)>
+where
+    R: Read,
+{
+    let max = max.unwrap_or(usize::MAX);
+    let mut body = Vec::new();
+    let mut pos = 0;
+    loop {
+        // Obtain size of next chunk, reading more data if necessary
+        let (n, size) = match httparse::parse_chunk_size(&data[pos..]) {
+            Ok(Status::Complete(value)) => value,
+            Ok(Status::Partial) => {
+                if data.len() - pos > MAX_HEAD_SIZE {
+                    return Err(Error::Security("chunk size line too long"));
+                }
+                fill(reader, &mut data)?;
+                continue;
+            }
+            Err(_) => return Err(Error::Security("invalid chunk size")),
+        };
+
+        // The last chunk has a size of zero, and is followed by the optional
+        // trailers, terminated by an empty line, which we need to consume
+        if size == 0 {
+            pos += n;
+            loop {
+                if let Some(end) = terminator(&data[pos..]) {
+                    let rest = data.split_off(pos + end);
+                    return Ok((body, rest));
+                }
+                if data.len() - pos > MAX_HEAD_SIZE {
+                    return Err(Error::HeadersTooLarge);
+                }
+                fill(reader, &mut data)?;
+            }
+        }
+
+        // Ensure the chunk doesn't exceed the remaining length of the body
+        let size = usize::try_from(size).unwrap_or(usize::MAX);
+        if size > max - body.len() {
+            return Err(Error::BodyTooLarge);
+        }
+
+        // Read chunk data and the trailing line break, ensuring that the size
+        // of the chunk doesn't overflow when computing its end
+        let end = (pos + n)
+            .checked_add(size)
+            .filter(|end| end.checked_add(2).is_some())
+            .ok_or(Error::Security("invalid chunk size"))?;
+        while data.len() < end + 2 {
+            fill(reader, &mut data)?;
+        }
+
+        // Ensure chunk data is terminated by a line break, or the chunk size
+        // doesn't match the actual length of the chunk data
+        if &data[end..end + 2] != b"\r\n" {
+            return Err(Error::Security("invalid chunk"));
+        }
+
+        // Append chunk data to body and continue with next chunk
+        body.extend_from_slice(&data[pos + n..end]);
+        pos = end + 2;
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Reads the next bytes from the given reader into the given buffer.
+fn fill<R>(reader: &mut R, buffer: &mut Vec<u8>) -> Result
+where
+    R: Read,
+{
+    let mut chunk = [0; CHUNK_SIZE];
+    match reader.read(&mut chunk)? {
+        0 => Err(Error::Incomplete),
+        n => {
+            buffer.extend_from_slice(&chunk[..n]);
+            Ok(())
+        }
+    }
+}
+
+/// Returns the end of the trailers, which are terminated by an empty line.
+fn terminator(data: &[u8]) -> Option<usize> {
+    if data.starts_with(b"\r\n") {
+        Some(2)
+    } else {
+        let index = data.windows(4).position(|w| w == b"\r\n\r\n")?;
+        Some(index + 4)
+    }
+}
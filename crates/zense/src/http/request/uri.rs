@@ -25,7 +25,7 @@
 use std::borrow::Cow;
 use std::fmt;
 
-mod encoding;
+pub mod encoding;
 mod query;
 
 use encoding::{decode, encode, Kind};
@@ -45,8 +45,35 @@ pub use query::Query;
 /// For now, we just assume that paths always start with a `/`, which is sane
 /// to assume for a local web server that is not intended for proxying.
 ///
+/// Path and query string are stored percent-decoded, which is why URIs that
+/// only differ in the encoding of the same characters compare as equal, and
+/// also hash identically, so they can be used as keys, e.g., for caching.
+///
+/// Conversely, path and query string are always percent-encoded when the URI
+/// is formatted. As input is decoded exactly once when parsed, and encoded
+/// exactly once when formatted, raw and already-encoded input can be mixed
+/// freely without ever being double-encoded, e.g., `/a b` and `/a%20b` are
+/// both formatted as `/a%20b`.
+///
 /// [`url`]: https://crates.io/crates/url
-#[derive(Clone, Debug, PartialEq, Eq)]
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zense::http::{Method, Uri};
+///
+/// // Create request URIs with different encodings
+/// let a = Uri::from("/caf%C3%A9?q=%7Euser");
+/// let b = Uri::from("/café?q=~user");
+/// assert_eq!(a, b);
+///
+/// // Use request URIs as keys
+/// let mut cache = HashMap::new();
+/// cache.insert((Method::Get, a), "cached");
+/// assert_eq!(cache.get(&(Method::Get, b)), Some(&"cached"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Uri<'a> {
     /// Request path.
     pub path: Cow<'a, str>,
@@ -99,6 +126,57 @@ impl<'a> Uri<'a> {
     }
 }
 
+#[allow(clippy::must_use_candidate)]
+impl Uri<'_> {
+    /// Returns whether the request URI is in asterisk-form.
+    ///
+    /// The asterisk-form `*` is only used with `OPTIONS` requests, in order to
+    /// query the capabilities of the server as a whole, rather than those of
+    /// a specific resource. It's represented as a path consisting of a single
+    /// `*`, which is formatted verbatim. Since routes always start with `/`,
+    /// routers never match it, so handlers must explicitly check for it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::{Method, Request};
+    ///
+    /// // Create request from bytes in asterisk-form
+    /// let req = Request::from_bytes(b"OPTIONS * HTTP/1.1\r\n\r\n")?;
+    /// assert_eq!(req.method, Method::Options);
+    /// assert!(req.uri.is_asterisk());
+    /// assert_eq!(req.uri.to_string(), "*");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn is_asterisk(&self) -> bool {
+        self.path == "*" && self.query.is_empty()
+    }
+}
+
+impl Uri<'_> {
+    /// Converts the request URI into an owned request URI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Uri;
+    ///
+    /// // Create request URI and convert into owned request URI
+    /// let uri = Uri::from("/path?key=value").into_owned();
+    /// ```
+    #[must_use]
+    pub fn into_owned(self) -> Uri<'static> {
+        Uri {
+            path: Cow::Owned(self.path.into_owned()),
+            query: self.query.into_owned(),
+        }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Trait implementations
 // ----------------------------------------------------------------------------
@@ -106,6 +184,9 @@ impl<'a> Uri<'a> {
 impl<'a> From<&'a str> for Uri<'a> {
     /// Creates a request URI from a string.
     ///
+    /// Surrounding whitespace is trimmed, and an empty path is normalized to
+    /// `/`, as the request line must never contain an empty target.
+    ///
     /// Note that we can't implement [`FromStr`][] for [`Uri`] because of the
     /// required `&'a str` lifetime, which is not compatible with the trait.
     ///
@@ -118,18 +199,22 @@ impl<'a> From<&'a str> for Uri<'a> {
     ///
     /// // Create request URI from string
     /// let uri = Uri::from("/path?key=value");
+    ///
+    /// // Create request URIs from empty and whitespace-only strings
+    /// assert_eq!(Uri::from("").path, "/");
+    /// assert_eq!(Uri::from("  ").path, "/");
+    /// assert_eq!(Uri::from("?key=value").to_string(), "/?key=value");
     /// ```
     fn from(value: &'a str) -> Self {
-        match value.split_once('?') {
-            Some((path, query)) => Uri {
-                path: decode(path),
-                query: Query::from(query),
-            },
-            None => Uri {
-                path: decode(value),
-                query: Query::default(),
-            },
-        }
+        let value = value.trim();
+        let (path, query) = match value.split_once('?') {
+            Some((path, query)) => (path, Query::from(query)),
+            None => (value, Query::default()),
+        };
+
+        // Normalize empty path
+        let path = if path.is_empty() { "/" } else { path };
+        Uri { path: decode(path), query }
     }
 }
 
@@ -158,8 +243,25 @@ impl Default for Uri<'_> {
 
 impl fmt::Display for Uri<'_> {
     /// Formats the request URI for display.
+    ///
+    /// An empty path is formatted as `/`, e.g., when it was set directly, so
+    /// the request line never contains an empty target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Uri;
+    ///
+    /// // Create request URI from parts with empty path
+    /// let uri = Uri::from_parts("", "key=value");
+    /// assert_eq!(uri.to_string(), "/?key=value");
+    /// ```
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(encode(&self.path, Kind::Path).as_ref())?;
+        if self.path.is_empty() {
+            f.write_str("/")?;
+        } else {
+            f.write_str(encode(&self.path, Kind::Path).as_ref())?;
+        }
 
         // Write query string, if any
         if !self.query.is_empty() {
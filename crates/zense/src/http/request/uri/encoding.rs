@@ -20,7 +20,7 @@
 
 // ----------------------------------------------------------------------------
 
-//! Encoding.
+//! Percent-encoding.
 
 use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet};
 use std::borrow::Cow;
@@ -30,12 +30,24 @@ use std::borrow::Cow;
 // ----------------------------------------------------------------------------
 
 /// Encoding kind.
+///
+/// The set of characters that must be percent-encoded depends on the context
+/// of the value, as characters that act as delimiters in one component of a
+/// URI might be allowed in another.
 #[derive(Clone, Copy, Debug)]
 pub enum Kind {
     /// Request path.
     Path,
     /// Query string.
     Query,
+    /// Key or value of a query parameter.
+    QueryParam,
+    /// Fragment.
+    Fragment,
+    /// User or password of the user information.
+    Userinfo,
+    /// Arbitrary component, encoding everything but unreserved characters.
+    Component,
 }
 
 // ----------------------------------------------------------------------------
@@ -76,6 +88,58 @@ const URI_QUERY: &AsciiSet = &percent_encoding::CONTROLS
     .add(b'|')
     .add(b'}');
 
+/// Characters that must be percent-encoded in keys and values of query
+/// parameters, which additionally includes the delimiters of parameters, as
+/// well as `+`, which is decoded as a space in form-encoded query strings.
+const URI_QUERY_PARAM: &AsciiSet = &URI_QUERY.add(b'&').add(b'+').add(b'=');
+
+/// Characters that must be percent-encoded in fragments.
+const URI_FRAGMENT: &AsciiSet = &percent_encoding::CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'[')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Characters that must be percent-encoded in user information.
+const URI_USERINFO: &AsciiSet = &percent_encoding::CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b':')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'@')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Characters that must be percent-encoded in arbitrary components, i.e., all
+/// characters except for the unreserved characters defined in [RFC 3986].
+///
+/// [RFC 3986]: https://www.rfc-editor.org/rfc/rfc3986#section-2.3
+const URI_COMPONENT: &AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
 // ----------------------------------------------------------------------------
 // Functions
 // ----------------------------------------------------------------------------
@@ -83,13 +147,36 @@ const URI_QUERY: &AsciiSet = &percent_encoding::CONTROLS
 /// Encodes a string.
 ///
 /// The second argument specifies the kind of encoding to use, as this varies
-/// by the usage context of the value, i.e., in paths or query strings.
+/// by the usage context of the value, i.e., in paths or query strings. Note
+/// that [`Kind::Query`] is meant for entire query strings, and thus retains
+/// the delimiters `&` and `=`, so keys and values of query parameters must
+/// be encoded with [`Kind::QueryParam`]. When in doubt, e.g., when building
+/// a component from untrusted input, use the [`Kind::Component`] set, which
+/// encodes all delimiters.
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::request::encoding::{encode, Kind};
+///
+/// // Encode value as path and as component
+/// assert_eq!(encode("a&b=c/d", Kind::Path), "a&b=c/d");
+/// assert_eq!(encode("a&b=c/d", Kind::Component), "a%26b%3Dc%2Fd");
+///
+/// // Encode value as user information and as fragment
+/// assert_eq!(encode("user@host:80", Kind::Userinfo), "user%40host%3A80");
+/// assert_eq!(encode("top of#page", Kind::Fragment), "top%20of%23page");
+/// ```
 #[inline]
 #[must_use]
-pub fn encode(value: &str, kind: Kind) -> Cow<str> {
+pub fn encode(value: &str, kind: Kind) -> Cow<'_, str> {
     let set = match kind {
         Kind::Path => URI_PATH,
         Kind::Query => URI_QUERY,
+        Kind::QueryParam => URI_QUERY_PARAM,
+        Kind::Fragment => URI_FRAGMENT,
+        Kind::Userinfo => URI_USERINFO,
+        Kind::Component => URI_COMPONENT,
     };
 
     // Encode using the specified set of characters
@@ -100,8 +187,17 @@ pub fn encode(value: &str, kind: Kind) -> Cow<str> {
 ///
 /// This function replaces invalid UTF-8 sequences with the Unicode replacement
 /// character �, as otherwise, this would lead to a much less ergonomic API.
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::request::encoding::decode;
+///
+/// // Decode value
+/// assert_eq!(decode("a%26b%3Dc%2Fd"), "a&b=c/d");
+/// ```
 #[inline]
 #[must_use]
-pub fn decode(value: &str) -> Cow<str> {
+pub fn decode(value: &str) -> Cow<'_, str> {
     percent_decode_str(value).decode_utf8_lossy()
 }
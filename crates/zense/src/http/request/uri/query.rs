@@ -23,11 +23,14 @@
 //! HTTP query string.
 
 use std::borrow::Cow;
-use std::{fmt, iter, str};
+use std::{fmt, str};
 use tinyvec::TinyVec;
 
 use super::encoding::{decode, encode, Kind};
 
+#[cfg(feature = "serde")]
+mod de;
+
 // ----------------------------------------------------------------------------
 // Structs
 // ----------------------------------------------------------------------------
@@ -42,20 +45,42 @@ use super::encoding::{decode, encode, Kind};
 /// When parsing a query string with [`Query::from`], the keys and values will
 /// be percent-decoded and stored decoded in a parameter list, as query strings
 /// might have multiple values for the same key, and ordering always needs to
-/// be preserved when formatting with [`fmt::Display`].
+/// be preserved when formatting with [`fmt::Display`]. Parsing happens exactly
+/// once, when the [`Uri`][] is created, so accessing parameters never requires
+/// parsing the query string again, and clones copy the parsed parameters, so
+/// they don't share any state.
 ///
 /// Since we don't expect middlewares to handle query strings with hundreds of
 /// parameters, we use a [`TinyVec`] with a capacity of 4 for enough headroom.
 ///
 /// [`Request`]: crate::http::Request
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// [`Uri`]: crate::http::Uri
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::Request;
+///
+/// // Create request with percent-encoded query string
+/// let req = Request::new()
+///     .uri("/search?q=caf%C3%A9");
+///
+/// // Obtain parameter value three times - the decoded value is owned, so
+/// // parsing again would allocate it anew, but all accesses share it
+/// let a = req.uri.query.get("q").unwrap();
+/// let b = req.uri.query.get("q").unwrap();
+/// let c = req.uri.query.get("q").unwrap();
+/// assert_eq!(a, "café");
+/// assert!(std::ptr::eq(a, b) && std::ptr::eq(b, c));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Query<'a> {
     /// List of parameters.
     inner: TinyVec<[Param<'a>; 4]>,
 }
 
 /// HTTP query string parameter.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 struct Param<'a> {
     /// Parameter key.
     key: Cow<'a, str>,
@@ -183,19 +208,64 @@ impl<'a> Query<'a> {
         });
     }
 
-    /// Removes the given parameter.
+    /// Sets the given key-value pair as a parameter, replacing all values.
+    ///
+    /// The first occurrence of the parameter retains its position, and all
+    /// further occurrences are removed. If the parameter isn't contained, it's
+    /// added to the end, which is identical to [`Query::add`].
     ///
     /// # Examples
     ///
     /// ```
     /// use zense::http::Query;
     ///
-    /// // Create query string and add parameter
-    /// let mut query = Query::new();
-    /// query.add("key", "value");
+    /// // Create query string from string
+    /// let mut query = Query::from("page=1&limit=25&page=2");
+    ///
+    /// // Set parameter
+    /// query.set("page", "3");
+    /// assert_eq!(query.to_string(), "page=3&limit=25");
+    /// ```
+    pub fn set<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        let key = key.into();
+        let value = value.into();
+        let Some(index) = self.inner.iter().position(|param| param.key == key)
+        else {
+            return self.inner.push(Param { key, value });
+        };
+
+        // Replace value of first occurrence, and remove all others - since no
+        // parameter before the first occurrence is removed, the index is kept
+        let mut i = 0;
+        self.inner.retain(|param| {
+            i += 1;
+            i - 1 == index || param.key != key
+        });
+        self.inner[index].value = value;
+    }
+
+    /// Removes the given parameter.
+    ///
+    /// All occurrences of the parameter are removed, which allows middlewares
+    /// to rewrite the query string of a request, e.g., to strip tracking
+    /// parameters, as the query string is formatted from its parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Request;
+    ///
+    /// // Create request with query string
+    /// let mut req = Request::new()
+    ///     .uri("/?utm_source=news&page=2&utm_source=feed");
     ///
     /// // Remove parameter
-    /// query.remove("key");
+    /// req.uri.query.remove("utm_source");
+    /// assert_eq!(req.uri.to_string(), "/?page=2");
     /// ```
     pub fn remove<K>(&mut self, key: K)
     where
@@ -203,6 +273,56 @@ impl<'a> Query<'a> {
     {
         self.inner.retain(|param| param.key != key.as_ref());
     }
+
+    /// Deserializes the query string into the given type.
+    ///
+    /// Parameters are deserialized as a map, in which repeated keys can be
+    /// collected into sequences like [`Vec`], and missing keys into [`None`]
+    /// for fields wrapped in an [`Option`]. Values are parsed into the types
+    /// of the respective fields, e.g., numbers or booleans.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`Error::Query`][], if a required key is missing,
+    /// or a value can't be parsed into the type of the field.
+    ///
+    /// [`Error::Query`]: crate::http::request::Error::Query
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use serde::Deserialize;
+    /// use zense::http::Query;
+    ///
+    /// // Define parameters
+    /// #[derive(Deserialize)]
+    /// struct Params {
+    ///     page: u32,
+    ///     tags: Vec<String>,
+    ///     sort: Option<String>,
+    /// }
+    ///
+    /// // Create query string and deserialize parameters
+    /// let query = Query::from("page=2&tags=a&tags=b");
+    /// let params: Params = query.deserialize()?;
+    /// assert_eq!(params.page, 2);
+    /// assert_eq!(params.tags, ["a", "b"]);
+    /// assert_eq!(params.sort, None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn deserialize<T>(&self) -> Result<T, crate::http::request::Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let iter = self.inner.iter();
+        T::deserialize(de::Deserializer::new(
+            iter.map(|param| (param.key.as_ref(), param.value.as_ref())),
+        ))
+    }
 }
 
 #[allow(clippy::must_use_candidate)]
@@ -220,6 +340,29 @@ impl Query<'_> {
     }
 }
 
+impl Query<'_> {
+    /// Converts the query string into an owned query string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Query;
+    ///
+    /// // Create query string and convert into owned query string
+    /// let query = Query::from("query=search&limit=25").into_owned();
+    /// ```
+    #[must_use]
+    pub fn into_owned(self) -> Query<'static> {
+        let iter = self.inner.into_iter().map(|param| Param {
+            key: Cow::Owned(param.key.into_owned()),
+            value: Cow::Owned(param.value.into_owned()),
+        });
+
+        // Collect parameters into owned query string
+        Query { inner: iter.collect() }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Trait implementations
 // ----------------------------------------------------------------------------
@@ -230,7 +373,8 @@ impl<'a> From<&'a str> for Query<'a> {
     /// The query string is parsed from the given string, which is expected to
     /// be in the format of a query string, i.e., a sequence of key-value pairs
     /// connected with `&`, but with the initial `?` separator removed. Both
-    /// keys and values are percent-decoded and stored.
+    /// keys and values are percent-decoded and stored, and empty pairs, e.g.,
+    /// resulting from consecutive `&` separators, are skipped.
     ///
     /// Note that we can't implement [`FromStr`][] for [`Query`] because of the
     /// required `&'a str` lifetime, which is not compatible with the trait.
@@ -245,54 +389,19 @@ impl<'a> From<&'a str> for Query<'a> {
     /// // Create query string from string
     /// let query = Query::from("query=search&limit=25");
     /// ```
-    #[allow(clippy::missing_panics_doc)]
     fn from(value: &'a str) -> Self {
-        let mut pairs = Vec::new();
-
-        // Initialize start and pair index
-        let mut start = 0;
-        let mut index = 0;
-
-        // Extract key-value pairs from string after conversion - we append a
-        // sentinel `&` separator to the end of the string, which makes parsing
-        // much simpler, as we don't need to replicate the logic for the last
-        // key-value pair outside of the loop
-        let chars = value.char_indices();
-        for (i, char) in chars.chain(iter::once((value.len(), '&'))) {
-            match char {
-                // If the current character is a `=` separator, we consumed a
-                // key (which may be empty), so we start a new key-value pair.
-                // Note that the `=` separator can also appear multiple times,
-                // in which case it's treated as a verbatim character.
-                '=' if index == pairs.len() => {
-                    pairs.push((decode(&value[start..i]), Cow::Borrowed("")));
-                    start = i + 1;
-                }
-
-                // If the current character is a `&` separator, we consumed a
-                // key-value pair, or just a key, both of which might be empty
-                '&' if start != i.saturating_sub(1) => {
-                    if index < pairs.len() && pairs[index].1.is_empty() {
-                        pairs[index].1 = decode(&value[start..i]);
-                    } else {
-                        pairs.push((
-                            decode(&value[start..i]),
-                            Cow::Borrowed(""),
-                        ));
-                    }
-
-                    // Continue after separator
-                    start = i + 1;
-                    index += 1;
-                }
-
-                // Consume all other characters
-                _ => {}
-            }
-        }
+        let iter = value.split('&').filter(|pair| !pair.is_empty());
+
+        // Extract key-value pairs - the first `=` separator splits key and
+        // value, and all further `=` separators are treated as verbatim
+        // characters of the value. Pairs without separator are just keys.
+        let pairs = iter.map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        });
 
         // Create query string from key-value pairs
-        Query::from_iter(pairs)
+        pairs.collect()
     }
 }
 
@@ -332,6 +441,33 @@ where
 
 impl fmt::Display for Query<'_> {
     /// Formats the query string for display.
+    ///
+    /// Keys and values are percent-encoded and joined with `&`, and parameters
+    /// that occur multiple times are written as multiple pairs, so the query
+    /// string can be parsed again with [`Query::from`], e.g., when building a
+    /// URL for a redirect. Parameters with empty values are written as keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Query;
+    ///
+    /// // Create query string from string and modify parameters
+    /// let mut query = Query::from("a=1&b=hello world");
+    /// query.remove("a");
+    /// query.add("c", "1");
+    /// query.add("c", "2");
+    ///
+    /// // Format query string and parse it again
+    /// let value = query.to_string();
+    /// assert_eq!(value, "b=hello%20world&c=1&c=2");
+    /// assert_eq!(Query::from(value.as_str()), query);
+    ///
+    /// // Format query string with delimiters in value
+    /// let mut query = Query::default();
+    /// query.add("q", "a&b=c");
+    /// assert_eq!(query.to_string(), "q=a%26b%3Dc");
+    /// ```
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for (i, param) in self.inner.iter().enumerate() {
             if i > 0 {
@@ -339,10 +475,10 @@ impl fmt::Display for Query<'_> {
             }
 
             // Write parameter key and value, if any
-            f.write_str(encode(&param.key, Kind::Query).as_ref())?;
+            f.write_str(encode(&param.key, Kind::QueryParam).as_ref())?;
             if !param.value.is_empty() {
                 f.write_str("=")?;
-                f.write_str(encode(&param.value, Kind::Query).as_ref())?;
+                f.write_str(encode(&param.value, Kind::QueryParam).as_ref())?;
             }
         }
 
@@ -0,0 +1,351 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! HTTP query string deserializer.
+
+use serde::de::value::{BorrowedStrDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use std::fmt::Display;
+use std::vec;
+
+use crate::http::request::Error;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// HTTP query string deserializer.
+///
+/// Parameters are grouped by key, retaining the order of first appearance, so
+/// repeated keys can be deserialized into sequences, e.g., [`Vec`].
+pub struct Deserializer<'de> {
+    /// Parameters grouped by key.
+    groups: vec::IntoIter<(&'de str, Vec<&'de str>)>,
+    /// Values of the current key.
+    values: Option<Vec<&'de str>>,
+}
+
+/// HTTP query string value deserializer, for all values of a key.
+struct Values<'de>(Vec<&'de str>);
+
+/// HTTP query string value deserializer, for a single value.
+struct Value<'de>(&'de str);
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl<'de> Deserializer<'de> {
+    /// Creates a deserializer from the given parameters.
+    pub fn new<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (&'de str, &'de str)>,
+    {
+        let mut groups: Vec<(&str, Vec<&str>)> = Vec::new();
+        for (key, value) in iter {
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, values)) => values.push(value),
+                None => groups.push((key, vec![value])),
+            }
+        }
+
+        // Return deserializer
+        Self {
+            groups: groups.into_iter(),
+            values: None,
+        }
+    }
+}
+
+impl<'de> Values<'de> {
+    /// Returns the deserializer for the first value.
+    fn first(&self) -> Value<'de> {
+        Value(self.0.first().copied().unwrap_or_default())
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Macros
+// ----------------------------------------------------------------------------
+
+/// Internal macro to forward methods to the deserializer of the first value.
+macro_rules! forward_to_first {
+    ($($method:ident)*) => {
+        $(
+            /// Deserializes the first value.
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.first().$method(visitor)
+            }
+        )*
+    };
+}
+
+/// Internal macro to implement methods that parse the value.
+macro_rules! parse_value {
+    ($($method:ident => $visit:ident),* $(,)?) => {
+        $(
+            /// Deserializes the value by parsing it.
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                match self.0.parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(de::Error::invalid_value(
+                        de::Unexpected::Str(self.0),
+                        &stringify!($visit).trim_start_matches("visit_"),
+                    )),
+                }
+            }
+        )*
+    };
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl de::Error for Error {
+    /// Creates an error from the given message.
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Error::Query(msg.to_string())
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    /// Deserializes the query string as a map.
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> MapAccess<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    /// Deserializes the next key, if any.
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let Some((key, values)) = self.groups.next() else {
+            return Ok(None);
+        };
+        self.values = Some(values);
+        seed.deserialize(BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    /// Deserializes the values of the current key.
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let values = self.values.take().unwrap_or_default();
+        seed.deserialize(Values(values))
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl<'de> de::Deserializer<'de> for Values<'de> {
+    type Error = Error;
+
+    /// Deserializes the first value.
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.first().deserialize_any(visitor)
+    }
+
+    /// Deserializes all values as a sequence.
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let iter = self.0.into_iter().map(Value);
+        visitor.visit_seq(SeqDeserializer::new(iter))
+    }
+
+    /// Deserializes all values as a tuple.
+    fn deserialize_tuple<V>(
+        self, _len: usize, visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    /// Deserializes a present value, as absent keys are never visited.
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    /// Deserializes the first value as a newtype.
+    fn deserialize_newtype_struct<V>(
+        self, name: &'static str, visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.first().deserialize_newtype_struct(name, visitor)
+    }
+
+    /// Deserializes the first value as a unit variant of an enum.
+    fn deserialize_enum<V>(
+        self, name: &'static str, variants: &'static [&'static str], visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.first().deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_first! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16
+        deserialize_u32 deserialize_u64 deserialize_u128 deserialize_f32
+        deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_unit
+    }
+
+    forward_to_deserialize_any! {
+        bytes byte_buf unit_struct tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value<'de> {
+    type Deserializer = Self;
+
+    /// Returns the value as a deserializer.
+    #[inline]
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl<'de> de::Deserializer<'de> for Value<'de> {
+    type Error = Error;
+
+    /// Deserializes the value as a string.
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_str(self.0)
+    }
+
+    /// Deserializes the value as a present option.
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    /// Deserializes the value as a newtype.
+    fn deserialize_newtype_struct<V>(
+        self, _name: &'static str, visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Deserializes the value as a unit variant of an enum.
+    fn deserialize_enum<V>(
+        self, _name: &'static str, _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let value: BorrowedStrDeserializer<'de, Error> =
+            BorrowedStrDeserializer::new(self.0);
+        visitor.visit_enum(value)
+    }
+
+    /// Deserializes an empty value as unit.
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.0.is_empty() {
+            visitor.visit_unit()
+        } else {
+            Err(de::Error::invalid_value(
+                de::Unexpected::Str(self.0),
+                &"empty",
+            ))
+        }
+    }
+
+    parse_value! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
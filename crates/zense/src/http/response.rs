@@ -23,18 +23,22 @@
 //! HTTP response.
 
 use std::fmt;
+use std::io::{self, Write};
 
-use super::component::{Header, Status};
+use super::component::{Header, Status, Version};
 
 mod conversion;
 mod error;
 mod extension;
+mod file;
 mod headers;
+pub mod sse;
 
-pub use conversion::IntoResponse;
+pub use conversion::{IntoResponse, ResultExt, WithStatus};
 pub use error::{Error, Result};
 pub use extension::ResponseExt;
-pub use headers::Headers;
+pub use file::{FileBody, CHUNK_SIZE};
+pub use headers::{Headers, Iter};
 
 // ----------------------------------------------------------------------------
 // Structs
@@ -61,12 +65,24 @@ pub use headers::Headers;
 /// ```
 #[derive(Clone, Debug)]
 pub struct Response {
+    /// Response version.
+    pub version: Version,
     /// Response status.
     pub status: Status,
     /// Response headers.
     pub headers: Headers,
+    /// Response trailers.
+    ///
+    /// Trailers are only written when the body is sent with chunked transfer
+    /// encoding, as there's no place for them after a fixed-length body.
+    pub trailers: Headers,
     /// Response body.
     pub body: Vec<u8>,
+    /// Response file body.
+    ///
+    /// Files are read in chunks while the response is written, after the
+    /// body, which is usually empty, so they're never loaded into memory.
+    pub file: Option<FileBody>,
 }
 
 // ----------------------------------------------------------------------------
@@ -92,60 +108,224 @@ impl Response {
 
     /// Converts the response into bytes.
     ///
+    /// This is the serialization format for writing to a socket - the status
+    /// line and headers are followed by the raw body, which is why binary
+    /// bodies are retained exactly. The [`Display`][] implementation is meant
+    /// for debugging only, as it omits the body.
+    ///
+    /// [`Display`]: fmt::Display
+    ///
     /// # Examples
     ///
     /// ```
     /// use zense::http::{Header, Response, Status};
     ///
-    /// // Create response
+    /// // Create response with binary body
     /// let res = Response::new()
     ///    .status(Status::Ok)
-    ///    .header(Header::ContentType, "text/plain")
-    ///    .header(Header::ContentLength, 11)
-    ///    .body("Hello world");
+    ///    .header(Header::ContentType, "application/octet-stream")
+    ///    .header(Header::ContentLength, 4)
+    ///    .body([0xDE, 0xAD, 0xBE, 0xEF]);
     ///
     /// // Convert response into bytes
     /// let bytes = res.into_bytes();
+    /// let n = bytes.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+    ///
+    /// // Ensure head and body are retained
+    /// let head = std::str::from_utf8(&bytes[..n]).unwrap();
+    /// let mut lines = head.split("\r\n");
+    /// assert_eq!(lines.next(), Some("HTTP/1.1 200 OK"));
+    /// assert_eq!(lines.next(), Some("Content-Length: 4"));
+    /// assert_eq!(lines.next(), Some("Content-Type: application/octet-stream"));
+    /// assert_eq!(&bytes[n + 4..], [0xDE, 0xAD, 0xBE, 0xEF]);
     /// ```
     #[must_use]
     pub fn into_bytes(self) -> Vec<u8> {
         // Compute an estimate for the response size - we know that we need 8
         // bytes for the HTTP/1.1 prefix + 36 bytes for the status code + info,
         // both with 2 bytes for the CRLF at the end. Then, for each header, we
-        // estimate an average size of 64 bytes per header (which might be more
-        // than necessary, but that's okay), and reserve just enough space for
-        // the body + 2 bytes for the CLRF that preceeds it.
+        // estimate an average size of 64 bytes per header and trailer (which
+        // might be more than necessary, but that's okay), and reserve just
+        // enough space for the body + 2 bytes for the CLRF that preceeds it.
         let capacity = (8 + 2)
             + 4 + 32 + 2 // fmt
             + self.headers.len() * 64 + 2 // fmt
+            + self.trailers.len() * 64
             + self.body.len();
 
-        // Create pre-sized buffer and append prefix and status
+        // Create pre-sized buffer and write response - writing to a vector
+        // is infallible, so we can safely ignore the result
         let mut buffer = Vec::with_capacity(capacity);
-        buffer.extend_from_slice(b"HTTP/1.1 ");
-        buffer.extend_from_slice(self.status.to_string().as_bytes());
-        buffer.extend_from_slice(b"\r\n");
+        let _ = self.write_to(&mut buffer);
+
+        // Return buffer
+        buffer
+    }
 
-        // Append all headers to buffer
+    /// Writes the response to the given writer.
+    ///
+    /// This method writes the same bytes as [`Response::into_bytes`], but
+    /// doesn't consume the response, and writes directly to the given writer,
+    /// e.g., a [`TcpStream`][], without allocating an intermediate buffer.
+    ///
+    /// When neither [`Header::ContentLength`] nor [`Header::TransferEncoding`]
+    /// is set, the content length is computed from the body and added, unless
+    /// the status doesn't allow for a body, e.g., "204 No Content", so that
+    /// the response is always correctly framed.
+    ///
+    /// When the [`Header::TransferEncoding`] header ends with `chunked`, the
+    /// body is written as a single chunk, followed by the terminating chunk
+    /// and the trailers, if any. File bodies are read and written in chunks
+    /// of [`CHUNK_SIZE`] bytes, each of which becomes a separate chunk when
+    /// chunked transfer encoding is used. In this case, a [`Header::Trailer`] header
+    /// listing the names of all trailers is added, unless already present.
+    ///
+    /// [`TcpStream`]: std::net::TcpStream
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error, if writing to the given writer fails, or
+    /// the file body can't be read entirely.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::{Response, Status};
+    ///
+    /// // Create response
+    /// let res = Response::new()
+    ///    .status(Status::NoContent);
+    ///
+    /// // Write response to buffer
+    /// let mut buffer = Vec::new();
+    /// res.write_to(&mut buffer)?;
+    /// assert_eq!(buffer, b"HTTP/1.1 204 No Content\r\n\r\n");
+    ///
+    /// // Create response with body, but without content length
+    /// let res = Response::new()
+    ///     .body("Hello world");
+    ///
+    /// // Write response to buffer - content length is added
+    /// let mut buffer = Vec::new();
+    /// res.write_to(&mut buffer)?;
+    /// assert_eq!(buffer, concat!(
+    ///     "HTTP/1.1 200 OK\r\n",
+    ///     "Content-Length: 11\r\n\r\n",
+    ///     "Hello world",
+    /// ).as_bytes());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_to<W>(&self, mut writer: W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        write!(writer, "{} {}\r\n", self.version, self.status)?;
+
+        // Write all headers
         for (header, value) in &self.headers {
-            buffer.extend_from_slice(header.name().as_bytes());
-            buffer.extend_from_slice(b": ");
-            buffer.extend_from_slice(value.as_bytes());
-            buffer.extend_from_slice(b"\r\n");
+            write!(writer, "{}: {value}\r\n", header.name())?;
+        }
+
+        // Write body as is, if not chunked, and add the content length, if
+        // missing, unless the status doesn't allow for a body
+        if !self.is_chunked() {
+            if !self.headers.contains(Header::ContentLength)
+                && !self.headers.contains(Header::TransferEncoding)
+                && self.has_body()
+            {
+                let len = self.len();
+                write!(writer, "{}: {len}\r\n", Header::ContentLength.name())?;
+            }
+            writer.write_all(b"\r\n")?;
+            writer.write_all(&self.body)?;
+            return match &self.file {
+                Some(file) => file.write_to(writer, false),
+                None => Ok(()),
+            };
         }
 
-        // Append empty line and body to buffer, if given
-        buffer.extend_from_slice(b"\r\n");
+        // Announce trailers, unless already done, and write body as a single
+        // chunk, omitting it if empty, since an empty chunk ends the body
+        if !self.trailers.is_empty() && !self.headers.contains(Header::Trailer)
+        {
+            let iter = self.trailers.iter().map(|(header, _)| header.name());
+            let names = iter.collect::<Vec<_>>().join(", ");
+            write!(writer, "{}: {names}\r\n", Header::Trailer.name())?;
+        }
+        writer.write_all(b"\r\n")?;
         if !self.body.is_empty() {
-            buffer.extend_from_slice(&self.body);
+            write!(writer, "{:X}\r\n", self.body.len())?;
+            writer.write_all(&self.body)?;
+            writer.write_all(b"\r\n")?;
+        }
+        if let Some(file) = &self.file {
+            file.write_to(&mut writer, true)?;
         }
 
-        // Return buffer
-        buffer
+        // Write terminating chunk, trailers and empty line
+        writer.write_all(b"0\r\n")?;
+        for (header, value) in &self.trailers {
+            write!(writer, "{}: {value}\r\n", header.name())?;
+        }
+        writer.write_all(b"\r\n")
+    }
+
+    /// Returns the length of the body, including the file body, if any.
+    fn len(&self) -> u64 {
+        let len = self.file.as_ref().map_or(0, FileBody::len);
+        self.body.len() as u64 + len
+    }
+
+    /// Returns whether the status of the response allows for a body.
+    fn has_body(&self) -> bool {
+        !self.status.is_informational()
+            && !matches!(self.status, Status::NoContent | Status::NotModified)
+    }
+
+    /// Returns whether the body is sent with chunked transfer encoding.
+    fn is_chunked(&self) -> bool {
+        self.headers
+            .get(Header::TransferEncoding)
+            .is_some_and(|value| {
+                let last = value.rsplit(',').next().unwrap_or_default();
+                last.trim().eq_ignore_ascii_case("chunked")
+            })
     }
 }
 
 impl Response {
+    /// Sets the version of the response.
+    ///
+    /// Responses default to HTTP/1.1, but when a request is handled with a
+    /// [`Stack`][], the version of the response is set to match the version
+    /// of the request, so HTTP/1.0 clients receive an HTTP/1.0 response.
+    ///
+    /// [`Stack`]: crate::handler::Stack
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Response, Status, Version};
+    ///
+    /// // Create response and set version
+    /// let res = Response::new()
+    ///     .version(Version::Http10)
+    ///     .status(Status::NoContent);
+    ///
+    /// // Convert response into bytes
+    /// let bytes = res.into_bytes();
+    /// assert_eq!(bytes, b"HTTP/1.0 204 No Content\r\n\r\n");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn version(mut self, version: Version) -> Self {
+        self.version = version;
+        self
+    }
+
     /// Sets the status of the response.
     ///
     /// # Examples
@@ -185,6 +365,124 @@ impl Response {
         self
     }
 
+    /// Adds a trailer to the response.
+    ///
+    /// Trailers are headers that are sent after the body, which is useful for
+    /// metadata that is only known after the body was generated, e.g., a
+    /// checksum. They're only written when the body is sent with chunked
+    /// transfer encoding, and ignored for fixed-length bodies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Response};
+    ///
+    /// // Create response and add trailer
+    /// let res = Response::new()
+    ///     .header(Header::TransferEncoding, "chunked")
+    ///     .trailer(Header::ETag, "\"2a\"")
+    ///     .body("Hello world");
+    ///
+    /// // Ensure trailer is written after terminating chunk
+    /// let bytes = res.into_bytes();
+    /// assert_eq!(bytes, concat!(
+    ///     "HTTP/1.1 200 OK\r\n",
+    ///     "Transfer-Encoding: chunked\r\n",
+    ///     "Trailer: ETag\r\n\r\n",
+    ///     "B\r\nHello world\r\n",
+    ///     "0\r\n",
+    ///     "ETag: \"2a\"\r\n\r\n",
+    /// ).as_bytes());
+    ///
+    /// // Ensure trailer is ignored for fixed-length body
+    /// let res = Response::new()
+    ///     .trailer(Header::ETag, "\"2a\"")
+    ///     .body("Hello world");
+    /// let bytes = res.into_bytes();
+    /// assert_eq!(bytes, concat!(
+    ///     "HTTP/1.1 200 OK\r\n",
+    ///     "Content-Length: 11\r\n\r\n",
+    ///     "Hello world",
+    /// ).as_bytes());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn trailer<V>(mut self, header: Header, value: V) -> Self
+    where
+        V: ToString,
+    {
+        self.trailers.put(header, value);
+        self
+    }
+
+    /// Adds a header to the [`Header::Vary`] header of the response.
+    ///
+    /// The header is kept in a single value, so the given header is appended
+    /// to the comma-separated list of existing values, if it isn't already
+    /// part of it. Comparison is case-insensitive, and if the
+    /// header already contains `*`, the response varies on everything anyway.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Response};
+    ///
+    /// // Create response and add headers to vary on
+    /// let res = Response::new()
+    ///     .vary(Header::AcceptEncoding)
+    ///     .vary(Header::Origin)
+    ///     .vary(Header::AcceptEncoding);
+    ///
+    /// // Obtain header value
+    /// let value = res.headers.get(Header::Vary);
+    /// assert_eq!(value, Some("Accept-Encoding, Origin"));
+    /// ```
+    #[must_use]
+    pub fn vary(mut self, header: Header) -> Self {
+        let name = header.name();
+        match self.headers.get(Header::Vary) {
+            None => self.headers.put(Header::Vary, name),
+            Some(value) => {
+                let mut iter = value.split(',').map(str::trim);
+                let check =
+                    |item: &str| item == "*" || item.eq_ignore_ascii_case(name);
+
+                // Only append header if it's not already contained
+                if !iter.any(check) {
+                    let value = format!("{value}, {name}");
+                    self.headers.put(Header::Vary, value);
+                }
+            }
+        }
+
+        // Return self for chaining
+        self
+    }
+
+    /// Signals that the connection should be closed after the response.
+    ///
+    /// This sets the [`Header::Connection`] header to `close`, which tells the
+    /// client that the connection won't be reused for further requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Header, Response};
+    ///
+    /// // Create response and close connection
+    /// let res = Response::new()
+    ///     .close_connection();
+    ///
+    /// // Obtain header value
+    /// let value = res.headers.get(Header::Connection);
+    /// assert_eq!(value, Some("close"));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn close_connection(self) -> Self {
+        self.header(Header::Connection, "close")
+    }
+
     /// Sets the body of the response.
     ///
     /// __Warning__: Albeit the [`Header::ContentLength`] header is required in
@@ -230,9 +528,12 @@ impl Default for Response {
     #[inline]
     fn default() -> Self {
         Self {
+            version: Version::default(),
             status: Status::Ok,
             headers: Headers::default(),
+            trailers: Headers::default(),
             body: Vec::default(),
+            file: None,
         }
     }
 }
@@ -242,8 +543,56 @@ impl Default for Response {
 impl fmt::Display for Response {
     /// Formats the response for display.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "HTTP/1.1 {}\r\n", self.status)?;
+        write!(f, "{} {}\r\n", self.version, self.status)?;
         write!(f, "{}\r\n", self.headers)?;
-        write!(f, "[Body: {} bytes]\r\n", self.body.len())
+        write!(f, "[Body: {} bytes]\r\n", self.len())
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Write for Response {
+    /// Appends the given bytes to the body of the response.
+    ///
+    /// This allows to build the body incrementally, e.g., with [`write!`],
+    /// instead of assembling it before passing it to [`Response::body`]. The
+    /// [`Header::ContentLength`] header is kept in sync with the body, unless
+    /// the body is sent with chunked transfer encoding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::io::Write;
+    /// use zense::http::{Header, Response};
+    ///
+    /// // Create response and write CSV rows
+    /// let mut res = Response::new()
+    ///     .header(Header::ContentType, "text/csv");
+    /// for (name, price) in [("espresso", 2), ("cappuccino", 3)] {
+    ///     writeln!(res, "{name},{price}")?;
+    /// }
+    ///
+    /// // Ensure content length matches body
+    /// assert_eq!(res.body, b"espresso,2\ncappuccino,3\n");
+    /// assert_eq!(res.headers.get(Header::ContentLength), Some("24"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.body.extend_from_slice(buf);
+        if !self.is_chunked() {
+            self.headers.put(Header::ContentLength, self.len());
+        }
+
+        // Return number of bytes written
+        Ok(buf.len())
+    }
+
+    /// Flushes the response, which is a no-op.
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
     }
 }
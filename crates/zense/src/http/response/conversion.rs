@@ -25,7 +25,7 @@
 use std::error::Error;
 use std::result::Result;
 
-use crate::http::Status;
+use crate::http::{request, Header, Status};
 
 use super::extension::ResponseExt;
 use super::Response;
@@ -40,6 +40,56 @@ pub trait IntoResponse {
     fn into_response(self) -> Response;
 }
 
+/// Extension trait for results of fallible handlers.
+pub trait ResultExt<E>: Sized {
+    /// Sets the status to respond with, if the result is an error.
+    ///
+    /// Results are converted into responses with "500 Internal Server Error"
+    /// for errors, which isn't always appropriate, e.g., for gateways, where
+    /// upstream failures should be answered with "502 Bad Gateway".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use zense::handler::Handler;
+    /// use zense::http::response::ResultExt;
+    /// use zense::http::{Request, Response, Status};
+    ///
+    /// // Define upstream request
+    /// fn upstream(req: &Request) -> io::Result<Response> {
+    ///     Err(io::Error::from(io::ErrorKind::ConnectionRefused))
+    /// }
+    ///
+    /// // Create handler
+    /// let handler = |req: Request| {
+    ///     upstream(&req).or_status(Status::BadGateway)
+    /// };
+    ///
+    /// // Handle request with handler
+    /// let res = handler.handle(Request::new());
+    /// assert_eq!(res.status, Status::BadGateway);
+    /// ```
+    #[must_use]
+    fn or_status(self, status: Status) -> WithStatus<E>;
+}
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Result with the status to respond with, if it's an error.
+///
+/// This type is returned by [`ResultExt::or_status`], and converted into the
+/// response, if the result is successful, or a response with the status.
+#[derive(Debug)]
+pub struct WithStatus<E> {
+    /// Result.
+    result: Result<Response, E>,
+    /// Status for errors.
+    status: Status,
+}
+
 // ----------------------------------------------------------------------------
 // Trait implementations
 // ----------------------------------------------------------------------------
@@ -52,6 +102,214 @@ impl IntoResponse for Response {
     }
 }
 
+impl IntoResponse for Status {
+    /// Converts a status into a response.
+    ///
+    /// This is equivalent to [`ResponseExt::from_status`], which allows to
+    /// return a bare status from handlers, e.g., to signal an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::handler::Handler;
+    /// use zense::http::{Request, Status};
+    ///
+    /// // Create handler
+    /// let handler = |_: Request| Status::Forbidden;
+    ///
+    /// // Handle request with handler
+    /// let res = handler.handle(Request::new());
+    /// assert_eq!(res.status, Status::Forbidden);
+    /// ```
+    #[inline]
+    fn into_response(self) -> Response {
+        Response::from_status(self)
+    }
+}
+
+impl IntoResponse for &str {
+    /// Converts a string slice into a response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::IntoResponse;
+    /// use zense::http::{Header, Status};
+    ///
+    /// // Create response from string slice
+    /// let res = "Hello world".into_response();
+    /// assert_eq!(res.status, Status::Ok);
+    /// assert_eq!(
+    ///     res.headers.get(Header::ContentType),
+    ///     Some("text/plain; charset=utf-8")
+    /// );
+    /// assert_eq!(res.headers.get(Header::ContentLength), Some("11"));
+    /// ```
+    #[inline]
+    fn into_response(self) -> Response {
+        Response::text(self)
+    }
+}
+
+impl IntoResponse for String {
+    /// Converts a string into a response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::IntoResponse;
+    /// use zense::http::{Header, Status};
+    ///
+    /// // Create response from string
+    /// let res = String::from("Hello world").into_response();
+    /// assert_eq!(res.status, Status::Ok);
+    /// assert_eq!(
+    ///     res.headers.get(Header::ContentType),
+    ///     Some("text/plain; charset=utf-8")
+    /// );
+    /// assert_eq!(res.headers.get(Header::ContentLength), Some("11"));
+    /// ```
+    #[inline]
+    fn into_response(self) -> Response {
+        Response::text(self)
+    }
+}
+
+impl IntoResponse for Vec<u8> {
+    /// Converts a vector of bytes into a response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::IntoResponse;
+    /// use zense::http::{Header, Status};
+    ///
+    /// // Create response from bytes
+    /// let res = vec![0, 1, 2, 3].into_response();
+    /// assert_eq!(res.status, Status::Ok);
+    /// assert_eq!(
+    ///     res.headers.get(Header::ContentType),
+    ///     Some("application/octet-stream")
+    /// );
+    /// assert_eq!(res.headers.get(Header::ContentLength), Some("4"));
+    /// ```
+    fn into_response(self) -> Response {
+        Response::new()
+            .header(Header::ContentType, "application/octet-stream")
+            .header(Header::ContentLength, self.len())
+            .body(self)
+    }
+}
+
+impl<T> IntoResponse for (Status, T)
+where
+    T: IntoResponse,
+{
+    /// Converts a status and a body into a response.
+    ///
+    /// The body is converted into a response first, after which the status
+    /// is set, so this works for strings and bytes alike.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::IntoResponse;
+    /// use zense::http::{Header, Status};
+    ///
+    /// // Create response from status and string
+    /// let res = (Status::Created, String::from("Created")).into_response();
+    /// assert_eq!(res.status, Status::Created);
+    /// assert_eq!(
+    ///     res.headers.get(Header::ContentType),
+    ///     Some("text/plain; charset=utf-8")
+    /// );
+    /// assert_eq!(res.headers.get(Header::ContentLength), Some("7"));
+    ///
+    /// // Create response from status and bytes
+    /// let res = (Status::Accepted, vec![0, 1, 2]).into_response();
+    /// assert_eq!(res.status, Status::Accepted);
+    /// assert_eq!(
+    ///     res.headers.get(Header::ContentType),
+    ///     Some("application/octet-stream")
+    /// );
+    /// assert_eq!(res.headers.get(Header::ContentLength), Some("3"));
+    /// ```
+    #[inline]
+    fn into_response(self) -> Response {
+        let (status, body) = self;
+        body.into_response().status(status)
+    }
+}
+
+impl<T> IntoResponse for Option<T>
+where
+    T: IntoResponse,
+{
+    /// Converts an option into a response.
+    ///
+    /// If the option is [`None`], the "404 Not Found" status code is returned
+    /// as a response, which allows handlers to signal that they're unable to
+    /// answer the request. Since handlers are always at the end of the request
+    /// processing chain, there's nothing left to defer to, which is why this
+    /// is equivalent to the [`NotFound`][] handler. Middlewares that want to
+    /// defer to the next handler should invoke it directly.
+    ///
+    /// [`NotFound`]: crate::handler::NotFound
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::handler::Handler;
+    /// use zense::http::{Request, Response, Status};
+    ///
+    /// // Create handler
+    /// let handler = |req: Request| -> Option<Response> {
+    ///     (req.uri.path == "/coffee").then(Response::new)
+    /// };
+    ///
+    /// // Handle request with handler
+    /// let res = handler.handle(Request::new().uri("/tea"));
+    /// assert_eq!(res.status, Status::NotFound);
+    /// ```
+    fn into_response(self) -> Response {
+        self.map_or_else(
+            || Response::from_status(Status::NotFound),
+            IntoResponse::into_response,
+        )
+    }
+}
+
+impl<E> IntoResponse for WithStatus<E>
+where
+    E: Error,
+{
+    /// Converts a result with status into a response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Error;
+    /// use zense::http::response::{IntoResponse, ResultExt};
+    /// use zense::http::{Response, Status};
+    ///
+    /// // Create response from error with status
+    /// let err = Error::from_raw_os_error(1);
+    /// let res = Err(err).or_status(Status::BadGateway).into_response();
+    /// assert_eq!(res.status, Status::BadGateway);
+    ///
+    /// // Create response from success with status
+    /// let res = Ok::<_, Error>(Response::new())
+    ///     .or_status(Status::BadGateway)
+    ///     .into_response();
+    /// assert_eq!(res.status, Status::Ok);
+    /// ```
+    fn into_response(self) -> Response {
+        let status = self.status;
+        self.result
+            .unwrap_or_else(|_| Response::from_status(status))
+    }
+}
+
 impl<E> IntoResponse for Result<Response, E>
 where
     E: Error,
@@ -59,7 +317,8 @@ where
     /// Converts a result into a response.
     ///
     /// If the result is an error, the "500 Internal Server Error" status code
-    /// is returned as a response, which indicates an unrecoverable error.
+    /// is returned as a response, which indicates an unrecoverable error. Use
+    /// [`ResultExt::or_status`] to respond with a different status.
     ///
     /// # Examples
     ///
@@ -79,3 +338,58 @@ where
         })
     }
 }
+
+impl IntoResponse for request::Error {
+    /// Converts a request error into a response.
+    ///
+    /// Errors caused by limits map to their dedicated status codes, i.e., an
+    /// oversized body to "413 Payload Too Large", and an oversized head to
+    /// "431 Request Header Fields Too Large". I/O errors map to "500 Internal
+    /// Server Error", and all other errors to "400 Bad Request", as they're
+    /// caused by invalid requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::request::{self, Config};
+    /// use zense::http::response::IntoResponse;
+    /// use zense::http::{Request, Status};
+    ///
+    /// // Create configuration with maximum head size
+    /// let config = Config::new()
+    ///     .max_header_bytes(16);
+    ///
+    /// // Create response from error for oversized head
+    /// let data = b"GET /coffee HTTP/1.1\r\nAccept: text/plain\r\n\r\n";
+    /// let err = Request::from_bytes_with(data, &config).unwrap_err();
+    /// let res = err.into_response();
+    /// assert_eq!(res.status, Status::RequestHeaderFieldsTooLarge);
+    ///
+    /// // Create response from error for invalid request
+    /// let err = request::Error::Security("path traversal");
+    /// let res = err.into_response();
+    /// assert_eq!(res.status, Status::BadRequest);
+    /// ```
+    fn into_response(self) -> Response {
+        let status = match self {
+            request::Error::BodyTooLarge => Status::PayloadTooLarge,
+            request::Error::HeadersTooLarge => {
+                Status::RequestHeaderFieldsTooLarge
+            }
+            request::Error::Io(_) => Status::InternalServerError,
+            _ => Status::BadRequest,
+        };
+        Response::from_status(status)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Blanket implementations
+// ----------------------------------------------------------------------------
+
+impl<E> ResultExt<E> for Result<Response, E> {
+    #[inline]
+    fn or_status(self, status: Status) -> WithStatus<E> {
+        WithStatus { result: self, status }
+    }
+}
@@ -22,9 +22,16 @@
 
 //! HTTP response.
 
+use std::error::Error;
+use std::io;
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use crate::http::problem::Problem;
 use crate::http::{Header, Status};
 
-use super::Response;
+use super::sse::Event;
+use super::{FileBody, Response};
 
 // ----------------------------------------------------------------------------
 // Traits
@@ -38,13 +45,344 @@ pub trait ResponseExt: Sized {
     /// and a text body, particularly useful for error handling.
     #[must_use]
     fn from_status(status: Status) -> Response {
-        let content = status.name();
+        Response::text(status.name()).status(status)
+    }
+
+    /// Creates a response from an error.
+    ///
+    /// If no status is given, it's derived from the error - [`io::Error`] is
+    /// mapped by kind, e.g., [`io::ErrorKind::NotFound`] to "404 Not Found",
+    /// and all other errors to "500 Internal Server Error". In debug builds,
+    /// the body includes the error message to ease development, while release
+    /// builds only include the status name, so internals are never leaked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::io;
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Response, Status};
+    ///
+    /// // Create response from error
+    /// let err = io::Error::new(io::ErrorKind::NotFound, "secret.txt");
+    /// let res = Response::from_error(err, None);
+    /// assert_eq!(res.status, Status::NotFound);
+    ///
+    /// // Ensure error message is only included in debug builds
+    /// let body = String::from_utf8(res.body)?;
+    /// assert_eq!(body.contains("secret.txt"), cfg!(debug_assertions));
+    ///
+    /// // Create response from error with status
+    /// let err = io::Error::other("secret.txt");
+    /// let res = Response::from_error(err, Some(Status::BadGateway));
+    /// assert_eq!(res.status, Status::BadGateway);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    fn from_error<E>(err: E, status: Option<Status>) -> Response
+    where
+        E: Error + 'static,
+    {
+        let status = status.unwrap_or_else(|| {
+            let err: &(dyn Error + 'static) = &err;
+            match err.downcast_ref::<io::Error>().map(io::Error::kind) {
+                Some(io::ErrorKind::NotFound) => Status::NotFound,
+                Some(io::ErrorKind::PermissionDenied) => Status::Forbidden,
+                Some(
+                    io::ErrorKind::InvalidInput | io::ErrorKind::InvalidData,
+                ) => Status::BadRequest,
+                _ => Status::InternalServerError,
+            }
+        });
+
+        // Only include the error message in debug builds
+        if cfg!(debug_assertions) {
+            let body = format!("{}: {err}", status.name());
+            Response::text(body).status(status)
+        } else {
+            Response::from_status(status)
+        }
+    }
+
+    /// Creates a response with a file body.
+    ///
+    /// The file isn't loaded into memory, but read in chunks while the
+    /// response is written, so memory stays bounded regardless of the size
+    /// of the file. The content length is taken from the metadata of the
+    /// file, and the content type defaults to `application/octet-stream`,
+    /// which should be replaced with the actual media type, if known.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error, if the file can't be opened, see
+    /// [`FileBody::open`], which can be converted with
+    /// [`ResponseExt::from_error`], e.g., into "404 Not Found".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::env;
+    /// use std::fs;
+    /// use zense::http::response::{ResponseExt, CHUNK_SIZE};
+    /// use zense::http::{Header, Response};
+    ///
+    /// // Create file spanning multiple chunks
+    /// let data = (0..CHUNK_SIZE * 2 + 1).map(|n| n as u8).collect::<Vec<_>>();
+    /// let path = env::temp_dir().join("zense-response-ext-file.bin");
+    /// fs::write(&path, &data)?;
+    ///
+    /// // Create response, and ensure body isn't loaded
+    /// let res = Response::file(&path)?;
+    /// let len = data.len().to_string();
+    /// assert_eq!(res.headers.get(Header::ContentLength), Some(len.as_str()));
+    /// assert!(res.body.is_empty());
+    ///
+    /// // Convert response into bytes, and ensure file is sent entirely
+    /// let bytes = res.into_bytes();
+    /// assert!(bytes.ends_with(&data));
+    /// # fs::remove_file(&path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn file<P>(path: P) -> io::Result<Response>
+    where
+        P: AsRef<Path>,
+    {
+        let file = FileBody::open(path)?;
+        let mut res = Response::new()
+            .header(Header::ContentType, "application/octet-stream")
+            .header(Header::ContentLength, file.len());
+
+        // Set file body and return response
+        res.file = Some(file);
+        Ok(res)
+    }
+
+    /// Creates a response with a HTML body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Header, Response};
+    ///
+    /// // Create response
+    /// let res = Response::html("<h1>Hello world</h1>");
+    /// assert_eq!(
+    ///     res.headers.get(Header::ContentType),
+    ///     Some("text/html; charset=utf-8")
+    /// );
+    /// assert_eq!(res.headers.get(Header::ContentLength), Some("20"));
+    /// ```
+    #[must_use]
+    fn html<B>(body: B) -> Response
+    where
+        B: Into<Vec<u8>>,
+    {
+        let content = body.into();
+        Response::new()
+            .header(Header::ContentType, "text/html; charset=utf-8")
+            .header(Header::ContentLength, content.len())
+            .body(content)
+    }
+
+    /// Creates a response with a plain text body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Header, Response};
+    ///
+    /// // Create response
+    /// let res = Response::text("Hello world");
+    /// assert_eq!(
+    ///     res.headers.get(Header::ContentType),
+    ///     Some("text/plain; charset=utf-8")
+    /// );
+    /// assert_eq!(res.headers.get(Header::ContentLength), Some("11"));
+    /// ```
+    #[must_use]
+    fn text<B>(body: B) -> Response
+    where
+        B: Into<Vec<u8>>,
+    {
+        let content = body.into();
         Response::new()
-            .status(status)
             .header(Header::ContentType, "text/plain; charset=utf-8")
             .header(Header::ContentLength, content.len())
             .body(content)
     }
+
+    /// Creates a response with problem details.
+    ///
+    /// The problem details are serialized as JSON, and sent with the media
+    /// type `application/problem+json`, as defined in [RFC 7807]. The status
+    /// of the response is set to the status of the problem.
+    ///
+    /// [RFC 7807]: https://www.rfc-editor.org/rfc/rfc7807
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::problem::Problem;
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Header, Response, Status};
+    ///
+    /// // Create response with problem details
+    /// let res = Response::problem(
+    ///     Problem::new(Status::NotFound)
+    ///         .detail("No user with id 7")
+    ///         .instance("/users/7"),
+    /// );
+    /// assert_eq!(res.status, Status::NotFound);
+    /// assert_eq!(
+    ///     res.headers.get(Header::ContentType),
+    ///     Some("application/problem+json")
+    /// );
+    /// assert_eq!(res.body, concat!(
+    ///     r#"{"type":"about:blank","title":"Not Found","status":404,"#,
+    ///     r#""detail":"No user with id 7","instance":"/users/7"}"#,
+    /// ).as_bytes());
+    /// ```
+    #[cfg(feature = "serde")]
+    #[must_use]
+    fn problem(problem: Problem) -> Response {
+        // Serializing into a vector can only fail for maps with non-string
+        // keys, or serializers that fail, neither of which applies here
+        let content = serde_json::to_vec(&problem).unwrap_or_default();
+        Response::new()
+            .status(problem.status())
+            .header(Header::ContentType, "application/problem+json")
+            .header(Header::ContentLength, content.len())
+            .body(content)
+    }
+
+    /// Creates a response with a finite batch of server-sent events.
+    ///
+    /// The events are formatted as explained in [`Event`], and the response is
+    /// sent with caching disabled. As response bodies are buffered, all events
+    /// are encoded when the response is created, so the source must be finite,
+    /// e.g., the events that are currently buffered in a channel. The response
+    /// has a known length, so it works with HTTP/1.0 clients as well. Clients
+    /// reconnect after the body was received, using the identifier of the last
+    /// event, so they can resume where they left off. Keeping the connection
+    /// open to push events as they happen requires a streaming body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::http::response::sse::Event;
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Header, Response};
+    ///
+    /// // Create response with events
+    /// let res = Response::sse_batch([
+    ///     Event::new().event("greeting").data("Hello"),
+    ///     Event::comment("keep-alive"),
+    ///     Event::new().id("2").data("multi\nline"),
+    /// ]);
+    /// assert_eq!(
+    ///     res.headers.get(Header::ContentType),
+    ///     Some("text/event-stream")
+    /// );
+    ///
+    /// // Convert response into bytes, and check framing of events
+    /// let bytes = res.into_bytes();
+    /// let body = concat!(
+    ///     "event: greeting\ndata: Hello\n\n",
+    ///     ": keep-alive\n\n",
+    ///     "id: 2\ndata: multi\ndata: line\n\n",
+    /// );
+    /// let head = format!("Content-Length: {}\r\n", body.len());
+    /// let text = String::from_utf8(bytes)?;
+    /// assert!(text.contains(&head));
+    /// assert!(text.ends_with(&format!("\r\n\r\n{body}")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    fn sse_batch<I>(source: I) -> Response
+    where
+        I: IntoIterator<Item = Event>,
+    {
+        let content = source.into_iter().map(|event| event.to_string());
+        let content = content.collect::<String>();
+        Response::new()
+            .header(Header::ContentType, "text/event-stream")
+            .header(Header::CacheControl, "no-cache")
+            .header(Header::ContentLength, content.len())
+            .body(content)
+    }
+
+    /// Creates a "101 Switching Protocols" response for the given protocol.
+    ///
+    /// The response confirms an upgrade requested by the client, which can be
+    /// detected with [`Request::upgrade_target`][], and sets the given protocol
+    /// in the [`Header::Upgrade`] header, as well as the `Upgrade` option in
+    /// the [`Header::Connection`] header. After the response was sent, the
+    /// connection must be handed over to an implementation of the protocol.
+    ///
+    /// [`Request::upgrade_target`]: crate::http::Request::upgrade_target
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Header, Request, Response, Status};
+    ///
+    /// // Create request for upgrade
+    /// let req = Request::new()
+    ///     .header(Header::Connection, "Upgrade, HTTP2-Settings")
+    ///     .header(Header::Upgrade, "h2c");
+    ///
+    /// // Create response for upgrade
+    /// let protocol = req.upgrade_target().unwrap();
+    /// let res = Response::switching_protocols(protocol);
+    /// assert_eq!(res.into_bytes(), concat!(
+    ///     "HTTP/1.1 101 Switching Protocols\r\n",
+    ///     "Connection: Upgrade\r\n",
+    ///     "Upgrade: h2c\r\n\r\n",
+    /// ).as_bytes());
+    /// ```
+    #[must_use]
+    fn switching_protocols<P>(protocol: P) -> Response
+    where
+        P: Into<String>,
+    {
+        Response::new()
+            .status(Status::SwitchingProtocols)
+            .header(Header::Upgrade, protocol.into())
+            .header(Header::Connection, "Upgrade")
+    }
+
+    /// Creates an interim "100 Continue" response.
+    ///
+    /// Clients that send the [`Header::Expect`] header with `100-continue` wait
+    /// for this interim response before sending the request body. It consists
+    /// of the status line only, and must be followed by the final response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::Response;
+    ///
+    /// // Create interim response
+    /// let res = Response::continue_();
+    /// assert_eq!(res.into_bytes(), b"HTTP/1.1 100 Continue\r\n\r\n");
+    /// ```
+    #[must_use]
+    fn continue_() -> Response {
+        Response::new().status(Status::Continue)
+    }
 }
 
 // ----------------------------------------------------------------------------
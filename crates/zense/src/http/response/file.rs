@@ -0,0 +1,184 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! File body.
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+// ----------------------------------------------------------------------------
+// Constants
+// ----------------------------------------------------------------------------
+
+/// Number of bytes read from a file at once.
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// File body.
+///
+/// File bodies are read in chunks of [`CHUNK_SIZE`] bytes while the response
+/// is written, so memory stays bounded regardless of the size of the file.
+/// The length is taken from the metadata of the file when the body is opened,
+/// and the file is only opened again for reading when the response is written,
+/// which is why responses with file bodies can be cloned cheaply.
+///
+/// Exactly as many bytes as announced are written, since the framing of the
+/// response depends on it. If the file was truncated in the meantime, writing
+/// fails, and the connection must be closed. See [`ResponseExt::file`][] for
+/// creating a response.
+///
+/// [`ResponseExt::file`]: crate::http::response::ResponseExt::file
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::env;
+/// use std::fs;
+/// use zense::http::response::FileBody;
+///
+/// // Create file
+/// let path = env::temp_dir().join("zense-file-body.txt");
+/// fs::write(&path, "Hello world")?;
+///
+/// // Open file body, and obtain its length
+/// let file = FileBody::open(&path)?;
+/// assert_eq!(file.len(), 11);
+/// # fs::remove_file(&path)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileBody {
+    /// File path.
+    path: PathBuf,
+    /// File length.
+    len: u64,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl FileBody {
+    /// Opens a file body at the given path.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error, if the metadata of the file can't be
+    /// obtained, or the path doesn't point to a file, in which case the error
+    /// is of kind [`io::ErrorKind::NotFound`], as directories aren't served.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::env;
+    /// use std::io;
+    /// use zense::http::response::FileBody;
+    ///
+    /// // Open file body for directory
+    /// let res = FileBody::open(env::temp_dir());
+    /// assert!(res.is_err_and(|err| err.kind() == io::ErrorKind::NotFound));
+    /// ```
+    pub fn open<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let metadata = path.metadata()?;
+        if !metadata.is_file() {
+            return Err(io::Error::from(io::ErrorKind::NotFound));
+        }
+
+        // Return file body
+        Ok(Self {
+            path: path.to_path_buf(),
+            len: metadata.len(),
+        })
+    }
+
+    /// Writes the file to the given writer, optionally as chunks.
+    ///
+    /// Each read from the file is written as a separate chunk when chunked
+    /// transfer encoding is used, so the terminating chunk is never written.
+    pub(crate) fn write_to<W>(
+        &self, mut writer: W, chunked: bool,
+    ) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let mut file = File::open(&self.path)?.take(self.len);
+        let mut buffer = vec![0; CHUNK_SIZE];
+
+        // Read file in chunks, until the announced length is written
+        let mut remaining = self.len;
+        while remaining > 0 {
+            let n = match file.read(&mut buffer) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            // Write chunk, framed if necessary
+            if chunked {
+                write!(writer, "{n:X}\r\n")?;
+                writer.write_all(&buffer[..n])?;
+                writer.write_all(b"\r\n")?;
+            } else {
+                writer.write_all(&buffer[..n])?;
+            }
+            remaining -= n as u64;
+        }
+
+        // No errors occurred
+        Ok(())
+    }
+}
+
+#[allow(clippy::must_use_candidate)]
+impl FileBody {
+    /// Returns the path of the file.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the length of the file, as announced in the response.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns whether the file is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
@@ -22,10 +22,13 @@
 
 //! HTTP response headers.
 
-use std::collections::btree_map::Iter;
+use std::borrow::Cow;
+use std::collections::btree_map;
 use std::collections::BTreeMap;
 use std::fmt;
+use std::iter::FusedIterator;
 
+use crate::http::component::sanitize;
 use crate::http::Header;
 
 // ----------------------------------------------------------------------------
@@ -40,7 +43,9 @@ use crate::http::Header;
 /// one that optimizes for performance.
 ///
 /// As keys are integers, it's better to use a [`BTreeMap`] than a [`HashMap`],
-/// because the latter is 3x slower for integer keys.
+/// because the latter is 3x slower for integer keys. Each key is paired with
+/// the position of the value, so headers that can't be combined into a single
+/// comma-separated list, e.g., [`Header::SetCookie`], can be repeated.
 ///
 /// [`HashMap`]: std::collections::HashMap
 ///
@@ -57,10 +62,21 @@ use crate::http::Header;
 /// // Obtain string representation
 /// println!("{headers}");
 /// ```
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Headers {
-    /// Ordered map of headers.
-    inner: BTreeMap<Header, String>,
+    /// Ordered map of headers and positions of values.
+    inner: BTreeMap<(Header, usize), String>,
+}
+
+/// Iterator over HTTP response headers.
+///
+/// Repeated headers are yielded once for each value, in the order in which
+/// they were added.
+#[derive(Clone, Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Iter<'a> {
+    /// Iterator over ordered map of headers.
+    inner: btree_map::Iter<'a, (Header, usize), String>,
 }
 
 // ----------------------------------------------------------------------------
@@ -102,7 +118,7 @@ impl Headers {
     #[inline]
     #[must_use]
     pub fn get(&self, header: Header) -> Option<&str> {
-        self.inner.get(&header).map(AsRef::as_ref)
+        self.inner.get(&(header, 0)).map(AsRef::as_ref)
     }
 
     /// Returns whether the header is contained.
@@ -124,11 +140,45 @@ impl Headers {
     #[inline]
     #[must_use]
     pub fn contains(&self, header: Header) -> bool {
-        self.inner.contains_key(&header)
+        self.inner.contains_key(&(header, 0))
+    }
+
+    /// Returns whether the header lists the given token.
+    ///
+    /// The value is split into a comma-separated list, and each element is
+    /// trimmed and compared case-insensitively, which is how options of, e.g.,
+    /// [`Header::Connection`] or [`Header::Upgrade`] must be compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and add header
+    /// let mut headers = Headers::new();
+    /// headers.put(Header::Connection, "keep-alive, Upgrade");
+    ///
+    /// // Ensure presence of tokens
+    /// assert!(headers.contains_token(Header::Connection, "keep-alive"));
+    /// assert!(headers.contains_token(Header::Connection, "upgrade"));
+    /// assert!(!headers.contains_token(Header::Connection, "close"));
+    /// ```
+    #[must_use]
+    pub fn contains_token(&self, header: Header, token: &str) -> bool {
+        self.get(header).is_some_and(|value| {
+            value
+                .split(',')
+                .any(|option| option.trim().eq_ignore_ascii_case(token))
+        })
     }
 
     /// Updates the given header.
     ///
+    /// All values of the header are replaced with the given value. Carriage
+    /// returns, line feeds and null characters are removed from the
+    /// value, as they would allow to inject headers or split the response.
+    ///
     /// # Examples
     ///
     /// ```
@@ -139,16 +189,140 @@ impl Headers {
     /// let mut headers = Headers::new();
     /// headers.put(Header::ContentType, "text/plain");
     /// ```
+    ///
+    /// Values containing line breaks are sanitized:
+    ///
+    /// ```
+    /// use zense::http::response::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and add header with line break
+    /// let mut headers = Headers::new();
+    /// headers.put(Header::Location, "/\r\nSet-Cookie: evil");
+    ///
+    /// // Ensure header value was sanitized
+    /// let value = headers.get(Header::Location);
+    /// assert_eq!(value, Some("/Set-Cookie: evil"));
+    /// ```
     #[allow(clippy::needless_pass_by_value)]
     #[inline]
     pub fn put<V>(&mut self, header: Header, value: V)
     where
         V: ToString,
     {
-        self.inner.insert(header, value.to_string());
+        let value = sanitize(Cow::Owned(value.to_string()));
+        self.remove(header);
+        self.inner.insert((header, 0), value.into_owned());
+    }
+
+    /// Appends the given header, keeping present values.
+    ///
+    /// This is necessary for headers that can be repeated, but can't be
+    /// combined into a single comma-separated list, most notably
+    /// [`Header::SetCookie`], since every cookie needs its own line. When
+    /// the header is absent, this is equivalent to [`Headers::put`], and
+    /// [`Headers::get`] always returns the first value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and add headers
+    /// let mut headers = Headers::new();
+    /// headers.put(Header::SetCookie, "a=1");
+    /// headers.append(Header::SetCookie, "b=2");
+    ///
+    /// // Ensure both values are retained
+    /// let values = headers.get_all(Header::SetCookie).collect::<Vec<_>>();
+    /// assert_eq!(values, ["a=1", "b=2"]);
+    /// assert_eq!(headers.get(Header::SetCookie), Some("a=1"));
+    ///
+    /// // Obtain string representation
+    /// assert_eq!(headers.to_string(), concat!(
+    ///     "Set-Cookie: a=1\r\n",
+    ///     "Set-Cookie: b=2\r\n",
+    /// ));
+    /// ```
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn append<V>(&mut self, header: Header, value: V)
+    where
+        V: ToString,
+    {
+        let value = sanitize(Cow::Owned(value.to_string()));
+        let index = self
+            .inner
+            .range((header, 0)..=(header, usize::MAX))
+            .next_back()
+            .map_or(0, |((_, index), _)| index + 1);
+        self.inner.insert((header, index), value.into_owned());
+    }
+
+    /// Returns an iterator over all values for the given header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and add headers
+    /// let mut headers = Headers::new();
+    /// headers.append(Header::SetCookie, "a=1");
+    /// headers.append(Header::SetCookie, "b=2");
+    ///
+    /// // Iterate over header values
+    /// for value in headers.get_all(Header::SetCookie) {
+    ///     println!("{value}");
+    /// }
+    /// ```
+    pub fn get_all(&self, header: Header) -> impl Iterator<Item = &str> {
+        self.inner
+            .range((header, 0)..=(header, usize::MAX))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Adds the given header, if it's absent.
+    ///
+    /// This returns whether the header was added, and leaves present values
+    /// untouched, which is useful for middlewares that set default headers,
+    /// so values set by handlers always take precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::Headers;
+    /// use zense::http::Header;
+    ///
+    /// // Create header map and add header
+    /// let mut headers = Headers::new();
+    /// headers.put(Header::Server, "custom");
+    ///
+    /// // Add header if absent - present value is preserved
+    /// assert!(!headers.put_if_absent(Header::Server, "zense"));
+    /// assert_eq!(headers.get(Header::Server), Some("custom"));
+    ///
+    /// // Add header if absent - missing value is added
+    /// assert!(headers.put_if_absent(Header::Date, "today"));
+    /// assert_eq!(headers.get(Header::Date), Some("today"));
+    /// ```
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn put_if_absent<V>(&mut self, header: Header, value: V) -> bool
+    where
+        V: ToString,
+    {
+        match self.inner.entry((header, 0)) {
+            btree_map::Entry::Occupied(_) => false,
+            btree_map::Entry::Vacant(entry) => {
+                let value = sanitize(Cow::Owned(value.to_string()));
+                entry.insert(value.into_owned());
+                true
+            }
+        }
     }
 
-    /// Removes the given header.
+    /// Removes the given header, including all of its values.
     ///
     /// # Examples
     ///
@@ -165,7 +339,7 @@ impl Headers {
     /// ```
     #[inline]
     pub fn remove(&mut self, header: Header) {
-        self.inner.remove(&header);
+        self.inner.retain(|(key, _), _| *key != header);
     }
 
     /// Returns an iterator over the header map.
@@ -186,14 +360,14 @@ impl Headers {
     /// }
     /// ```
     #[inline]
-    pub fn iter(&self) -> Iter<Header, String> {
-        self.inner.iter()
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { inner: self.inner.iter() }
     }
 }
 
 #[allow(clippy::must_use_candidate)]
 impl Headers {
-    /// Returns the number of headers.
+    /// Returns the number of headers, counting each value of repeated ones.
     #[inline]
     pub fn len(&self) -> usize {
         self.inner.len()
@@ -212,7 +386,7 @@ impl Headers {
 
 impl<'a> IntoIterator for &'a Headers {
     type Item = (&'a Header, &'a String);
-    type IntoIter = Iter<'a, Header, String>;
+    type IntoIter = Iter<'a>;
 
     /// Creates an iterator over the header map.
     ///
@@ -239,10 +413,44 @@ impl<'a> IntoIterator for &'a Headers {
 
 // ----------------------------------------------------------------------------
 
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a Header, &'a String);
+
+    /// Returns the next header and value.
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|((header, _), value)| (header, value))
+    }
+
+    /// Returns the bounds on the remaining length of the iterator.
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    /// Returns the next header and value from the end.
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|((header, _), value)| (header, value))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+// ----------------------------------------------------------------------------
+
 impl fmt::Display for Headers {
     /// Formats the header map for display.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for (header, value) in &self.inner {
+        for (header, value) in self {
             f.write_str(header.name())?;
             f.write_str(": ")?;
             f.write_str(value)?;
@@ -0,0 +1,263 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Server-sent events.
+
+use std::fmt::{self, Write};
+use std::iter;
+use std::time::Duration;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Server-sent event.
+///
+/// Events are sent as part of a `text/event-stream` body, as defined in the
+/// [HTML Living Standard], and consist of lines of fields, terminated by an
+/// empty line. Data that spans multiple lines is split into multiple `data`
+/// fields, which the client joins again, while line breaks in the event name
+/// and identifier are removed, as they would break the framing. Events that
+/// only consist of a comment are ignored by clients, and can be sent to keep
+/// connections alive. See [`ResponseExt::sse_batch`][] for creating a
+/// response.
+///
+/// [HTML Living Standard]: https://html.spec.whatwg.org/multipage/server-sent-events.html
+/// [`ResponseExt::sse_batch`]: crate::http::response::ResponseExt::sse_batch
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::response::sse::Event;
+///
+/// // Create event and format it
+/// let event = Event::new()
+///     .event("update")
+///     .id("1")
+///     .data("Hello\nworld");
+/// assert_eq!(
+///     event.to_string(),
+///     "event: update\nid: 1\ndata: Hello\ndata: world\n\n"
+/// );
+///
+/// // Create comment for keeping the connection alive
+/// let event = Event::comment("keep-alive");
+/// assert_eq!(event.to_string(), ": keep-alive\n\n");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Event {
+    /// Comment.
+    comment: Option<String>,
+    /// Event name.
+    name: Option<String>,
+    /// Event identifier.
+    id: Option<String>,
+    /// Event data.
+    data: Option<String>,
+    /// Reconnection time.
+    retry: Option<Duration>,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Event {
+    /// Creates an event.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::sse::Event;
+    ///
+    /// // Create event
+    /// let event = Event::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an event that only consists of a comment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::sse::Event;
+    ///
+    /// // Create comment
+    /// let event = Event::comment("keep-alive");
+    /// ```
+    #[must_use]
+    pub fn comment<C>(comment: C) -> Self
+    where
+        C: Into<String>,
+    {
+        Self {
+            comment: Some(comment.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the name of the event.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::sse::Event;
+    ///
+    /// // Create event and set name
+    /// let event = Event::new()
+    ///     .event("update");
+    /// ```
+    #[must_use]
+    pub fn event<E>(mut self, event: E) -> Self
+    where
+        E: Into<String>,
+    {
+        self.name = Some(event.into());
+        self
+    }
+
+    /// Sets the identifier of the event.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::sse::Event;
+    ///
+    /// // Create event and set identifier
+    /// let event = Event::new()
+    ///     .id("1");
+    /// ```
+    #[must_use]
+    pub fn id<I>(mut self, id: I) -> Self
+    where
+        I: Into<String>,
+    {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the data of the event.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::response::sse::Event;
+    ///
+    /// // Create event and set data
+    /// let event = Event::new()
+    ///     .data("Hello world");
+    /// ```
+    #[must_use]
+    pub fn data<D>(mut self, data: D) -> Self
+    where
+        D: Into<String>,
+    {
+        self.data = Some(data.into());
+        self
+    }
+
+    /// Sets the reconnection time of the event.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use zense::http::response::sse::Event;
+    ///
+    /// // Create event and set reconnection time
+    /// let event = Event::new()
+    ///     .retry(Duration::from_secs(3));
+    /// assert_eq!(event.to_string(), "retry: 3000\n\n");
+    /// ```
+    #[must_use]
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl fmt::Display for Event {
+    /// Formats the event for display.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(comment) = &self.comment {
+            for line in lines(comment) {
+                writeln!(f, ": {line}")?;
+            }
+        }
+
+        // Write single-line fields, removing line breaks
+        for (name, value) in [("event", &self.name), ("id", &self.id)] {
+            if let Some(value) = value {
+                f.write_str(name)?;
+                f.write_str(": ")?;
+                for char in value.chars().filter(|&c| c != '\r' && c != '\n') {
+                    f.write_char(char)?;
+                }
+                f.write_char('\n')?;
+            }
+        }
+
+        // Write data, splitting it into one field per line
+        if let Some(data) = &self.data {
+            for line in lines(data) {
+                writeln!(f, "data: {line}")?;
+            }
+        }
+
+        // Write reconnection time, and terminate event with an empty line
+        if let Some(retry) = self.retry {
+            writeln!(f, "retry: {}", retry.as_millis())?;
+        }
+        f.write_char('\n')
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Splits the given value into lines, accepting all line terminators.
+fn lines(value: &str) -> impl Iterator<Item = &str> {
+    let mut rest = Some(value);
+    iter::from_fn(move || {
+        let value = rest?;
+        if let Some(index) = value.find(['\r', '\n']) {
+            let skip = if value[index..].starts_with("\r\n") {
+                2
+            } else {
+                1
+            };
+            rest = Some(&value[index + skip..]);
+            Some(&value[..index])
+        } else {
+            rest = None;
+            Some(value)
+        }
+    })
+}
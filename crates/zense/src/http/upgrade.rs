@@ -0,0 +1,149 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! HTTP protocol upgrades.
+
+use super::response::ResponseExt;
+use super::{Header, Method, Request, Response};
+
+mod digest;
+mod error;
+
+pub use error::{Error, Result};
+
+// ----------------------------------------------------------------------------
+// Constants
+// ----------------------------------------------------------------------------
+
+/// WebSocket GUID, appended to the key when computing the accept value.
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Validates a WebSocket upgrade request, and creates the handshake response.
+///
+/// As defined in [RFC 6455], the request must use the `GET` method, contain
+/// the `websocket` token in the [`Header::Upgrade`] header, and the `upgrade`
+/// token in the [`Header::Connection`] header, both matched case-insensitively.
+/// Additionally, the [`Header::SecWebSocketKey`] header must contain a 16-byte
+/// value encoded with Base64, and the [`Header::SecWebSocketVersion`] header
+/// must contain version 13. The returned response has status "101 Switching
+/// Protocols", after which the connection must be handed over to a WebSocket
+/// implementation, as framing is out of scope for this crate.
+///
+/// [RFC 6455]: https://www.rfc-editor.org/rfc/rfc6455
+///
+/// # Errors
+///
+/// This function returns [`Error::Method`], if the method isn't `GET`, and
+/// [`Error::Header`], if a header is missing or invalid, which should be
+/// answered with "400 Bad Request". [`Error::Version`] is returned for other
+/// versions than 13, which should be answered with "426 Upgrade Required",
+/// and a [`Header::SecWebSocketVersion`] header listing the version.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::http::{upgrade, Header, Request, Status};
+///
+/// // Create upgrade request
+/// let req = Request::new()
+///     .uri("/chat")
+///     .header(Header::Upgrade, "websocket")
+///     .header(Header::Connection, "keep-alive, Upgrade")
+///     .header(Header::SecWebSocketKey, "dGhlIHNhbXBsZSBub25jZQ==")
+///     .header(Header::SecWebSocketVersion, 13);
+///
+/// // Create handshake response
+/// let res = upgrade::websocket(&req)?;
+/// assert_eq!(res.status, Status::SwitchingProtocols);
+/// assert_eq!(
+///     res.headers.get(Header::SecWebSocketAccept),
+///     Some("s3pPLMBiTxaQ9kYGzzhZRbK+xOo=")
+/// );
+///
+/// // Create handshake response for unsupported version
+/// let req = req.header(Header::SecWebSocketVersion, 8);
+/// assert!(matches!(upgrade::websocket(&req), Err(upgrade::Error::Version)));
+/// # Ok(())
+/// # }
+/// ```
+pub fn websocket(req: &Request) -> Result<Response> {
+    if req.method != Method::Get {
+        return Err(Error::Method);
+    }
+
+    // Ensure the connection is meant to be upgraded to the WebSocket protocol
+    for (header, token) in [
+        (Header::Upgrade, "websocket"),
+        (Header::Connection, "upgrade"),
+    ] {
+        if !req.headers.contains_token(header, token) {
+            return Err(Error::Header(header));
+        }
+    }
+
+    // Ensure key is present, and a Base64-encoded 16-byte value
+    let key = req.headers.get(Header::SecWebSocketKey).map(str::trim);
+    let Some(key) = key.filter(|key| digest::is_base64_16(key)) else {
+        return Err(Error::Header(Header::SecWebSocketKey));
+    };
+
+    // Ensure version is 13, the only version defined by RFC 6455
+    let version = req.headers.get(Header::SecWebSocketVersion);
+    if version.map(str::trim) != Some("13") {
+        return Err(Error::Version);
+    }
+
+    // Return handshake response
+    Ok(Response::switching_protocols("websocket")
+        .header(Header::SecWebSocketAccept, accept_key(key)))
+}
+
+/// Computes the [`Header::SecWebSocketAccept`] value for the given key.
+///
+/// The value is the Base64-encoded SHA-1 digest of the key concatenated with
+/// the GUID defined in [RFC 6455], which proves to the client that the server
+/// understood the WebSocket handshake.
+///
+/// [RFC 6455]: https://www.rfc-editor.org/rfc/rfc6455
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::upgrade::accept_key;
+///
+/// // Compute accept value for the key from RFC 6455
+/// let value = accept_key("dGhlIHNhbXBsZSBub25jZQ==");
+/// assert_eq!(value, "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+/// ```
+#[must_use]
+pub fn accept_key(key: &str) -> String {
+    let mut data = String::with_capacity(key.len() + WEBSOCKET_GUID.len());
+    data.push_str(key);
+    data.push_str(WEBSOCKET_GUID);
+    digest::base64(&digest::sha1(data.as_bytes()))
+}
@@ -0,0 +1,448 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Integration with `hyper`.
+//!
+//! Requests and responses of `hyper` are converted from and into the types of
+//! this crate, which is not free, as both sides own their data differently.
+//! When converting a request, the path is decoded into an owned string, and
+//! each known header value is copied into an owned string, while unknown
+//! headers are skipped. The body is moved without copying if the [`Bytes`]
+//! are uniquely owned, which is the case for collected bodies, and copied
+//! otherwise. When converting a response, each header value is copied once
+//! more, as `hyper` validates them, but the body is always moved. For most
+//! applications, this is negligible compared to the cost of I/O. File bodies
+//! are read into memory, as responses are converted into [`Bytes`], so large
+//! files are better served with the [`Server`][] of this crate.
+//!
+//! [`Server`]: crate::server::Server
+
+use ::http::header::{HeaderName, HeaderValue};
+use ::http::{StatusCode, Version as HttpVersion};
+use ::hyper::body::Body;
+use ::hyper::service::Service;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use std::borrow::Cow;
+use std::error;
+use std::fmt;
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::result;
+use std::sync::Arc;
+
+use crate::handler::{self, TryIntoHandler};
+use crate::http::request::Result;
+use crate::http::response::ResponseExt;
+use crate::http::{Header, Request, Response, Status, Uri, Version};
+use crate::integrations::local::Local;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Service wrapping handlers for `hyper`.
+///
+/// This adapter allows to serve a [`Handler`][] with `hyper`, e.g., by passing
+/// it to a connection builder of `hyper`. As handlers don't need to be [`Send`]
+/// and [`Sync`], while the executors of `hyper` move services and futures
+/// between threads, the service is created from a factory, which is invoked
+/// once by each thread handling requests, like for the [`Server`][]. State
+/// that should be shared by all threads must be created outside of the
+/// factory, and cloned into each handler.
+///
+/// The body of each request is collected before the request is converted and
+/// passed to the handler, which runs on the executor of `hyper`, so it should
+/// not block for long. Requests with bodies exceeding the maximum length are
+/// answered with "413 Content Too Large", and requests with methods that are
+/// not supported by this crate are answered with "501 Not Implemented". The
+/// factory is shared between clones of the service.
+///
+/// As `hyper` doesn't pass connection information to services, the peer of
+/// requests is only set when it's given with [`HyperService::peer`], which
+/// is why a service should be cloned for each accepted connection.
+///
+/// [`Handler`]: crate::handler::Handler
+/// [`Server`]: crate::server::Server
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use bytes::Bytes;
+/// use http_body_util::Full;
+/// use hyper::service::Service;
+/// use std::future::Future;
+/// use std::pin::pin;
+/// use std::sync::Arc;
+/// use std::task::{Context, Poll, Wake, Waker};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Request, Response};
+/// use zense::integrations::hyper::HyperService;
+/// use zense::router::{Params, Router};
+///
+/// // Create service from a factory for a router
+/// let service = HyperService::new(|| {
+///     Router::default().get("/{drink}", |_: Request, params: Params| {
+///         Response::text(params.get("drink").unwrap_or_default().to_owned())
+///     })
+/// })?;
+///
+/// // Define waker, as collected bodies are always ready
+/// struct Noop;
+/// impl Wake for Noop {
+///     fn wake(self: Arc<Self>) {}
+/// }
+///
+/// // Create request of hyper, and poll service to completion
+/// let req = http::Request::get("/coffee").body(Full::new(Bytes::new()))?;
+/// let waker = Waker::from(Arc::new(Noop));
+/// let mut cx = Context::from_waker(&waker);
+/// let Poll::Ready(res) = pin!(service.call(req)).poll(&mut cx) else {
+///     unreachable!()
+/// };
+/// let res = res.map_err(|err| err as Box<dyn Error>)?;
+/// assert_eq!(res.status(), http::StatusCode::OK);
+/// assert_eq!(res.headers()["content-length"], "6");
+/// # Ok(())
+/// # }
+/// ```
+pub struct HyperService<F> {
+    /// Handlers, one for each thread.
+    local: Arc<Local<F>>,
+    /// Maximum length of request bodies.
+    max_body_len: usize,
+    /// Peer of the connection.
+    peer: Option<IpAddr>,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl<F, T> HyperService<F>
+where
+    F: Fn() -> T + Send + Sync + 'static,
+    T: TryIntoHandler,
+    T::Output: 'static,
+{
+    /// Creates a service wrapping handlers created by the given factory.
+    ///
+    /// The factory is invoked right away for the current thread, so errors
+    /// when converting its result into a handler surface here, and not when
+    /// serving requests. The maximum length of request bodies defaults to 1
+    /// MiB, the same as the maximum length of the [`Server`][].
+    ///
+    /// [`Server`]: crate::server::Server
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`handler::Error`], if the conversion into a
+    /// handler fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::NotFound;
+    /// use zense::integrations::hyper::HyperService;
+    ///
+    /// // Create service from handler factory
+    /// let service = HyperService::new(|| NotFound)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(factory: F) -> handler::Result<Self> {
+        Ok(Self {
+            local: Arc::new(Local::new(factory)?),
+            max_body_len: 1024 * 1024,
+            peer: None,
+        })
+    }
+
+    /// Sets the maximum length of request bodies.
+    ///
+    /// Requests with bodies exceeding this length are answered with "413
+    /// Content Too Large", without passing them to the handler. The body is
+    /// collected up to the maximum length, so a client can't exhaust memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::NotFound;
+    /// use zense::integrations::hyper::HyperService;
+    ///
+    /// // Create service and set maximum length of request bodies
+    /// let service = HyperService::new(|| NotFound)?
+    ///     .max_body_len(16 * 1024 * 1024);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn max_body_len(mut self, max_body_len: usize) -> Self {
+        self.max_body_len = max_body_len;
+        self
+    }
+
+    /// Sets the peer of the connection.
+    ///
+    /// The peer is set on each request passed to the handler, which allows to
+    /// use [`Request::client_ip`][], e.g., for logging. Services should
+    /// be cloned for each accepted connection, sharing the factory.
+    ///
+    /// [`Request::client_ip`]: crate::http::Request::client_ip
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::NotFound;
+    /// use zense::integrations::hyper::HyperService;
+    ///
+    /// // Create service, and clone it for an accepted connection
+    /// let service = HyperService::new(|| NotFound)?;
+    /// let service = service.clone().peer("192.0.2.1".parse()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn peer(mut self, peer: IpAddr) -> Self {
+        self.peer = Some(peer);
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl<F, T, B> Service<::http::Request<B>> for HyperService<F>
+where
+    F: Fn() -> T + Send + Sync + 'static,
+    T: TryIntoHandler,
+    T::Output: 'static,
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    type Response = ::http::Response<Full<Bytes>>;
+    type Error = BoxError;
+    type Future = BoxFuture<result::Result<Self::Response, Self::Error>>;
+
+    /// Handles the given request with the handler of the current thread, after
+    /// collecting the body.
+    fn call(&self, req: ::http::Request<B>) -> Self::Future {
+        let local = Arc::clone(&self.local);
+        let max_body_len = self.max_body_len;
+        let peer = self.peer;
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+
+            // Collect body, and answer with an error if it exceeds the maximum
+            // length - errors of the connection are passed through
+            let body = match Limited::new(body, max_body_len).collect().await {
+                Ok(body) => body.to_bytes(),
+                Err(err) => {
+                    if !err.is::<LengthLimitError>() {
+                        return Err(err);
+                    }
+                    let res = Response::from_status(Status::PayloadTooLarge);
+                    return Ok(into_response(res).map(Full::new));
+                }
+            };
+
+            // Convert request, and answer with handler, or with an error if
+            // the method is not supported by this crate
+            let req = ::http::Request::from_parts(parts, body);
+            let res = match from_request(req) {
+                Ok(mut req) => {
+                    req.peer = peer;
+                    local.handle(req)
+                }
+                Err(_) => Response::from_status(Status::NotImplemented),
+            };
+
+            // Convert response
+            Ok(into_response(res).map(Full::new))
+        })
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl<F> Clone for HyperService<F> {
+    /// Clones the service, sharing the factory.
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            local: Arc::clone(&self.local),
+            max_body_len: self.max_body_len,
+            peer: self.peer,
+        }
+    }
+}
+
+impl<F> fmt::Debug for HyperService<F> {
+    /// Formats the service for debugging.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HyperService").finish_non_exhaustive()
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Converts a request of `hyper` into a request.
+///
+/// Method, URI, version, known headers and body are copied or moved, as
+/// explained in the [module documentation][self]. Headers that appear more
+/// than once are joined with commas.
+///
+/// # Errors
+///
+/// This function returns [`Error::Component`][], if the method of the given
+/// request is not supported by this crate.
+///
+/// [`Error::Component`]: crate::http::request::Error::Component
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use bytes::Bytes;
+/// use zense::handler::Handler;
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Method, Request, Response};
+/// use zense::integrations::hyper::{from_request, into_response};
+///
+/// // Create request of hyper, and convert it
+/// let req = http::Request::post("/coffee?milk=true")
+///     .header("content-type", "text/plain")
+///     .header("x-unknown", "skipped")
+///     .body(Bytes::from("Hello world"))?;
+/// let req = from_request(req)?;
+/// assert_eq!(req.method, Method::Post);
+/// assert_eq!(req.uri.to_string(), "/coffee?milk=true");
+/// assert_eq!(req.headers.get(Header::ContentType), Some("text/plain"));
+/// assert_eq!(req.body.as_ref(), b"Hello world");
+///
+/// // Handle request, and convert response back
+/// let handler = |req: Request| Response::text(req.body.into_owned());
+/// let res = into_response(handler.handle(req));
+/// assert_eq!(res.status(), http::StatusCode::OK);
+/// assert_eq!(res.headers()["content-length"], "11");
+/// assert_eq!(res.body().as_ref(), b"Hello world");
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_request(req: ::http::Request<Bytes>) -> Result<Request<'static>> {
+    let (parts, body) = req.into_parts();
+    let method = parts.method.as_str().parse()?;
+    let uri = Uri::from(parts.uri.path_and_query().map_or("/", |p| p.as_str()));
+    let version = match parts.version {
+        HttpVersion::HTTP_09 | HttpVersion::HTTP_10 => Version::Http10,
+        _ => Version::Http11,
+    };
+
+    // Copy known headers, joining values of headers that appear more than once
+    let mut req = Request::new().method(method).uri(uri.into_owned());
+    req.version = version;
+    for (name, value) in &parts.headers {
+        let Ok(header) = Header::from_bytes(name.as_str().as_bytes()) else {
+            continue;
+        };
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        let value = match req.headers.get(header) {
+            Some(prior) => Cow::Owned(format!("{prior}, {value}")),
+            None => Cow::Owned(value.to_owned()),
+        };
+        req.headers.put(header, value);
+    }
+
+    // Move body, and return request
+    req.body = Cow::Owned(Vec::from(body));
+    Ok(req)
+}
+
+/// Converts a response into a response of `hyper`.
+///
+/// Status, version, headers and body are copied or moved, as explained in the
+/// [module documentation][self]. Header values that `hyper` considers invalid
+/// are skipped, and trailers are not converted, as `hyper` expects them to be
+/// sent as part of the body. File bodies are read and appended to the body,
+/// and if that fails, "500 Internal Server Error" is returned instead.
+#[must_use]
+pub fn into_response(res: Response) -> ::http::Response<Bytes> {
+    let status = StatusCode::from_u16(res.status as u16)
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let version = match res.version {
+        Version::Http10 => HttpVersion::HTTP_10,
+        Version::Http11 => HttpVersion::HTTP_11,
+    };
+
+    // Read file body, if any, as the body must be kept in memory
+    let mut body = res.body;
+    if let Some(file) = &res.file {
+        if file.write_to(&mut body, false).is_err() {
+            let status = Status::InternalServerError;
+            return into_response(
+                Response::from_status(status).version(res.version),
+            );
+        }
+    }
+
+    // Create response, and copy headers
+    let mut response = ::http::Response::new(Bytes::from(body));
+    *response.status_mut() = status;
+    *response.version_mut() = version;
+    for (header, value) in &res.headers {
+        let name = HeaderName::from_bytes(header.name().as_bytes());
+        if let Some((name, value)) =
+            name.ok().zip(value.parse::<HeaderValue>().ok())
+        {
+            response.headers_mut().append(name, value);
+        }
+    }
+
+    // Return response
+    response
+}
+
+// ----------------------------------------------------------------------------
+// Type aliases
+// ----------------------------------------------------------------------------
+
+/// Boxed error, as returned by services.
+type BoxError = Box<dyn error::Error + Send + Sync>;
+
+/// Boxed future, as returned by services.
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
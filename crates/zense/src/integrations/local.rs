@@ -0,0 +1,142 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Thread-local handlers.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::handler::{Handler, Result, TryIntoHandler};
+use crate::http::response::ResponseExt;
+use crate::http::{Request, Response, Status};
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Handlers created by a factory, one for each thread.
+///
+/// Handlers don't need to be [`Send`] and [`Sync`], so integrations with
+/// runtimes that move services between threads can't share them. Instead, the
+/// factory is invoked the first time a thread handles a request, and the
+/// handler is kept for the lifetime of the thread, or until this is dropped.
+pub(crate) struct Local<F> {
+    /// Identifier in thread-local storage.
+    id: usize,
+    /// Factory for handlers.
+    factory: F,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl<F, T> Local<F>
+where
+    F: Fn() -> T,
+    T: TryIntoHandler,
+    T::Output: 'static,
+{
+    /// Creates thread-local handlers with the given factory.
+    ///
+    /// The factory is invoked once for the current thread, so errors when
+    /// converting its result into a handler are returned right away.
+    pub(crate) fn new(factory: F) -> Result<Self> {
+        static ID: AtomicUsize = AtomicUsize::new(0);
+        let local = Self {
+            id: ID.fetch_add(1, Ordering::Relaxed),
+            factory,
+        };
+        let handler = (local.factory)().try_into_handler()?;
+        HANDLERS.with_borrow_mut(|handlers| {
+            handlers.insert(local.id, Rc::new(handler));
+        });
+        Ok(local)
+    }
+
+    /// Handles the given request with the handler of the current thread.
+    ///
+    /// If the handler of the current thread can't be created, the request is
+    /// answered with "500 Internal Server Error", which only happens when the
+    /// factory doesn't return the same result on every invocation.
+    pub(crate) fn handle(&self, req: Request) -> Response {
+        let handler = HANDLERS
+            .with_borrow(|handlers| handlers.get(&self.id).map(Rc::clone));
+
+        // Create handler for the current thread, if necessary, and release
+        // the storage before handling, as handlers might be nested
+        let handler = match handler {
+            Some(handler) => handler,
+            None => match (self.factory)().try_into_handler() {
+                Ok(handler) => {
+                    let handler: Rc<dyn Any> = Rc::new(handler);
+                    HANDLERS.with_borrow_mut(|handlers| {
+                        handlers.insert(self.id, Rc::clone(&handler));
+                    });
+                    handler
+                }
+                Err(_) => {
+                    return Response::from_status(Status::InternalServerError);
+                }
+            },
+        };
+
+        // Handle request with the handler of the current thread
+        match handler.downcast_ref::<T::Output>() {
+            Some(handler) => handler.handle(req),
+            None => Response::from_status(Status::InternalServerError),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl<F> Drop for Local<F> {
+    /// Drops the handler of the current thread.
+    ///
+    /// Handlers of other threads are dropped when those threads exit, as they
+    /// can't be reached from here.
+    fn drop(&mut self) {
+        let handler = HANDLERS.try_with(|handlers| {
+            let mut handlers = handlers.try_borrow_mut().ok()?;
+            handlers.remove(&self.id)
+        });
+
+        // Drop handler after releasing the storage, as handlers might be nested
+        drop(handler);
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Thread-local storage
+// ----------------------------------------------------------------------------
+
+thread_local! {
+    /// Handlers of the current thread, by identifier.
+    static HANDLERS: RefCell<HashMap<usize, Rc<dyn Any>>> =
+        RefCell::new(HashMap::new());
+}
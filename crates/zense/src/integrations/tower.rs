@@ -0,0 +1,337 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Integration with `tower`.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::future::{self, Future, Ready};
+use std::pin::pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use tower_service::Service;
+
+use crate::handler::{self, Handler, TryIntoHandler};
+use crate::http::response::ResponseExt;
+use crate::http::{Request, Response, Status};
+use crate::integrations::local::Local;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Service wrapping handlers.
+///
+/// This adapter allows to use a [`Handler`] as a [`Service`], e.g., in servers
+/// built on top of `tower`. As handlers don't need to be [`Send`] and [`Sync`],
+/// while servers built on top of `tower` usually move services between threads,
+/// the service is created from a factory, which is invoked once by each thread
+/// handling requests, like for the [`Server`][]. State that should be shared
+/// by all threads must be created outside of the factory, and cloned into each
+/// handler. As handlers are synchronous and infallible, the returned future is
+/// always ready, and the service is always ready to accept requests. The
+/// factory is shared between clones of the service.
+///
+/// [`Server`]: crate::server::Server
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::future::Future;
+/// use std::pin::pin;
+/// use std::sync::Arc;
+/// use std::task::{Context, Poll, Wake, Waker};
+/// use std::thread;
+/// use tower_service::Service;
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Request, Response};
+/// use zense::integrations::tower::HandlerService;
+/// use zense::router::{Params, Router};
+///
+/// // Create service from a factory for a router
+/// let mut service = HandlerService::new(|| {
+///     Router::default().get("/{drink}", |_: Request, params: Params| {
+///         Response::text(params.get("drink").unwrap_or_default().to_owned())
+///     })
+/// })?;
+///
+/// // Define waker, as the service and its futures are always ready
+/// struct Noop;
+/// impl Wake for Noop {
+///     fn wake(self: Arc<Self>) {}
+/// }
+///
+/// // Move service to another thread, wait for it to be ready, then call it
+/// // and resolve the response, which creates the handler of that thread
+/// let res = thread::spawn(move || {
+///     let waker = Waker::from(Arc::new(Noop));
+///     let mut cx = Context::from_waker(&waker);
+///     let Poll::Ready(Ok(())) = service.poll_ready(&mut cx) else {
+///         unreachable!()
+///     };
+///     let future = service.call(Request::new().uri("/coffee"));
+///     let Poll::Ready(Ok(res)) = pin!(future).poll(&mut cx) else {
+///         unreachable!()
+///     };
+///     res
+/// });
+/// assert_eq!(res.join().unwrap().body, b"coffee");
+/// # Ok(())
+/// # }
+/// ```
+pub struct HandlerService<F> {
+    /// Handlers, one for each thread.
+    local: Arc<Local<F>>,
+}
+
+/// Handler wrapping a service.
+///
+/// This adapter allows to use a [`Service`] as a [`Handler`], e.g., to reuse
+/// services written for `tower` in a [`Stack`][]. As handlers are synchronous,
+/// the calling thread is blocked until the service is ready and the response
+/// was computed, so the service must not depend on the context of an async
+/// runtime. As is customary for `tower`, the service is cloned for each
+/// request, so requests are handled concurrently, and a service that is slow
+/// to respond doesn't block other threads. Requests are converted into owned
+/// requests, and errors returned by the service are answered with "500
+/// Internal Server Error".
+///
+/// [`Stack`]: crate::handler::Stack
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::Infallible;
+/// use std::future::{ready, Ready};
+/// use std::task::{Context, Poll};
+/// use tower_service::Service;
+/// use zense::handler::Handler;
+/// use zense::http::{Request, Response, Status};
+/// use zense::integrations::tower::ServiceHandler;
+///
+/// // Define service
+/// #[derive(Clone)]
+/// struct Teapot;
+///
+/// // Create service implementation
+/// impl Service<Request<'static>> for Teapot {
+///     type Response = Response;
+///     type Error = Infallible;
+///     type Future = Ready<Result<Response, Infallible>>;
+///
+///     fn poll_ready(
+///         &mut self, _: &mut Context<'_>
+///     ) -> Poll<Result<(), Infallible>> {
+///         Poll::Ready(Ok(()))
+///     }
+///
+///     fn call(&mut self, _: Request<'static>) -> Self::Future {
+///         ready(Ok(Response::new().status(Status::ImATeapot)))
+///     }
+/// }
+///
+/// // Create handler from service
+/// let handler = ServiceHandler::new(Teapot);
+/// let res = handler.handle(Request::new());
+/// assert_eq!(res.status, Status::ImATeapot);
+/// ```
+pub struct ServiceHandler<S> {
+    /// Service.
+    service: Mutex<S>,
+}
+
+/// Waker unparking a thread.
+struct ThreadWaker(Thread);
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl<F, T> HandlerService<F>
+where
+    F: Fn() -> T,
+    T: TryIntoHandler,
+    T::Output: 'static,
+{
+    /// Creates a service wrapping handlers created by the given factory.
+    ///
+    /// The factory is invoked right away for the current thread, so errors
+    /// when converting its result into a handler surface here, and not when
+    /// serving requests.
+    ///
+    /// # Errors
+    ///
+    /// This method returns [`handler::Error`], if the conversion into a
+    /// handler fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::NotFound;
+    /// use zense::integrations::tower::HandlerService;
+    ///
+    /// // Create service from handler factory
+    /// let service = HandlerService::new(|| NotFound)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(factory: F) -> handler::Result<Self> {
+        Ok(Self {
+            local: Arc::new(Local::new(factory)?),
+        })
+    }
+}
+
+impl<S> ServiceHandler<S>
+where
+    S: Service<Request<'static>, Response = Response> + Clone,
+{
+    /// Creates a handler wrapping the given service.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::NotFound;
+    /// use zense::integrations::tower::{HandlerService, ServiceHandler};
+    ///
+    /// // Create handler from service
+    /// let handler = ServiceHandler::new(HandlerService::new(|| NotFound)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn new(service: S) -> Self {
+        Self { service: Mutex::new(service) }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl<'a, F, T> Service<Request<'a>> for HandlerService<F>
+where
+    F: Fn() -> T,
+    T: TryIntoHandler,
+    T::Output: 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Ready<Result<Response, Infallible>>;
+
+    /// Returns whether the service is ready, which is always the case.
+    #[inline]
+    fn poll_ready(
+        &mut self, _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Handles the given request with the handler of the current thread.
+    #[inline]
+    fn call(&mut self, req: Request<'a>) -> Self::Future {
+        future::ready(Ok(self.local.handle(req)))
+    }
+}
+
+impl<S> Handler for ServiceHandler<S>
+where
+    S: Service<Request<'static>, Response = Response> + Clone,
+{
+    /// Handles the given request with a clone of the service.
+    fn handle(&self, req: Request) -> Response {
+        // Clone service, so the lock isn't held while waiting for it
+        let guard = self.service.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut service = guard.clone();
+        drop(guard);
+
+        // Wait for service to be ready, then call it and wait for response
+        let res = block_on(future::poll_fn(|cx| service.poll_ready(cx)))
+            .and_then(|()| block_on(service.call(req.into_owned())));
+
+        // Answer errors with a generic response, as we can't inspect them
+        res.unwrap_or_else(|_| {
+            Response::from_status(Status::InternalServerError)
+        })
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl<F> Clone for HandlerService<F> {
+    /// Clones the service, sharing the factory.
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { local: Arc::clone(&self.local) }
+    }
+}
+
+impl Wake for ThreadWaker {
+    /// Wakes the thread waiting for the future.
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl<F> fmt::Debug for HandlerService<F> {
+    /// Formats the service for debugging.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HandlerService").finish_non_exhaustive()
+    }
+}
+
+impl<S> fmt::Debug for ServiceHandler<S> {
+    /// Formats the handler for debugging.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServiceHandler").finish_non_exhaustive()
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Blocks the current thread until the given future is ready.
+fn block_on<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -24,5 +24,8 @@
 
 pub mod handler;
 pub mod http;
+pub mod integrations;
 pub mod middleware;
 pub mod router;
+#[cfg(feature = "server")]
+pub mod server;
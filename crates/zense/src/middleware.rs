@@ -28,6 +28,23 @@ use crate::handler::{Handler, Result, Scope};
 use crate::http::response::IntoResponse;
 use crate::http::{Request, Response};
 
+pub mod cache;
+pub mod catch;
+#[cfg(feature = "getrandom")]
+pub mod csrf;
+#[cfg(feature = "flate2")]
+pub mod decompress;
+pub mod dump;
+pub mod https;
+mod map;
+pub mod metrics;
+pub mod request_id;
+pub mod retry;
+pub mod rewrite;
+pub mod server_header;
+
+pub use map::{map_request, map_response, MapRequest, MapResponse};
+
 // ----------------------------------------------------------------------------
 // Traits
 // ----------------------------------------------------------------------------
@@ -95,6 +112,19 @@ pub trait Middleware: 'static {
     /// assert_eq!(res.status, Status::ImATeapot);
     /// ```
     fn process(&self, req: Request, next: &dyn Handler) -> Response;
+
+    /// Returns whether the middleware applies to the given request.
+    ///
+    /// Stacks created with [`Stack::first_match`][] use this method to select
+    /// the single middleware that handles the request. Middlewares that are
+    /// scoped to a path, like stacks and routers, return whether the request
+    /// path matches, while all other middlewares apply to any request.
+    ///
+    /// [`Stack::first_match`]: crate::handler::Stack::first_match
+    #[inline]
+    fn matches(&self, _req: &Request) -> bool {
+        true
+    }
 }
 
 // ----------------------------------------------------------------------------
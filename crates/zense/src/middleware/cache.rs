@@ -0,0 +1,536 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Middleware for caching responses.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::handler::Handler;
+use crate::http::cache_control::{self, CacheControl};
+use crate::http::request::Headers;
+use crate::http::{Header, Method, Request, Response, Status, Uri};
+use crate::middleware::Middleware;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware for caching responses.
+///
+/// Responses to requests with safe methods, i.e., `GET` and `HEAD`, are cached
+/// in memory, keyed by method, host and URI, so hosts served by the same stack,
+/// e.g., with a [`HostRouter`][], don't share responses for the same path. The
+/// host is taken from [`Header::Host`], ignoring case and surrounding
+/// whitespace. On a hit, the cached response is sent
+/// with an [`Header::Age`] header, and the remainder of the request processing
+/// chain is skipped. On a miss, the request is passed to the next handler, and
+/// successful responses are stored.
+///
+/// [`Header::CacheControl`] directives are honored on both sides: responses
+/// marked as `no-store` or `private` are never stored, and neither are those
+/// marked as `no-cache`, as they must be revalidated before reuse, which this
+/// cache doesn't support. `max-age` takes precedence over the configured time
+/// to live. Requests marked as `no-cache` bypass the cache lookup, but refresh
+/// the entry, while requests marked as `no-store` bypass the cache entirely.
+///
+/// As the cache is shared between clients, requests carrying credentials, i.e.,
+/// [`Header::Authorization`] or [`Header::Cookie`], bypass the cache entirely,
+/// and responses setting cookies or answering range requests are never stored,
+/// as recommended by [RFC 9111]. Responses with a [`Header::Vary`] header are
+/// only served to requests that match the stored request in the headers named
+/// by it, and responses varying on all headers, i.e., `Vary: *`, are never
+/// stored. Only the most recently stored variant of a response is retained.
+///
+/// [`HostRouter`]: crate::router::HostRouter
+/// [RFC 9111]: https://www.rfc-editor.org/rfc/rfc9111#section-3.5
+///
+/// Entries expire after the configured time to live, and when the cache is
+/// full, the least recently used entry is evicted. The cache is guarded by a
+/// mutex, so it can be safely shared between threads.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Request, Response};
+/// use zense::middleware::cache::ResponseCache;
+///
+/// // Create counter for cache misses
+/// let misses = Arc::new(AtomicUsize::new(0));
+/// let counter = Arc::clone(&misses);
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(ResponseCache::new())
+///     .with(move |_: Request, _: &dyn Handler| {
+///         counter.fetch_add(1, Ordering::Relaxed);
+///         Response::text("Hello world")
+///     })
+///     .try_into_handler()?;
+///
+/// // Handle request twice with stack
+/// let res = stack.handle(Request::new().uri("/coffee"));
+/// assert_eq!(res.headers.get(Header::Age), None);
+/// let res = stack.handle(Request::new().uri("/coffee"));
+/// assert_eq!(res.headers.get(Header::Age), Some("0"));
+///
+/// // Ensure second request was served from cache
+/// assert_eq!(res.body, b"Hello world");
+/// assert_eq!(misses.load(Ordering::Relaxed), 1);
+///
+/// // Handle request with credentials with stack - cache is bypassed
+/// let req = Request::new()
+///     .uri("/coffee")
+///     .header(Header::Authorization, "Bearer secret");
+/// let res = stack.handle(req);
+/// assert_eq!(res.headers.get(Header::Age), None);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Responses that vary on request headers are only served to matching
+/// requests, while responses setting cookies or answering range requests, or
+/// varying on all headers, are never stored:
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Request, Response, Status};
+/// use zense::middleware::cache::ResponseCache;
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(ResponseCache::new())
+///     .with(|req: Request, _: &dyn Handler| {
+///         let res = Response::text("Hello world");
+///         match req.uri.path.as_ref() {
+///             "/vary" => res.header(Header::Vary, "Accept-Language"),
+///             "/vary-all" => res.header(Header::Vary, "*"),
+///             "/cookie" => res.header(Header::SetCookie, "id=1"),
+///             _ => res.status(Status::PartialContent),
+///         }
+///     })
+///     .try_into_handler()?;
+///
+/// // Create request with language
+/// let req = |path: &'static str, language: &'static str| {
+///     Request::new()
+///         .uri(path)
+///         .header(Header::AcceptLanguage, language)
+/// };
+///
+/// // Handle requests with stack - only matching requests are served
+/// stack.handle(req("/vary", "en"));
+/// let res = stack.handle(req("/vary", "de"));
+/// assert_eq!(res.headers.get(Header::Age), None);
+/// let res = stack.handle(req("/vary", "de"));
+/// assert_eq!(res.headers.get(Header::Age), Some("0"));
+///
+/// // Handle requests with stack - responses are never stored
+/// for path in ["/vary-all", "/cookie", "/range"] {
+///     stack.handle(req(path, "en"));
+///     let res = stack.handle(req(path, "en"));
+///     assert_eq!(res.headers.get(Header::Age), None);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Responses are stored per host, so requests for the same path on different
+/// hosts are never answered with each other's responses:
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Request, Response};
+/// use zense::middleware::cache::ResponseCache;
+///
+/// // Create stack with middleware, and handler echoing the host
+/// let stack = Stack::new()
+///     .with(ResponseCache::new())
+///     .with(|req: Request, _: &dyn Handler| {
+///         let host = req.headers.get(Header::Host).unwrap_or_default();
+///         Response::text(host.to_owned())
+///     })
+///     .try_into_handler()?;
+///
+/// // Create request for host
+/// let req = |host: &'static str| {
+///     Request::new()
+///         .uri("/")
+///         .header(Header::Host, host)
+/// };
+///
+/// // Handle requests for both hosts with stack - responses aren't shared
+/// stack.handle(req("a.example"));
+/// let res = stack.handle(req("b.example"));
+/// assert_eq!(res.headers.get(Header::Age), None);
+/// assert_eq!(res.body, b"b.example");
+///
+/// // Handle request with differently cased host - response is served
+/// let res = stack.handle(req("A.Example"));
+/// assert_eq!(res.headers.get(Header::Age), Some("0"));
+/// assert_eq!(res.body, b"a.example");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ResponseCache {
+    /// Time to live of entries.
+    ttl: Duration,
+    /// Maximum number of entries.
+    capacity: usize,
+    /// Cache state.
+    state: Mutex<State>,
+}
+
+/// Cache state.
+#[derive(Debug, Default)]
+struct State {
+    /// Map of keys to entries.
+    entries: HashMap<Key, Entry>,
+    /// Logical clock for tracking usage.
+    clock: u64,
+}
+
+/// Cache entry.
+#[derive(Debug)]
+struct Entry {
+    /// Cached response.
+    response: Response,
+    /// Request headers named by the response's vary header, with values.
+    vary: Vec<(Header, Option<String>)>,
+    /// Time of storage.
+    stored: Instant,
+    /// Time to live.
+    ttl: Duration,
+    /// Logical time of last usage.
+    used: u64,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl ResponseCache {
+    /// Creates a middleware for caching responses.
+    ///
+    /// By default, entries live for 60 seconds, and at most 1024 entries are
+    /// stored in the cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::cache::ResponseCache;
+    ///
+    /// // Create middleware
+    /// let middleware = ResponseCache::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time to live of entries.
+    ///
+    /// The time to live only applies to responses without a `max-age`, as the
+    /// [`Header::CacheControl`] directive takes precedence. Thus, a response
+    /// with `max-age=0` is never stored, while one with `max-age=60` is stored
+    /// for 60 seconds, even if the time to live is zero, unless it's also
+    /// marked as `no-cache`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::time::Duration;
+    /// use zense::handler::{Handler, Stack, TryIntoHandler};
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Header, Request, Response};
+    /// use zense::middleware::cache::ResponseCache;
+    ///
+    /// // Create stack with middleware with time to live
+    /// let stack = Stack::new()
+    ///     .with(ResponseCache::new().ttl(Duration::ZERO))
+    ///     .with(|req: Request, _: &dyn Handler| {
+    ///         let directives = req.uri.path.trim_start_matches('/').to_owned();
+    ///         Response::text("Hello world")
+    ///             .header(Header::CacheControl, directives)
+    ///     })
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request twice with stack - response is not stored
+    /// stack.handle(Request::new().uri("/max-age=0"));
+    /// let res = stack.handle(Request::new().uri("/max-age=0"));
+    /// assert_eq!(res.headers.get(Header::Age), None);
+    ///
+    /// // Handle request twice with stack - response is stored
+    /// stack.handle(Request::new().uri("/max-age=60"));
+    /// let res = stack.handle(Request::new().uri("/max-age=60"));
+    /// assert_eq!(res.headers.get(Header::Age), Some("0"));
+    ///
+    /// // Handle request twice with stack - response is not stored
+    /// stack.handle(Request::new().uri("/max-age=60,no-cache"));
+    /// let res = stack.handle(Request::new().uri("/max-age=60,no-cache"));
+    /// assert_eq!(res.headers.get(Header::Age), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets the maximum number of entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::cache::ResponseCache;
+    ///
+    /// // Create middleware with capacity
+    /// let middleware = ResponseCache::new()
+    ///     .capacity(128);
+    /// ```
+    #[must_use]
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Locks the cache state, ignoring poisoning.
+    ///
+    /// The state is always consistent, as it's only modified through simple
+    /// map operations, so we can safely recover from a poisoned mutex.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl State {
+    /// Returns the cached response for the given key, if fresh, and if it
+    /// matches the given request headers it varies on.
+    fn get(
+        &mut self, key: &Key, headers: &Headers, now: Instant,
+    ) -> Option<Response> {
+        let entry = self.entries.get_mut(key)?;
+        let age = now.saturating_duration_since(entry.stored);
+
+        // Ensure request matches the stored request in all varying headers
+        let matches = entry
+            .vary
+            .iter()
+            .all(|(header, value)| headers.get(*header) == value.as_deref());
+        if !matches {
+            return None;
+        }
+
+        // Remove entry if it expired
+        if age >= entry.ttl {
+            self.entries.remove(key);
+            return None;
+        }
+
+        // Update usage and return response with age
+        self.clock += 1;
+        entry.used = self.clock;
+        let res = entry.response.clone();
+        Some(res.header(Header::Age, age.as_secs()))
+    }
+
+    /// Stores the given response, evicting the least recently used entry.
+    fn insert(&mut self, key: Key, entry: Entry, capacity: usize) {
+        if !self.entries.contains_key(&key) && self.entries.len() >= capacity {
+            let iter = self.entries.iter();
+            let lru = iter.min_by_key(|(_, entry)| entry.used);
+            if let Some(key) = lru.map(|(key, _)| key.clone()) {
+                self.entries.remove(&key);
+            }
+        }
+
+        // Store entry with current usage
+        self.clock += 1;
+        self.entries
+            .insert(key, Entry { used: self.clock, ..entry });
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Middleware for ResponseCache {
+    /// Processes the given request.
+    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+        if !matches!(req.method, Method::Get | Method::Head)
+            || self.capacity == 0
+        {
+            return next.handle(req);
+        }
+
+        // Bypass cache entirely if the request must not be stored, or if it
+        // carries credentials, as the response might be specific to the user
+        let directives = cache_control(req.headers.get(Header::CacheControl));
+        if directives.no_store
+            || req.headers.contains(Header::Authorization)
+            || req.headers.contains(Header::Cookie)
+        {
+            return next.handle(req);
+        }
+
+        // Return cached response, if any, unless the request asks for a
+        // fresh response, in which case we refresh the cache entry
+        let key = key(&req);
+        let now = Instant::now();
+        if !directives.no_cache {
+            if let Some(res) = self.lock().get(&key, &req.headers, now) {
+                return res;
+            }
+        }
+
+        // Pass request to next handler, and store successful responses,
+        // unless the response must not be stored in a shared cache
+        let headers = req.headers.clone();
+        let res = next.handle(req);
+        if storable(&res) {
+            let directives =
+                cache_control(res.headers.get(Header::CacheControl));
+            let ttl = directives.max_age.unwrap_or(self.ttl);
+            if !(directives.no_store
+                || directives.no_cache
+                || directives.private
+                || ttl.is_zero())
+            {
+                if let Some(vary) = vary(&res, &headers) {
+                    let entry = Entry {
+                        response: res.clone(),
+                        vary,
+                        stored: now,
+                        ttl,
+                        used: 0,
+                    };
+                    self.lock().insert(key, entry, self.capacity);
+                }
+            }
+        }
+
+        // Return response
+        res
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Default for ResponseCache {
+    /// Creates a middleware for caching responses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::cache::ResponseCache;
+    ///
+    /// // Create middleware
+    /// let middleware = ResponseCache::default();
+    /// ```
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60),
+            capacity: 1024,
+            state: Mutex::default(),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Parses the cache directives of the given header value, if any.
+fn cache_control(value: Option<&str>) -> CacheControl {
+    value.map(cache_control::parse).unwrap_or_default()
+}
+
+/// Returns the cache key for the given request.
+///
+/// The host is normalized, as host names are case-insensitive, and requests
+/// without a [`Header::Host`] header share an empty host.
+fn key(req: &Request) -> Key {
+    let host = req.headers.get(Header::Host).unwrap_or_default();
+    let host = host.trim().to_ascii_lowercase();
+    (req.method, host, req.uri.clone().into_owned())
+}
+
+/// Returns whether the given response can be stored in a shared cache.
+///
+/// Partial responses are not stored, as they only answer a specific range,
+/// and neither are responses setting cookies, which are specific to a user.
+/// Responses with file bodies are served from disk anyway, and the file might
+/// change, which is why they're not stored either.
+fn storable(res: &Response) -> bool {
+    res.status.is_success()
+        && res.status != Status::PartialContent
+        && res.headers.get(Header::SetCookie).is_none()
+        && res.file.is_none()
+}
+
+/// Returns the request headers the given response varies on, with values.
+///
+/// If the response varies on all headers, or on a header that is unknown, and
+/// thus can't be compared, [`None`] is returned, as it can't be stored.
+fn vary(
+    res: &Response, headers: &Headers,
+) -> Option<Vec<(Header, Option<String>)>> {
+    let Some(value) = res.headers.get(Header::Vary) else {
+        return Some(Vec::new());
+    };
+
+    // Collect request headers and their values
+    let iter = value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty());
+    iter.map(|name| {
+        let header = name.parse::<Header>().ok()?;
+        Some((header, headers.get(header).map(ToOwned::to_owned)))
+    })
+    .collect()
+}
+
+// ----------------------------------------------------------------------------
+// Type aliases
+// ----------------------------------------------------------------------------
+
+/// Cache key, consisting of method, normalized host and URI.
+type Key = (Method, String, Uri<'static>);
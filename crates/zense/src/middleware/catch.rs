@@ -0,0 +1,159 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Middleware for catching panics.
+
+use std::any::Any;
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::handler::Handler;
+use crate::http::response::ResponseExt;
+use crate::http::{Request, Response, Status};
+use crate::middleware::Middleware;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware for catching panics.
+///
+/// Although handlers should not panic, it might still happen, e.g., due to a
+/// bug in a third-party library. This middleware catches panics that occur in
+/// the remainder of the request processing chain, and answers the request with
+/// "500 Internal Server Error", instead of unwinding the server thread.
+///
+/// Note that the panic hook is still invoked before the panic is caught, which
+/// means the panic message is printed to stderr, unless the hook is replaced.
+/// Also note that shared state of handlers might be left in a broken state by
+/// a caught panic, and is observed as such by later requests.
+///
+/// # Examples
+///
+/// ```
+/// use zense::http::{Request, Response, Status};
+/// use zense::middleware::catch::CatchPanic;
+/// use zense::middleware::Middleware;
+///
+/// // Create middleware
+/// let middleware = CatchPanic::new();
+///
+/// // Handle request with middleware
+/// let res = middleware.process(Request::new(), &|_: Request| -> Response {
+///     panic!("handler panicked")
+/// });
+/// assert_eq!(res.status, Status::InternalServerError);
+/// ```
+#[allow(clippy::type_complexity)]
+#[derive(Default)]
+pub struct CatchPanic {
+    /// Panic hook.
+    hook: Option<Box<dyn Fn(&(dyn Any + Send))>>,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl CatchPanic {
+    /// Creates a middleware for catching panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::catch::CatchPanic;
+    ///
+    /// // Create middleware
+    /// let middleware = CatchPanic::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a hook that is invoked with the payload of a caught panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::catch::CatchPanic;
+    ///
+    /// // Create middleware with hook
+    /// let middleware = CatchPanic::new()
+    ///     .hook(|payload| {
+    ///         if let Some(message) = payload.downcast_ref::<&str>() {
+    ///             eprintln!("{message}");
+    ///         }
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn hook<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&(dyn Any + Send)) + 'static,
+    {
+        self.hook = Some(Box::new(f));
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Middleware for CatchPanic {
+    /// Processes the given request.
+    ///
+    /// The next handler is invoked inside of [`catch_unwind`]. Requests and
+    /// handlers are not [`UnwindSafe`][], so unwind safety is asserted, which
+    /// is not actually guaranteed: while the request is consumed, handlers are
+    /// reused for later requests, and might hold shared state with interior
+    /// mutability, e.g., a [`Mutex`][], [`RefCell`][] or atomics, which can be
+    /// left half-updated by the panic. Later requests might thus observe broken
+    /// state, unless handlers restore their invariants.
+    ///
+    /// [`Mutex`]: std::sync::Mutex
+    /// [`RefCell`]: std::cell::RefCell
+    /// [`UnwindSafe`]: std::panic::UnwindSafe
+    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+        catch_unwind(AssertUnwindSafe(|| next.handle(req))).unwrap_or_else(
+            |payload| {
+                if let Some(hook) = &self.hook {
+                    hook(payload.as_ref());
+                }
+
+                // Answer with internal server error
+                Response::from_status(Status::InternalServerError)
+            },
+        )
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl fmt::Debug for CatchPanic {
+    /// Formats the middleware for debugging.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CatchPanic")
+            .field("hook", &self.hook.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,384 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Middleware for protection against cross-site request forgery.
+
+use std::str;
+
+use crate::handler::Handler;
+use crate::http::response::ResponseExt;
+use crate::http::{Header, Query, Request, Response, Status};
+use crate::middleware::Middleware;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware for protection against cross-site request forgery.
+///
+/// This middleware implements the double-submit pattern: a token is issued in
+/// a cookie, and requests with unsafe methods, as determined by
+/// [`Method::is_safe`][], must submit the same token in the
+/// [`Header::XCsrfToken`] header, or in a form field of URL-encoded bodies.
+/// Other sites can make browsers send the cookie, but can't read it, so they
+/// can't submit the token. Requests with missing or mismatching tokens are
+/// answered with "403 Forbidden".
+///
+/// Requests with safe methods are passed on, and if they lack a token, a new
+/// one is issued via [`Header::SetCookie`]. The token is set in the
+/// [`Header::XCsrfToken`] header of the request, so handlers can embed it into
+/// forms. The cookie is appended to the response, so cookies set by handlers,
+/// e.g., for sessions, are sent alongside it. Tokens consist of 128 bits taken from
+/// the operating system's source of randomness, so they can't be predicted.
+/// If it's unavailable, the request is answered with "500 Internal Server
+/// Error", as no token can be issued. The cookie is marked as `Secure` by
+/// default, so browsers only send it via HTTPS.
+///
+/// [`Method::is_safe`]: crate::http::Method::is_safe
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Method, Request, Response, Status};
+/// use zense::middleware::csrf::Csrf;
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(Csrf::new())
+///     .with(|_: Request, _: &dyn Handler| Response::text("ok"))
+///     .try_into_handler()?;
+///
+/// // Handle safe request - token is issued
+/// let res = stack.handle(Request::new());
+/// let cookie = res.headers.get(Header::SetCookie).unwrap();
+/// assert!(cookie.ends_with("; Secure"));
+/// let (pair, _) = cookie.split_once(';').unwrap();
+/// let (_, token) = pair.split_once('=').unwrap();
+///
+/// // Handle unsafe request without token - request is rejected
+/// let req = Request::new()
+///     .method(Method::Post)
+///     .header(Header::Cookie, pair);
+/// assert_eq!(stack.handle(req).status, Status::Forbidden);
+///
+/// // Handle unsafe request with mismatching token - request is rejected
+/// let req = Request::new()
+///     .method(Method::Post)
+///     .header(Header::Cookie, pair)
+///     .header(Header::XCsrfToken, "forged");
+/// assert_eq!(stack.handle(req).status, Status::Forbidden);
+///
+/// // Handle unsafe request with matching token - request is passed on
+/// let req = Request::new()
+///     .method(Method::Post)
+///     .header(Header::Cookie, pair)
+///     .header(Header::XCsrfToken, token);
+/// assert_eq!(stack.handle(req).body, b"ok");
+///
+/// // Handle unsafe request with matching token in form field
+/// let req = Request::new()
+///     .method(Method::Post)
+///     .header(Header::Cookie, pair)
+///     .header(Header::ContentType, "application/x-www-form-urlencoded")
+///     .body(format!("name=zense&csrf_token={token}"));
+/// assert_eq!(stack.handle(req).body, b"ok");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Cookies set by handlers are sent alongside the token:
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Request, Response};
+/// use zense::middleware::csrf::Csrf;
+///
+/// // Create stack with middleware, and handler that sets a cookie
+/// let stack = Stack::new()
+///     .with(Csrf::new())
+///     .with(|_: Request, _: &dyn Handler| {
+///         Response::text("ok").header(Header::SetCookie, "session=1")
+///     })
+///     .try_into_handler()?;
+///
+/// // Handle safe request without token - both cookies are set
+/// let res = stack.handle(Request::new());
+/// let cookies = res.headers.get_all(Header::SetCookie).collect::<Vec<_>>();
+/// assert_eq!(cookies[0], "session=1");
+/// assert!(cookies[1].starts_with("csrf_token="));
+///
+/// // Ensure both cookies are written
+/// let bytes = res.into_bytes();
+/// let head = String::from_utf8_lossy(&bytes);
+/// assert_eq!(head.matches("Set-Cookie: ").count(), 2);
+///
+/// // Handle safe request with token - handler's cookie is set
+/// let token = "0123456789abcdef0123456789abcdef";
+/// let req = Request::new()
+///     .header(Header::Cookie, format!("csrf_token={token}"));
+/// let res = stack.handle(req);
+/// assert_eq!(res.headers.get(Header::SetCookie), Some("session=1"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Csrf {
+    /// Cookie name.
+    cookie: String,
+    /// Form field name.
+    field: String,
+    /// Whether the cookie is secure.
+    secure: bool,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Csrf {
+    /// Creates a middleware for protection against cross-site request forgery.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::csrf::Csrf;
+    ///
+    /// // Create middleware
+    /// let middleware = Csrf::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the cookie holding the token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::csrf::Csrf;
+    ///
+    /// // Create middleware and set cookie name
+    /// let middleware = Csrf::new().cookie("__Host-csrf");
+    /// ```
+    #[must_use]
+    pub fn cookie<N>(mut self, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.cookie = name.into();
+        self
+    }
+
+    /// Sets whether the cookie holding the token is secure.
+    ///
+    /// Secure cookies are only sent via HTTPS, which is why this should only
+    /// be disabled for development over plain HTTP. Cookies with names using
+    /// the `__Host-` or `__Secure-` prefix are always secure, as browsers
+    /// reject them otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::{Handler, Stack, TryIntoHandler};
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Header, Request, Response};
+    /// use zense::middleware::csrf::Csrf;
+    ///
+    /// // Create stack with middleware issuing insecure cookies
+    /// let stack = Stack::new()
+    ///     .with(Csrf::new().secure(false))
+    ///     .with(|_: Request, _: &dyn Handler| Response::text("ok"))
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request - token is issued without secure attribute
+    /// let res = stack.handle(Request::new());
+    /// let cookie = res.headers.get(Header::SetCookie).unwrap();
+    /// assert!(!cookie.ends_with("; Secure"));
+    ///
+    /// // Create stack with middleware using cookie name with prefix
+    /// let stack = Stack::new()
+    ///     .with(Csrf::new().cookie("__Host-csrf").secure(false))
+    ///     .with(|_: Request, _: &dyn Handler| Response::text("ok"))
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request - token is issued with secure attribute
+    /// let res = stack.handle(Request::new());
+    /// let cookie = res.headers.get(Header::SetCookie).unwrap();
+    /// assert!(cookie.ends_with("; Secure"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Sets the name of the form field holding the token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::csrf::Csrf;
+    ///
+    /// // Create middleware and set form field name
+    /// let middleware = Csrf::new().field("_csrf");
+    /// ```
+    #[must_use]
+    pub fn field<N>(mut self, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.field = name.into();
+        self
+    }
+
+    /// Returns whether the cookie must be marked as secure.
+    fn is_secure(&self) -> bool {
+        self.secure
+            || self.cookie.starts_with("__Host-")
+            || self.cookie.starts_with("__Secure-")
+    }
+
+    /// Returns whether the request submits the given token.
+    fn verify(&self, req: &Request, token: &str) -> bool {
+        if let Some(submitted) = req.headers.get(Header::XCsrfToken) {
+            return eq(token, submitted);
+        }
+
+        // Fall back to the form field, if the body is URL-encoded
+        let form = req.content_type().is_some_and(|(mime, _)| {
+            mime.eq_ignore_ascii_case("application/x-www-form-urlencoded")
+        });
+        form && str::from_utf8(&req.body).is_ok_and(|body| {
+            let query = Query::from(body);
+            query.get(&self.field).is_some_and(|value| eq(token, value))
+        })
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Middleware for Csrf {
+    /// Processes the given request.
+    fn process(&self, mut req: Request, next: &dyn Handler) -> Response {
+        let token = req
+            .headers
+            .get(Header::Cookie)
+            .and_then(|value| cookie(value, &self.cookie))
+            .filter(|token| is_valid(token))
+            .map(str::to_owned);
+
+        // Ensure requests with unsafe methods submit the token of the cookie
+        if !req.method.is_safe() {
+            return match token {
+                Some(token) if self.verify(&req, &token) => next.handle(req),
+                _ => Response::from_status(Status::Forbidden),
+            };
+        }
+
+        // Issue a new token, if the request lacks one, and make it available
+        // to handlers, so they can embed it into forms
+        let issue = token.is_none();
+        let Some(token) = token.or_else(generate) else {
+            return Response::from_status(Status::InternalServerError);
+        };
+        req.headers.put(Header::XCsrfToken, token.clone());
+        let mut res = next.handle(req);
+        if issue {
+            let mut value =
+                format!("{}={token}; Path=/; SameSite=Strict", self.cookie);
+            if self.is_secure() {
+                value.push_str("; Secure");
+            }
+            res.headers.append(Header::SetCookie, value);
+        }
+        res
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Default for Csrf {
+    /// Creates a middleware for protection against cross-site request forgery.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::csrf::Csrf;
+    ///
+    /// // Create middleware
+    /// let middleware = Csrf::default();
+    /// ```
+    fn default() -> Self {
+        Self {
+            cookie: String::from("csrf_token"),
+            field: String::from("csrf_token"),
+            secure: true,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Generates a new token from the operating system's source of randomness.
+fn generate() -> Option<String> {
+    let mut bytes = [0; 16];
+    getrandom::fill(&mut bytes).ok()?;
+    Some(format!("{:032x}", u128::from_ne_bytes(bytes)))
+}
+
+/// Returns the value of the cookie with the given name from a header value.
+fn cookie<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    value.split(';').find_map(|pair| {
+        let (key, value) = pair.trim().split_once('=')?;
+        (key == name).then_some(value)
+    })
+}
+
+/// Returns whether the given token is valid, i.e., hexadecimal.
+fn is_valid(token: &str) -> bool {
+    token.len() == 32 && token.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Compares the given tokens in constant time.
+fn eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
@@ -0,0 +1,243 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Middleware for decompressing request bodies.
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use std::borrow::Cow;
+use std::io::Read;
+
+use crate::handler::Handler;
+use crate::http::response::ResponseExt;
+use crate::http::{Header, Request, Response, Status};
+use crate::middleware::Middleware;
+
+// ----------------------------------------------------------------------------
+// Constants
+// ----------------------------------------------------------------------------
+
+/// Default maximum size of decompressed bodies.
+const DEFAULT_LIMIT: usize = 16 * 1024 * 1024;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware for decompressing request bodies.
+///
+/// This middleware decompresses bodies of requests that carry a
+/// [`Header::ContentEncoding`] header with `gzip` or `deflate`, or a list of
+/// both, which are removed in reverse order of application. Afterwards, the
+/// header is removed, and the [`Header::ContentLength`] header is updated, so
+/// downstream handlers see the request as if it was sent uncompressed.
+///
+/// Compressed bodies can expand by orders of magnitude, which is why the size
+/// of decompressed bodies is capped, and requests exceeding it are answered
+/// with "413 Payload Too Large". Bodies that fail to decompress are answered
+/// with "400 Bad Request", and unknown codings with "415 Unsupported Media
+/// Type", as defined in [RFC 7231].
+///
+/// [RFC 7231]: https://www.rfc-editor.org/rfc/rfc7231#section-3.1.2.2
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use flate2::write::GzEncoder;
+/// use flate2::Compression;
+/// use std::io::Write;
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::{Header, Method, Request, Response};
+/// use zense::middleware::decompress::Decompress;
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(Decompress::new())
+///     .with(|req: Request, _: &dyn Handler| {
+///         assert_eq!(req.headers.get(Header::ContentEncoding), None);
+///         assert_eq!(req.headers.get(Header::ContentLength), Some("16"));
+///         Response::new().body(req.body)
+///     })
+///     .try_into_handler()?;
+///
+/// // Compress body
+/// let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+/// encoder.write_all(br#"{"name":"zense"}"#)?;
+/// let body = encoder.finish()?;
+///
+/// // Handle request with compressed body
+/// let req = Request::new()
+///     .method(Method::Post)
+///     .header(Header::ContentType, "application/json")
+///     .header(Header::ContentEncoding, "gzip")
+///     .header(Header::ContentLength, body.len())
+///     .body(body);
+/// let res = stack.handle(req);
+/// assert_eq!(res.body, br#"{"name":"zense"}"#);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Decompress {
+    /// Maximum size of decompressed bodies.
+    limit: usize,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Decompress {
+    /// Creates a middleware for decompressing request bodies.
+    ///
+    /// The size of decompressed bodies is capped at 16 MiB by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::decompress::Decompress;
+    ///
+    /// // Create middleware
+    /// let middleware = Decompress::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum size of decompressed bodies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use flate2::write::ZlibEncoder;
+    /// use flate2::Compression;
+    /// use std::io::Write;
+    /// use zense::handler::{Handler, Stack, TryIntoHandler};
+    /// use zense::http::{Header, Method, Request, Response, Status};
+    /// use zense::middleware::decompress::Decompress;
+    ///
+    /// // Create stack with middleware
+    /// let stack = Stack::new()
+    ///     .with(Decompress::new().limit(1024))
+    ///     .with(|_: Request, _: &dyn Handler| Response::new())
+    ///     .try_into_handler()?;
+    ///
+    /// // Compress body, which exceeds the limit when decompressed
+    /// let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    /// encoder.write_all(&[0; 4096])?;
+    /// let body = encoder.finish()?;
+    ///
+    /// // Handle request with compressed body
+    /// let req = Request::new()
+    ///     .method(Method::Post)
+    ///     .header(Header::ContentEncoding, "deflate")
+    ///     .body(body);
+    /// let res = stack.handle(req);
+    /// assert_eq!(res.status, Status::PayloadTooLarge);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Decompresses the given body with the given coding.
+    fn decompress(&self, coding: &str, body: &[u8]) -> Result<Vec<u8>, Status> {
+        let reader: Box<dyn Read> = if coding.eq_ignore_ascii_case("gzip")
+            || coding.eq_ignore_ascii_case("x-gzip")
+        {
+            Box::new(GzDecoder::new(body))
+        } else if coding.eq_ignore_ascii_case("deflate") {
+            Box::new(ZlibDecoder::new(body))
+        } else if coding.eq_ignore_ascii_case("identity") {
+            Box::new(body)
+        } else {
+            return Err(Status::UnsupportedMediaType);
+        };
+
+        // Read at most one byte more than the limit, to detect overflows
+        let mut buffer = Vec::new();
+        let limit = u64::try_from(self.limit).unwrap_or(u64::MAX);
+        match reader
+            .take(limit.saturating_add(1))
+            .read_to_end(&mut buffer)
+        {
+            Ok(n) if n > self.limit => Err(Status::PayloadTooLarge),
+            Ok(_) => Ok(buffer),
+            Err(_) => Err(Status::BadRequest),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Middleware for Decompress {
+    /// Processes the given request.
+    fn process(&self, mut req: Request, next: &dyn Handler) -> Response {
+        let Some(value) = req.headers.get(Header::ContentEncoding) else {
+            return next.handle(req);
+        };
+
+        // Remove codings in reverse order of application
+        let mut body = Cow::Borrowed(req.body.as_ref());
+        for coding in value.rsplit(',').map(str::trim) {
+            match self.decompress(coding, &body) {
+                Ok(buffer) => body = Cow::Owned(buffer),
+                Err(status) => return Response::from_status(status),
+            }
+        }
+
+        // Replace body, and update headers accordingly
+        let body = body.into_owned();
+        req.headers.remove(Header::ContentEncoding);
+        req.headers
+            .put(Header::ContentLength, body.len().to_string());
+        req.body = Cow::Owned(body);
+        next.handle(req)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Default for Decompress {
+    /// Creates a middleware for decompressing request bodies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::decompress::Decompress;
+    ///
+    /// // Create middleware
+    /// let middleware = Decompress::default();
+    /// ```
+    fn default() -> Self {
+        Self { limit: DEFAULT_LIMIT }
+    }
+}
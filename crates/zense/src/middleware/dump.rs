@@ -0,0 +1,292 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Middleware for dumping requests and responses.
+
+use std::fmt::{self, Write as _};
+use std::io::{self, Write};
+use std::str;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::handler::Handler;
+use crate::http::{Header, Request, Response};
+use crate::middleware::Middleware;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware for dumping requests and responses.
+///
+/// This middleware writes a textual representation of each request and its
+/// response to a writer, which defaults to standard error, and is invaluable
+/// during development. Lines of the request are prefixed with `>`, and lines
+/// of the response with `<`, followed by a preview of the body, which is
+/// truncated to the configured limit, and omitted for binary content.
+///
+/// Headers carrying credentials, e.g., [`Header::Authorization`], as listed in
+/// [`Header::SENSITIVE`], are redacted, unless explicitly allowed. Since dumps
+/// contain the contents of requests and responses, the middleware shouldn't be
+/// used in production.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::io::{self, Write};
+/// use std::sync::{Arc, Mutex};
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Request, Response};
+/// use zense::middleware::dump::Dump;
+///
+/// // Define writer that captures the dump
+/// #[derive(Clone, Default)]
+/// struct Capture(Arc<Mutex<Vec<u8>>>);
+///
+/// // Create writer implementation
+/// impl Write for Capture {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+///         self.0.lock().unwrap().write(buf)
+///     }
+///     fn flush(&mut self) -> io::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// // Create stack with middleware
+/// let capture = Capture::default();
+/// let stack = Stack::new()
+///     .with(Dump::new().writer(capture.clone()))
+///     .with(|req: Request, _: &dyn Handler| Response::text("Hello world"))
+///     .try_into_handler()?;
+///
+/// // Handle request with credentials
+/// let req = Request::new()
+///     .uri("/coffee")
+///     .header(Header::Authorization, "Bearer secret");
+/// stack.handle(req);
+///
+/// // Ensure credentials are redacted
+/// let dump = String::from_utf8(capture.0.lock().unwrap().clone())?;
+/// assert!(dump.contains("> GET /coffee HTTP/1.1\n"));
+/// assert!(dump.contains("> Authorization: <redacted>\n"));
+/// assert!(dump.contains("< Hello world\n"));
+/// assert!(!dump.contains("secret"));
+/// # Ok(())
+/// # }
+/// ```
+pub struct Dump {
+    /// Writer the dump is written to.
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    /// Headers that are not redacted.
+    allow: Vec<Header>,
+    /// Maximum length of the body preview.
+    limit: usize,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Dump {
+    /// Creates a middleware for dumping requests and responses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::dump::Dump;
+    ///
+    /// // Create middleware
+    /// let middleware = Dump::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the writer the dump is written to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use zense::middleware::dump::Dump;
+    ///
+    /// // Create middleware and write to standard output
+    /// let middleware = Dump::new().writer(io::stdout());
+    /// ```
+    #[must_use]
+    pub fn writer<W>(mut self, writer: W) -> Self
+    where
+        W: Write + Send + 'static,
+    {
+        self.writer = Arc::new(Mutex::new(Box::new(writer)));
+        self
+    }
+
+    /// Allows the given header to be dumped without redaction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Header;
+    /// use zense::middleware::dump::Dump;
+    ///
+    /// // Create middleware and allow cookies
+    /// let middleware = Dump::new().allow(Header::Cookie);
+    /// ```
+    #[must_use]
+    pub fn allow(mut self, header: Header) -> Self {
+        self.allow.push(header);
+        self
+    }
+
+    /// Sets the maximum length of the body preview, defaulting to 1kb.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::dump::Dump;
+    ///
+    /// // Create middleware and set maximum length of body preview
+    /// let middleware = Dump::new().limit(256);
+    /// ```
+    #[must_use]
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Formats the given headers with the given prefix, redacting values.
+    fn headers<'h, I, V>(&self, output: &mut String, prefix: char, iter: I)
+    where
+        I: IntoIterator<Item = (&'h Header, V)>,
+        V: AsRef<str>,
+    {
+        for (header, value) in iter {
+            let redact = Header::SENSITIVE.contains(header)
+                && !self.allow.contains(header);
+            let value = if redact { "<redacted>" } else { value.as_ref() };
+            let _ = writeln!(output, "{prefix} {header}: {value}");
+        }
+    }
+
+    /// Formats a preview of the given body with the given prefix.
+    fn body(&self, output: &mut String, prefix: char, body: &[u8]) {
+        if body.is_empty() {
+            return;
+        }
+
+        // Truncate body, and decode it - if the truncation happened to split
+        // a character, the incomplete character at the end is dropped
+        let _ = writeln!(output, "{prefix}");
+        let data = &body[..body.len().min(self.limit)];
+        let text = match str::from_utf8(data) {
+            Ok(text) => Some(text),
+            Err(err) if err.error_len().is_none() => {
+                str::from_utf8(&data[..err.valid_up_to()]).ok()
+            }
+            Err(_) => None,
+        };
+
+        // Write preview, unless the body is binary
+        if let Some(text) = text.filter(|text| !text.contains('\0')) {
+            for line in text.lines() {
+                let _ = writeln!(output, "{prefix} {line}");
+            }
+            if data.len() < body.len() {
+                let len = body.len();
+                let _ = writeln!(output, "{prefix} [truncated: {len} bytes]");
+            }
+        } else {
+            let len = body.len();
+            let _ = writeln!(output, "{prefix} [binary: {len} bytes]");
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Middleware for Dump {
+    /// Processes the given request.
+    ///
+    /// The request is dumped before it's forwarded to the next handler, and
+    /// the response afterwards, so both are written together. Errors while
+    /// writing are ignored, as they must not affect request processing.
+    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+        let mut output = String::new();
+        let _ =
+            writeln!(output, "> {} {} {}", req.method, req.uri, req.version);
+        self.headers(&mut output, '>', &req.headers);
+        self.body(&mut output, '>', &req.body);
+
+        // Forward to next handler, and dump response - writing to a string is
+        // infallible, so we only need to ignore errors of the writer
+        let res = next.handle(req);
+        let _ = writeln!(output, "< {} {}", res.version, res.status);
+        self.headers(&mut output, '<', &res.headers);
+        self.body(&mut output, '<', &res.body);
+
+        // Write dump at once, so dumps of concurrent requests don't interleave
+        let mut writer =
+            self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let _ = writer.write_all(output.as_bytes());
+        let _ = writer.flush();
+        res
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Default for Dump {
+    /// Creates a middleware for dumping requests and responses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::dump::Dump;
+    ///
+    /// // Create middleware
+    /// let middleware = Dump::default();
+    /// ```
+    fn default() -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(Box::new(io::stderr()))),
+            allow: Vec::new(),
+            limit: 1024,
+        }
+    }
+}
+
+impl fmt::Debug for Dump {
+    /// Formats the middleware for debugging.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dump")
+            .field("allow", &self.allow)
+            .field("limit", &self.limit)
+            .finish_non_exhaustive()
+    }
+}
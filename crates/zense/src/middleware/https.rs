@@ -0,0 +1,282 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Middleware for redirecting to HTTPS.
+
+use crate::handler::Handler;
+use crate::http::forwarded::Trust;
+use crate::http::response::ResponseExt;
+use crate::http::{Header, Request, Response, Status};
+use crate::middleware::Middleware;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware for redirecting to HTTPS.
+///
+/// This middleware answers requests that were not made via HTTPS with a
+/// redirect to the `https://` equivalent, which is composed of the host from
+/// the [`Header::Host`] header and the original path and query. Requests that
+/// lack a valid host are answered with "400 Bad Request", as there's nowhere
+/// to redirect to. Secure requests and excluded paths, e.g., health checks
+/// polled by load balancers via HTTP, are passed on to the next handler.
+///
+/// As TLS is terminated by a proxy in front of this crate, whether a request
+/// is secure is determined from a forwarded header with [`Request::is_secure`],
+/// which must be explicitly trusted with [`RedirectToHttps::trust_forwarded`],
+/// and only if this crate is deployed behind a proxy that sets it. Without
+/// trusting a header, all requests are redirected, which is useful for a
+/// server that only answers plain HTTP. By default, "308 Permanent Redirect"
+/// is used, which preserves the method.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::forwarded::Trust;
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Request, Response, Status};
+/// use zense::middleware::https::RedirectToHttps;
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(RedirectToHttps::new()
+///         .trust_forwarded(Trust::XForwardedProto)
+///         .exclude("/health")
+///     )
+///     .with(|_: Request, _: &dyn Handler| Response::text("secure"))
+///     .try_into_handler()?;
+///
+/// // Handle insecure request - request is redirected
+/// let req = Request::new()
+///     .uri("/page?x=1")
+///     .header(Header::Host, "host");
+/// let res = stack.handle(req);
+/// let location = res.headers.get(Header::Location);
+/// assert_eq!(res.status, Status::PermanentRedirect);
+/// assert_eq!(location, Some("https://host/page?x=1"));
+///
+/// // Handle secure request - request is passed on
+/// let req = Request::new()
+///     .uri("/page?x=1")
+///     .header(Header::Host, "host")
+///     .header(Header::XForwardedProto, "https");
+/// assert_eq!(stack.handle(req).body, b"secure");
+///
+/// // Handle insecure request for excluded path - request is passed on
+/// let req = Request::new().uri("/health");
+/// assert_eq!(stack.handle(req).body, b"secure");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RedirectToHttps {
+    /// Redirect status.
+    status: Status,
+    /// Trusted forwarded header.
+    trust: Trust,
+    /// List of excluded paths.
+    excluded: Vec<String>,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl RedirectToHttps {
+    /// Creates a middleware for redirecting to HTTPS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::https::RedirectToHttps;
+    ///
+    /// // Create middleware
+    /// let middleware = RedirectToHttps::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the status used for redirects.
+    ///
+    /// This is usually either "301 Moved Permanently" or "308 Permanent
+    /// Redirect", the latter of which ensures that the method is preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Status;
+    /// use zense::middleware::https::RedirectToHttps;
+    ///
+    /// // Create middleware and set status
+    /// let middleware = RedirectToHttps::new()
+    ///     .status(Status::MovedPermanently);
+    /// ```
+    #[must_use]
+    pub fn status(mut self, status: Status) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Sets the trusted forwarded header.
+    ///
+    /// Only trust the [`Header::Forwarded`] or [`Header::XForwardedProto`]
+    /// header, if this crate is deployed behind a proxy that sets it, as
+    /// clients could otherwise claim that the request is secure. The other
+    /// header is always ignored, since the proxy passes it on unchanged. See
+    /// [`Trust`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::{Handler, Stack, TryIntoHandler};
+    /// use zense::http::forwarded::Trust;
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Header, Request, Response, Status};
+    /// use zense::middleware::https::RedirectToHttps;
+    ///
+    /// // Create stack with middleware not trusting forwarded headers
+    /// let stack = Stack::new()
+    ///     .with(RedirectToHttps::new().trust_forwarded(Trust::None))
+    ///     .with(|_: Request, _: &dyn Handler| Response::text("secure"))
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request claiming to be secure - request is redirected
+    /// let req = Request::new()
+    ///     .header(Header::Host, "host")
+    ///     .header(Header::XForwardedProto, "https");
+    /// let res = stack.handle(req);
+    /// assert_eq!(res.status, Status::PermanentRedirect);
+    ///
+    /// // Create stack with middleware trusting the header set by the proxy
+    /// let middleware = RedirectToHttps::new()
+    ///     .trust_forwarded(Trust::XForwardedProto);
+    /// let stack = Stack::new()
+    ///     .with(middleware)
+    ///     .with(|_: Request, _: &dyn Handler| Response::text("secure"))
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request with forged header - request is redirected
+    /// let req = Request::new()
+    ///     .header(Header::Host, "host")
+    ///     .header(Header::Forwarded, "proto=https")
+    ///     .header(Header::XForwardedProto, "http");
+    /// let res = stack.handle(req);
+    /// assert_eq!(res.status, Status::PermanentRedirect);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn trust_forwarded(mut self, trust: Trust) -> Self {
+        self.trust = trust;
+        self
+    }
+
+    /// Excludes the given path from redirects.
+    ///
+    /// Paths are matched exactly, i.e., without considering the query string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::https::RedirectToHttps;
+    ///
+    /// // Create middleware and exclude path
+    /// let middleware = RedirectToHttps::new()
+    ///     .exclude("/health");
+    /// ```
+    #[must_use]
+    pub fn exclude<P>(mut self, path: P) -> Self
+    where
+        P: Into<String>,
+    {
+        self.excluded.push(path.into());
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Middleware for RedirectToHttps {
+    /// Processes the given request.
+    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+        let path = req.uri.path.as_ref();
+        if req.is_secure(self.trust) || self.excluded.iter().any(|p| p == path)
+        {
+            return next.handle(req);
+        }
+
+        // Ensure host is present, and can't alter the target of the redirect
+        let host = req.headers.get(Header::Host).map(str::trim);
+        match host.filter(|host| is_valid(host)) {
+            Some(host) => {
+                let location = format!("https://{host}{}", req.uri);
+                Response::from_status(self.status)
+                    .header(Header::Location, location)
+            }
+            None => Response::from_status(Status::BadRequest),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Default for RedirectToHttps {
+    /// Creates a middleware for redirecting to HTTPS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::https::RedirectToHttps;
+    ///
+    /// // Create middleware
+    /// let middleware = RedirectToHttps::default();
+    /// ```
+    fn default() -> Self {
+        Self {
+            status: Status::PermanentRedirect,
+            trust: Trust::None,
+            excluded: Vec::new(),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Returns whether the given host is valid.
+fn is_valid(host: &str) -> bool {
+    !host.is_empty()
+        && host
+            .bytes()
+            .all(|b| b.is_ascii_graphic() && !matches!(b, b'/' | b'@' | b'\\'))
+}
@@ -0,0 +1,189 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Mapping middlewares.
+
+use crate::handler::Handler;
+use crate::http::{Request, Response};
+use crate::middleware::Middleware;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware mapping requests.
+///
+/// This middleware passes the request to a function, and forwards the returned
+/// request to the next handler. Use [`map_request`] to create it.
+pub struct MapRequest<F> {
+    /// Request mapper.
+    f: F,
+}
+
+/// Middleware mapping responses.
+///
+/// This middleware passes the response returned by the next handler to a
+/// function, and returns its result. Use [`map_response`] to create it.
+pub struct MapResponse<F> {
+    /// Response mapper.
+    f: F,
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl<F> Middleware for MapRequest<F>
+where
+    F: for<'a> Fn(Request<'a>) -> Request<'a> + 'static,
+{
+    /// Processes the given request.
+    #[inline]
+    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+        next.handle((self.f)(req))
+    }
+}
+
+impl<F> Middleware for MapResponse<F>
+where
+    F: Fn(Response) -> Response + 'static,
+{
+    /// Processes the given request.
+    #[inline]
+    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+        (self.f)(next.handle(req))
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Creates a middleware that maps requests with the given function.
+///
+/// The function receives the request before it's forwarded to the next
+/// handler, which makes it a concise alternative to implementing [`Middleware`]
+/// for modifications that don't need to inspect the response.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Request, Response};
+/// use zense::middleware::map_request;
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(map_request(|req: Request| req.uri("/tea")))
+///     .with(|req: Request, _: &dyn Handler| {
+///         Response::text(req.uri.path.to_string())
+///     })
+///     .try_into_handler()?;
+///
+/// // Handle request with stack
+/// let res = stack.handle(Request::new().uri("/coffee"));
+/// assert_eq!(res.body, b"/tea");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Injecting headers, e.g., to mark requests as secure for downstream handlers,
+/// when TLS is terminated in front of the application. Note that only known
+/// headers can be set, as headers are identified by [`Header`][] variants:
+///
+/// [`Header`]: crate::http::Header
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, Scope, Stack, TryIntoHandler};
+/// use zense::http::forwarded::Trust;
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Request, Response};
+/// use zense::middleware::{map_request, TryIntoMiddleware};
+///
+/// // Create middleware, which is also convertible
+/// let middleware = map_request(|req: Request| {
+///     req.header(Header::XForwardedProto, "https")
+/// });
+/// let middleware = middleware.try_into_middleware(&Scope::default())?;
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(middleware)
+///     .with(|req: Request, _: &dyn Handler| {
+///         Response::text(req.is_secure(Trust::XForwardedProto).to_string())
+///     })
+///     .try_into_handler()?;
+///
+/// // Handle request with stack
+/// let res = stack.handle(Request::new());
+/// assert_eq!(res.body, b"true");
+/// # Ok(())
+/// # }
+/// ```
+pub fn map_request<F>(f: F) -> MapRequest<F>
+where
+    F: for<'a> Fn(Request<'a>) -> Request<'a> + 'static,
+{
+    MapRequest { f }
+}
+
+/// Creates a middleware that maps responses with the given function.
+///
+/// The function receives the response returned by the next handler, which
+/// makes it a concise alternative to implementing [`Middleware`] for
+/// modifications that don't need to inspect the request.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::{Header, Request, Response, Status};
+/// use zense::middleware::map_response;
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(map_response(|res: Response| {
+///         res.header(Header::CacheControl, "no-store")
+///     }))
+///     .with(|_: Request, _: &dyn Handler| Status::Accepted)
+///     .try_into_handler()?;
+///
+/// // Handle request with stack
+/// let res = stack.handle(Request::new());
+/// assert_eq!(res.status, Status::Accepted);
+/// assert_eq!(res.headers.get(Header::CacheControl), Some("no-store"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn map_response<F>(f: F) -> MapResponse<F>
+where
+    F: Fn(Response) -> Response + 'static,
+{
+    MapResponse { f }
+}
@@ -0,0 +1,316 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Middleware for collecting metrics.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+use crate::handler::{Handler, Matcher, Result, Scope};
+use crate::http::{Method, Request, Response};
+use crate::middleware::{Middleware, TryIntoMiddleware};
+use crate::router::canonicalize;
+
+// ----------------------------------------------------------------------------
+// Constants
+// ----------------------------------------------------------------------------
+
+/// Upper bounds of latency histogram buckets in seconds.
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware for collecting metrics.
+///
+/// This middleware records request counts and latency histograms, labeled by
+/// method, route template and status, which can be rendered in the Prometheus
+/// text format with [`Metrics::render`]. The route template is the template of
+/// the route that matches the request, which is determined from the routes of
+/// the router the middleware is added to, and keeps the cardinality of labels
+/// low. If no route matches, e.g., when the request is answered by a nested
+/// router, the base path of the router is used.
+///
+/// Metrics are shared between all clones, so the same metrics can be added to
+/// several routers, and rendered from a route that serves them for scraping.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, TryIntoHandler};
+/// use zense::http::{Request, Response};
+/// use zense::middleware::metrics::Metrics;
+/// use zense::router::{Router, Params};
+///
+/// // Create metrics
+/// let metrics = Metrics::new();
+///
+/// // Create router with middleware
+/// let router = Router::new("/users/{id}")
+///     .with(metrics.clone())
+///     .get("/posts", |req: Request, params: Params| Response::new())
+///     .get("/likes", |req: Request, params: Params| Response::new())
+///     .try_into_handler()?;
+///
+/// // Handle requests with router
+/// router.handle(Request::new().uri("/users/1/posts"));
+/// router.handle(Request::new().uri("/users/2/posts"));
+/// router.handle(Request::new().uri("/users/1/likes"));
+/// router.handle(Request::new().uri("/users/1/other"));
+///
+/// // Render metrics
+/// let output = metrics.render();
+/// assert!(output.contains(concat!(
+///     "http_requests_total",
+///     r#"{method="GET",route="/users/{id}/posts",status="200"} 2"#,
+/// )));
+/// assert!(output.contains(concat!(
+///     "http_requests_total",
+///     r#"{method="GET",route="/users/{id}/likes",status="200"} 1"#,
+/// )));
+/// assert!(output.contains(concat!(
+///     "http_requests_total",
+///     r#"{method="GET",route="/users/{id}",status="404"} 1"#,
+/// )));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    /// Shared registry.
+    registry: Arc<Mutex<BTreeMap<Labels, Series>>>,
+}
+
+/// Middleware for collecting metrics, bound to a route template.
+#[derive(Debug)]
+pub struct Collector {
+    /// Shared metrics.
+    metrics: Metrics,
+    /// Route template of the scope.
+    route: String,
+    /// Map methods to matchers for all routes of the scope.
+    routes: Arc<BTreeMap<Method, Matcher>>,
+}
+
+/// Metric labels.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Labels {
+    /// Request method.
+    method: Method,
+    /// Route template.
+    route: String,
+    /// Response status code.
+    status: u16,
+}
+
+/// Metric series.
+#[derive(Clone, Debug, Default)]
+struct Series {
+    /// Number of requests.
+    count: u64,
+    /// Sum of latencies in seconds.
+    sum: f64,
+    /// Number of requests per bucket.
+    buckets: [u64; BUCKETS.len()],
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Metrics {
+    /// Creates metrics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::metrics::Metrics;
+    ///
+    /// // Create metrics
+    /// let metrics = Metrics::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renders the metrics in the Prometheus text format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::metrics::Metrics;
+    ///
+    /// // Create and render metrics
+    /// let metrics = Metrics::new();
+    /// println!("{}", metrics.render());
+    /// ```
+    #[must_use]
+    pub fn render(&self) -> String {
+        let registry = self.lock();
+        let mut output = String::new();
+
+        // Render request counters - writing to a string is infallible
+        output.push_str(concat!(
+            "# HELP http_requests_total Total number of HTTP requests.\n",
+            "# TYPE http_requests_total counter\n",
+        ));
+        for (labels, series) in registry.iter() {
+            let labels = labels.to_string();
+            let count = series.count;
+            let _ = writeln!(output, "http_requests_total{{{labels}}} {count}");
+        }
+
+        // Render latency histograms
+        output.push_str(concat!(
+            "# HELP http_request_duration_seconds ",
+            "HTTP request latency in seconds.\n",
+            "# TYPE http_request_duration_seconds histogram\n",
+        ));
+        for (labels, series) in registry.iter() {
+            let labels = labels.to_string();
+            let name = "http_request_duration_seconds";
+            for (bound, count) in BUCKETS.iter().zip(series.buckets) {
+                let _ = writeln!(
+                    output,
+                    "{name}_bucket{{{labels},le=\"{bound}\"}} {count}"
+                );
+            }
+
+            // Render catch-all bucket, sum and count
+            let count = series.count;
+            let _ = writeln!(
+                output,
+                "{name}_bucket{{{labels},le=\"+Inf\"}} {count}"
+            );
+            let _ = writeln!(output, "{name}_sum{{{labels}}} {}", series.sum);
+            let _ = writeln!(output, "{name}_count{{{labels}}} {count}");
+        }
+
+        // Return output
+        output
+    }
+
+    /// Records a request with the given labels and latency.
+    fn record(&self, labels: Labels, latency: f64) {
+        let mut registry = self.lock();
+        let series = registry.entry(labels).or_default();
+
+        // Update counter and histogram - buckets are cumulative
+        series.count += 1;
+        series.sum += latency;
+        for (bound, count) in BUCKETS.iter().zip(series.buckets.iter_mut()) {
+            if latency <= *bound {
+                *count += 1;
+            }
+        }
+    }
+
+    /// Locks the registry, ignoring poisoning, since it's never left in an
+    /// inconsistent state, as no code that could panic runs while locked.
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<Labels, Series>> {
+        self.registry.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl TryIntoMiddleware for Metrics {
+    type Output = Collector;
+
+    /// Attempts to convert the metrics into a middleware.
+    ///
+    /// The routes of the given scope are used to determine the template of
+    /// the matched route, falling back to the route template of the scope, or
+    /// `/`, if the scope doesn't define a route, e.g., outside of routers.
+    fn try_into_middleware(self, scope: &Scope) -> Result<Self::Output> {
+        let route = scope.route_template().unwrap_or("/").to_string();
+        let routes = Arc::clone(&scope.routes);
+        Ok(Collector { metrics: self, route, routes })
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Middleware for Collector {
+    /// Processes the given request.
+    ///
+    /// The request is forwarded to the next handler, and the latency as well
+    /// as the status of the response are recorded afterwards.
+    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+        let method = req.method;
+        let start = Instant::now();
+
+        // Determine template of the matched route before the request is
+        // forwarded, as it might be rewritten by the next handler
+        let path = canonicalize(&req.uri.path);
+        let route = self
+            .routes
+            .get(&method)
+            .and_then(|matcher| matcher.resolve(path))
+            .map_or_else(
+                || self.route.clone(),
+                |route| route.params.route_template().to_owned(),
+            );
+
+        // Forward to next handler and record metrics
+        let res = next.handle(req);
+        let labels = Labels {
+            method,
+            route,
+            status: res.status as u16,
+        };
+        self.metrics.record(labels, start.elapsed().as_secs_f64());
+
+        // Return response
+        res
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl fmt::Display for Labels {
+    /// Formats the labels for display.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let route = self
+            .route
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n");
+
+        // Format labels in the Prometheus text format
+        write!(
+            f,
+            "method=\"{}\",route=\"{route}\",status=\"{}\"",
+            self.method, self.status
+        )
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Middleware for propagating request identifiers.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::handler::Handler;
+use crate::http::{Header, Request, Response};
+use crate::middleware::Middleware;
+
+// ----------------------------------------------------------------------------
+// Constants
+// ----------------------------------------------------------------------------
+
+/// Maximum length of incoming request identifiers.
+const ID_MAX_LENGTH: usize = 128;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware for propagating request identifiers.
+///
+/// This middleware reads the [`Header::XRequestId`] header of the incoming
+/// request, or generates a new identifier if the header is missing or invalid,
+/// i.e., empty, too long, or not made of visible ASCII characters. The header
+/// is then set on the request for downstream access, and echoed on response,
+/// which allows to correlate logs across services for distributed tracing.
+///
+/// Identifiers are generated from a random prefix, which is chosen when the
+/// middleware is created, and a counter, so generation is cheap and does not
+/// require a dependency. Identifiers are unique for the lifetime of the
+/// middleware, and very likely unique across middlewares and processes.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Request, Response};
+/// use zense::middleware::request_id::RequestId;
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(RequestId::new())
+///     .with(|req: Request, _: &dyn Handler| {
+///         let id = req.headers.get(Header::XRequestId).unwrap_or_default();
+///         Response::text(id)
+///     })
+///     .try_into_handler()?;
+///
+/// // Handle request with identifier - identifier is preserved
+/// let req = Request::new().header(Header::XRequestId, "abc-123");
+/// let res = stack.handle(req);
+/// assert_eq!(res.headers.get(Header::XRequestId), Some("abc-123"));
+/// assert_eq!(res.body, b"abc-123");
+///
+/// // Handle request without identifier - identifier is generated
+/// let res = stack.handle(Request::new());
+/// let id = res.headers.get(Header::XRequestId).unwrap();
+/// assert_eq!(res.body, id.as_bytes());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RequestId {
+    /// Random prefix.
+    prefix: u64,
+    /// Counter for generated identifiers.
+    counter: AtomicU64,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl RequestId {
+    /// Creates a middleware for propagating request identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::request_id::RequestId;
+    ///
+    /// // Create middleware
+    /// let middleware = RequestId::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generates a new request identifier.
+    fn generate(&self) -> String {
+        let count = self.counter.fetch_add(1, Ordering::Relaxed);
+        format!("{:016x}-{count:08x}", self.prefix)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Middleware for RequestId {
+    /// Processes the given request.
+    fn process(&self, mut req: Request, next: &dyn Handler) -> Response {
+        let id = match req.headers.get(Header::XRequestId) {
+            Some(id) if is_valid(id) => id.to_owned(),
+            _ => self.generate(),
+        };
+
+        // Set identifier on request, and echo it on response
+        req.headers.put(Header::XRequestId, id.clone());
+        let mut res = next.handle(req);
+        res.headers.put(Header::XRequestId, id);
+        res
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Default for RequestId {
+    /// Creates a middleware for propagating request identifiers.
+    ///
+    /// The random prefix is derived from the keys of [`RandomState`], which
+    /// are seeded from the operating system's source of randomness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::request_id::RequestId;
+    ///
+    /// // Create middleware
+    /// let middleware = RequestId::default();
+    /// ```
+    fn default() -> Self {
+        Self {
+            prefix: RandomState::new().build_hasher().finish(),
+            counter: AtomicU64::new(0),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Returns whether the given request identifier is valid.
+fn is_valid(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= ID_MAX_LENGTH
+        && id.bytes().all(|b| b.is_ascii_graphic())
+}
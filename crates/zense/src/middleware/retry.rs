@@ -0,0 +1,235 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Middleware for retrying requests.
+
+use std::fmt;
+use std::thread;
+use std::time::Duration;
+
+use crate::handler::Handler;
+use crate::http::{Request, Response};
+use crate::middleware::Middleware;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware for retrying requests.
+///
+/// When this crate is used as a gateway, upstream handlers might fail for
+/// transient reasons. This middleware passes a clone of the request to the
+/// next handler, and retries up to the configured number of times, as long
+/// as the response is considered a failure, which by default is the case for
+/// all server errors, i.e., `5xx`. The response of the last attempt is always
+/// returned, whether it succeeded or not.
+///
+/// Only requests with idempotent methods are retried, since repeating others
+/// might have unintended side effects. An optional backoff delays retries,
+/// doubling the delay after each attempt. Note that the delay blocks the
+/// current thread, which is fine for the synchronous handlers of this crate.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::{Request, Response, Status};
+/// use zense::middleware::retry::Retry;
+///
+/// // Create counter for attempts
+/// let attempts = AtomicUsize::new(0);
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(Retry::new().retries(3))
+///     .with(move |_: Request, _: &dyn Handler| {
+///         if attempts.fetch_add(1, Ordering::Relaxed) < 2 {
+///             Response::new().status(Status::BadGateway)
+///         } else {
+///             Response::new()
+///         }
+///     })
+///     .try_into_handler()?;
+///
+/// // Handle request with stack - succeeds after two failed attempts
+/// let res = stack.handle(Request::new());
+/// assert_eq!(res.status, Status::Ok);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Retry {
+    /// Maximum number of retries.
+    retries: usize,
+    /// Initial delay between attempts.
+    backoff: Duration,
+    /// Predicate for failed responses.
+    predicate: Box<dyn Fn(&Response) -> bool>,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Retry {
+    /// Creates a middleware for retrying requests.
+    ///
+    /// By default, requests are retried twice without delay, and responses
+    /// with server errors are considered failures.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::retry::Retry;
+    ///
+    /// // Create middleware
+    /// let middleware = Retry::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of retries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::retry::Retry;
+    ///
+    /// // Create middleware with maximum number of retries
+    /// let middleware = Retry::new()
+    ///     .retries(5);
+    /// ```
+    #[must_use]
+    pub fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets the initial delay between attempts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use zense::middleware::retry::Retry;
+    ///
+    /// // Create middleware with backoff
+    /// let middleware = Retry::new()
+    ///     .backoff(Duration::from_millis(50));
+    /// ```
+    #[must_use]
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets the predicate that determines whether a response is a failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Status;
+    /// use zense::middleware::retry::Retry;
+    ///
+    /// // Create middleware with predicate
+    /// let middleware = Retry::new()
+    ///     .when(|res| res.status == Status::ServiceUnavailable);
+    /// ```
+    #[must_use]
+    pub fn when<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Response) -> bool + 'static,
+    {
+        self.predicate = Box::new(f);
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Middleware for Retry {
+    /// Processes the given request.
+    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+        if !req.method.is_idempotent() {
+            return next.handle(req);
+        }
+
+        // Pass clones of the request to the next handler, until the response
+        // is not a failure, or the maximum number of retries is reached
+        let mut delay = self.backoff;
+        for _ in 0..self.retries {
+            let res = next.handle(req.clone());
+            if !(self.predicate)(&res) {
+                return res;
+            }
+
+            // Wait before next attempt, doubling the delay
+            if !delay.is_zero() {
+                thread::sleep(delay);
+                delay = delay.saturating_mul(2);
+            }
+        }
+
+        // Last attempt consumes the request
+        next.handle(req)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Default for Retry {
+    /// Creates a middleware for retrying requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::retry::Retry;
+    ///
+    /// // Create middleware
+    /// let middleware = Retry::default();
+    /// ```
+    fn default() -> Self {
+        Self {
+            retries: 2,
+            backoff: Duration::ZERO,
+            predicate: Box::new(|res| res.status.is_server_error()),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl fmt::Debug for Retry {
+    /// Formats the middleware for debugging.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Retry")
+            .field("retries", &self.retries)
+            .field("backoff", &self.backoff)
+            .finish_non_exhaustive()
+    }
+}
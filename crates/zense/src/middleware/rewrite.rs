@@ -0,0 +1,278 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Middleware for rewriting request paths.
+
+use std::str::FromStr;
+
+use crate::handler::matcher::{Params, Route};
+use crate::handler::{Error, Handler, Matcher, Result, Scope};
+use crate::http::response::ResponseExt;
+use crate::http::{Header, Request, Response, Status, Uri};
+use crate::middleware::{Middleware, TryIntoMiddleware};
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware for rewriting request paths.
+///
+/// Rules map a pattern to a replacement, and are matched in the same way as
+/// the routes of a [`Router`][], which means that patterns can contain named
+/// parameters like `{id}`, as well as a catch-all parameter like `{*rest}`.
+/// The replacement is a path, in which parameters are substituted with the
+/// values they matched, e.g., `/legacy/{*rest}` and `/{rest}` strip a prefix.
+/// Placeholders that are not defined by the pattern are kept verbatim.
+///
+/// Patterns are scoped, i.e., relative to the base path of a router, while
+/// replacements are always absolute, and start with a single slash after
+/// substitution, so redirects can't point to another host. An internal
+/// rewrite transparently changes the path of the request before it's passed
+/// on, and retains the query string, while a redirect answers the request with
+/// the given status, and a location pointing to the rewritten path, including
+/// the query string.
+///
+/// [`Router`]: crate::router::Router
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Request, Response, Status};
+/// use zense::middleware::rewrite::Rewrite;
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(Rewrite::new()
+///         .internal("/api/v1/{*rest}", "/{rest}")
+///         .redirect("/posts/{id}", "/articles/{id}", Status::MovedPermanently)
+///         .redirect("/legacy/{*rest}", "/{rest}", Status::MovedPermanently)
+///     )
+///     .with(|req: Request, _: &dyn Handler| {
+///         Response::text(req.uri.to_string())
+///     })
+///     .try_into_handler()?;
+///
+/// // Handle request with internal rewrite
+/// let res = stack.handle(Request::new().uri("/api/v1/users/7?full"));
+/// assert_eq!(res.body, b"/users/7?full");
+///
+/// // Handle request with redirect
+/// let res = stack.handle(Request::new().uri("/posts/7?page=2"));
+/// assert_eq!(res.status, Status::MovedPermanently);
+/// assert_eq!(res.headers.get(Header::Location), Some("/articles/7?page=2"));
+///
+/// // Handle request with redirect, which can't point to another host
+/// let res = stack.handle(Request::new().uri("/legacy//example.com"));
+/// assert_eq!(res.headers.get(Header::Location), Some("/example.com"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Rewrite {
+    /// List of rules.
+    rules: Vec<(String, Rule)>,
+}
+
+/// Middleware for rewriting request paths (after conversion).
+#[derive(Debug)]
+pub struct Rewriter {
+    /// Matcher for rules.
+    matcher: Matcher<Rule>,
+}
+
+/// Rewrite rule.
+#[derive(Debug)]
+struct Rule {
+    /// Replacement path.
+    replacement: String,
+    /// Redirect status, if any.
+    redirect: Option<Status>,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Rewrite {
+    /// Creates a middleware for rewriting request paths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::rewrite::Rewrite;
+    ///
+    /// // Create middleware
+    /// let middleware = Rewrite::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule for an internal rewrite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::rewrite::Rewrite;
+    ///
+    /// // Create middleware and add rule
+    /// let middleware = Rewrite::new()
+    ///     .internal("/legacy/{*rest}", "/{rest}");
+    /// ```
+    #[must_use]
+    pub fn internal<P, R>(self, pattern: P, replacement: R) -> Self
+    where
+        P: Into<String>,
+        R: Into<String>,
+    {
+        self.rule(pattern.into(), replacement.into(), None)
+    }
+
+    /// Adds a rule for a redirect with the given status.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::Status;
+    /// use zense::middleware::rewrite::Rewrite;
+    ///
+    /// // Create middleware and add rule
+    /// let middleware = Rewrite::new()
+    ///     .redirect("/blog/{slug}", "/posts/{slug}", Status::Found);
+    /// ```
+    #[must_use]
+    pub fn redirect<P, R>(
+        self, pattern: P, replacement: R, status: Status,
+    ) -> Self
+    where
+        P: Into<String>,
+        R: Into<String>,
+    {
+        self.rule(pattern.into(), replacement.into(), Some(status))
+    }
+
+    /// Adds a rule.
+    fn rule(
+        mut self, pattern: String, replacement: String,
+        redirect: Option<Status>,
+    ) -> Self {
+        self.rules.push((pattern, Rule { replacement, redirect }));
+        self
+    }
+}
+
+impl Rule {
+    /// Returns the replacement path with substituted parameters.
+    fn apply(&self, params: &Params) -> String {
+        let mut path = String::with_capacity(self.replacement.len());
+        let mut rest = self.replacement.as_str();
+        while let Some((head, tail)) = rest.split_once('{') {
+            let Some((name, tail)) = tail.split_once('}') else {
+                break;
+            };
+
+            // Substitute parameter, or keep placeholder verbatim
+            path.push_str(head);
+            if let Some(value) = params.get(name.trim_start_matches('*')) {
+                path.push_str(value);
+            } else {
+                path.push('{');
+                path.push_str(name);
+                path.push('}');
+            }
+            rest = tail;
+        }
+
+        // Append remainder, and ensure path starts with a single slash, as
+        // substituted values might start with slashes or backslashes, which
+        // clients interpret as protocol-relative, e.g., `//host` or `/\host`,
+        // turning redirects into open redirects
+        path.push_str(rest);
+        let trimmed = path.trim_start_matches(['/', '\\']);
+        if path.len() - trimmed.len() != 1 || !path.starts_with('/') {
+            path = format!("/{trimmed}");
+        }
+        path
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl TryIntoMiddleware for Rewrite {
+    type Output = Rewriter;
+
+    /// Attempts to convert the rewrite into a middleware.
+    ///
+    /// The patterns of all rules are appended to the route of the given scope,
+    /// if any, and added to a matcher, so rules are resolved in a single pass.
+    fn try_into_middleware(self, scope: &Scope) -> Result<Self::Output> {
+        let mut matcher = Matcher::new();
+        for (pattern, rule) in self.rules {
+            let route = Route::from_str(&pattern)
+                .map_err(|err| Error::Matcher(err.into()))?;
+            let route = match &scope.route {
+                Some(base) => base.append(route),
+                None => route,
+            };
+            matcher.add(route, rule)?;
+        }
+
+        // Return middleware
+        Ok(Rewriter { matcher })
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Middleware for Rewriter {
+    /// Processes the given request.
+    fn process(&self, mut req: Request, next: &dyn Handler) -> Response {
+        let Some((rule, params)) = self.matcher.find(&req.uri.path) else {
+            return next.handle(req);
+        };
+
+        // Rewrite path, and either redirect or pass request on
+        let path = rule.apply(&params);
+        if let Some(status) = rule.redirect {
+            let uri = Uri::from_parts(path, req.uri.query);
+            Response::from_status(status).header(Header::Location, uri)
+        } else {
+            // Reset the mount point, unless the matched prefix is retained
+            let prefix = req.uri.path.get(..req.mount);
+            let rest = prefix.and_then(|prefix| path.strip_prefix(prefix));
+            let retained = rest
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+            if !retained {
+                req.mount = 0;
+            }
+            req.uri.path = path.into();
+            next.handle(req)
+        }
+    }
+}
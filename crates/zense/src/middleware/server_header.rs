@@ -0,0 +1,148 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Middleware for setting the server header.
+
+use crate::handler::Handler;
+use crate::http::{Header, Request, Response};
+use crate::middleware::Middleware;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Middleware for setting the server header.
+///
+/// This middleware sets the [`Header::Server`] header on responses, which
+/// identifies the software handling the request, and defaults to `zense`. The
+/// header is only set if absent, so values set by handlers take precedence.
+/// Note that detailed product tokens, e.g., including the version, might help
+/// attackers to identify vulnerable deployments, so they should be used with
+/// care.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, Stack, TryIntoHandler};
+/// use zense::http::{Header, Request, Response};
+/// use zense::middleware::server_header::ServerHeader;
+///
+/// // Create stack with middleware
+/// let stack = Stack::new()
+///     .with(ServerHeader::new())
+///     .with(|req: Request, _: &dyn Handler| {
+///         if req.uri.path == "/custom" {
+///             Response::new().header(Header::Server, "custom")
+///         } else {
+///             Response::new()
+///         }
+///     })
+///     .try_into_handler()?;
+///
+/// // Handle request - header is added
+/// let res = stack.handle(Request::new());
+/// assert_eq!(res.headers.get(Header::Server), Some("zense"));
+///
+/// // Handle request with header set by handler - header is preserved
+/// let res = stack.handle(Request::new().uri("/custom"));
+/// assert_eq!(res.headers.get(Header::Server), Some("custom"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ServerHeader {
+    /// Product token.
+    product: String,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl ServerHeader {
+    /// Creates a middleware for setting the server header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::server_header::ServerHeader;
+    ///
+    /// // Create middleware
+    /// let middleware = ServerHeader::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the product token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::server_header::ServerHeader;
+    ///
+    /// // Create middleware and set product token
+    /// let middleware = ServerHeader::new().product("acme/1.0");
+    /// ```
+    #[must_use]
+    pub fn product<P>(mut self, product: P) -> Self
+    where
+        P: Into<String>,
+    {
+        self.product = product.into();
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Middleware for ServerHeader {
+    /// Processes the given request.
+    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+        let mut res = next.handle(req);
+        res.headers.put_if_absent(Header::Server, &self.product);
+        res
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Default for ServerHeader {
+    /// Creates a middleware for setting the server header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::middleware::server_header::ServerHeader;
+    ///
+    /// // Create middleware
+    /// let middleware = ServerHeader::default();
+    /// ```
+    fn default() -> Self {
+        Self { product: String::from("zense") }
+    }
+}
@@ -22,11 +22,13 @@
 
 //! Router.
 
+use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use crate::handler::matcher::Route;
 use crate::handler::stack::{self, Stack};
-use crate::handler::{Error, Result, Scope, TryIntoHandler};
+use crate::handler::{Error, Handler, Matcher, Result, Scope, TryIntoHandler};
 use crate::http::Method;
 use crate::middleware::{Middleware, TryIntoMiddleware};
 
@@ -34,9 +36,14 @@ use crate::middleware::{Middleware, TryIntoMiddleware};
 pub use crate::handler::matcher::Params;
 
 mod action;
+mod fallback;
+mod host;
 mod routes;
 
 pub use action::Action;
+use fallback::Fallback;
+pub use host::{HostRouter, Hosts};
+pub(crate) use routes::canonicalize;
 use routes::Routes;
 
 // ----------------------------------------------------------------------------
@@ -71,6 +78,12 @@ pub struct Router {
     builders: Vec<Builder>,
     /// Base path.
     path: String,
+    /// Whether to answer `OPTIONS` requests automatically.
+    auto_options: bool,
+    /// Fallback handler.
+    fallback: Option<Box<dyn Handler>>,
+    /// Handler for disallowed methods.
+    not_allowed: Option<Box<dyn Handler>>,
 }
 
 // ----------------------------------------------------------------------------
@@ -98,6 +111,9 @@ impl Router {
         Self {
             builders: Vec::new(),
             path: path.into(),
+            auto_options: true,
+            fallback: None,
+            not_allowed: None,
         }
     }
 
@@ -301,7 +317,10 @@ impl Router {
     /// the router is converted into a middleware.
     ///
     /// Anything that can be converted into a [`Middleware`] can be added to
-    /// the stack, including middlewares, routers, stacks and closures.
+    /// the stack, including middlewares, routers, stacks and closures. When
+    /// routers are nested, their base paths are composed, so a middleware of a
+    /// router with base path `/admin`, added to a router with base path `/app`,
+    /// is only invoked for `/app/admin` and paths below it.
     ///
     /// # Errors
     ///
@@ -328,6 +347,38 @@ impl Router {
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// Nesting routers:
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::{Handler, TryIntoHandler};
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Request, Response, Status};
+    /// use zense::router::Router;
+    ///
+    /// // Create router with nested router and middleware
+    /// let router = Router::new("/app")
+    ///     .with(Router::new("/admin").with(|_: Request, _: &dyn Handler| {
+    ///         Response::text("admin")
+    ///     }))
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle requests under combined base path
+    /// for path in ["/app/admin", "/app/admin/", "/app/admin/users"] {
+    ///     let res = router.handle(Request::new().uri(path));
+    ///     assert_eq!(res.body, b"admin");
+    /// }
+    ///
+    /// // Handle requests outside of combined base path
+    /// for path in ["/admin", "/app", "/app/users", "/app/administrator"] {
+    ///     let res = router.handle(Request::new().uri(path));
+    ///     assert_eq!(res.status, Status::NotFound);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
     #[must_use]
     pub fn with<M>(mut self, middleware: M) -> Self
     where
@@ -347,6 +398,213 @@ impl Router {
         self
     }
 
+    /// Configures whether to answer `OPTIONS` requests automatically.
+    ///
+    /// When enabled, which is the default, `OPTIONS` requests for paths that
+    /// match routes of other methods are answered with "204 No Content" and
+    /// an [`Header::Allow`][] header listing those methods, unless a route for
+    /// `OPTIONS` matches the path, which always takes precedence.
+    ///
+    /// [`Header::Allow`]: crate::http::Header::Allow
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::{Handler, TryIntoHandler};
+    /// use zense::http::{Header, Method, Request, Response, Status};
+    /// use zense::router::{Params, Router};
+    ///
+    /// // Create router and add routes
+    /// let router = Router::default()
+    ///     .get("/users", |_: Request, _: Params| Response::new())
+    ///     .post("/users", |_: Request, _: Params| Response::new())
+    ///     .try_into_handler()?;
+    ///
+    /// // Create request
+    /// let req = Request::new()
+    ///     .method(Method::Options)
+    ///     .uri("/users");
+    ///
+    /// // Handle request with router
+    /// let res = router.handle(req);
+    /// assert_eq!(res.status, Status::NoContent);
+    /// assert_eq!(res.headers.get(Header::Allow), Some("GET, POST"));
+    ///
+    /// // Create router with routes separated by a middleware
+    /// let router = Router::default()
+    ///     .get("/users", |_: Request, _: Params| Response::new())
+    ///     .with(|req: Request, next: &dyn Handler| next.handle(req))
+    ///     .delete("/users", |_: Request, _: Params| Response::new())
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request with router, listing the methods of all routes
+    /// let req = Request::new().method(Method::Options).uri("/users");
+    /// let res = router.handle(req);
+    /// assert_eq!(res.headers.get(Header::Allow), Some("GET, DELETE"));
+    ///
+    /// // Create router with explicit route for OPTIONS after a middleware
+    /// let router = Router::default()
+    ///     .get("/users", |_: Request, _: Params| Response::new())
+    ///     .with(|req: Request, next: &dyn Handler| next.handle(req))
+    ///     .options("/users", |_: Request, _: Params| {
+    ///         Response::new().status(Status::Ok)
+    ///     })
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request with router, which invokes the explicit route
+    /// let req = Request::new().method(Method::Options).uri("/users");
+    /// let res = router.handle(req);
+    /// assert_eq!(res.status, Status::Ok);
+    ///
+    /// // Create router with automatic answers disabled
+    /// let router = Router::default()
+    ///     .auto_options(false)
+    ///     .get("/users", |_: Request, _: Params| Response::new())
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request with router
+    /// let req = Request::new().method(Method::Options).uri("/users");
+    /// let res = router.handle(req);
+    /// assert_eq!(res.status, Status::NotFound);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn auto_options(mut self, enabled: bool) -> Self {
+        self.auto_options = enabled;
+        self
+    }
+
+    /// Sets the fallback handler, invoked when no route matches.
+    ///
+    /// By default, requests that don't match any route are passed on to the
+    /// next handler, which eventually answers with "404 Not Found". With a
+    /// fallback handler, the router answers those requests itself, e.g., with
+    /// a branded error page. Note that the fallback handler is only invoked
+    /// for requests within the base path of the router.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::{Handler, TryIntoHandler};
+    /// use zense::http::{Header, Request, Response, Status};
+    /// use zense::router::{Params, Router};
+    ///
+    /// // Create router with fallback handler
+    /// let router = Router::default()
+    ///     .get("/users", |_: Request, _: Params| Response::new())
+    ///     .fallback(|_: Request| {
+    ///         Response::new()
+    ///             .status(Status::NotFound)
+    ///             .header(Header::ContentType, "application/json")
+    ///             .body(r#"{"error":"not found"}"#)
+    ///     })
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request for unmatched path with router
+    /// let res = router.handle(Request::new().uri("/posts"));
+    /// assert_eq!(res.status, Status::NotFound);
+    /// assert_eq!(res.body, br#"{"error":"not found"}"#);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn fallback<H>(mut self, handler: H) -> Self
+    where
+        H: Handler + 'static,
+    {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Sets the handler for disallowed methods.
+    ///
+    /// When no route matches, but the path matches routes of other methods,
+    /// the request is answered by the given handler, and an [`Header::Allow`][]
+    /// header listing the allowed methods is added to the response. Without
+    /// this handler, those requests are treated like any other request that
+    /// doesn't match, so the fallback handler, if any, is invoked.
+    ///
+    /// [`Header::Allow`]: crate::http::Header::Allow
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::{Handler, TryIntoHandler};
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Header, Method, Request, Response, Status};
+    /// use zense::router::{Params, Router};
+    ///
+    /// // Create router with handler for disallowed methods
+    /// let router = Router::default()
+    ///     .get("/users", |_: Request, _: Params| Response::new())
+    ///     .not_allowed(|_: Request| {
+    ///         Response::from_status(Status::MethodNotAllowed)
+    ///     })
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request with disallowed method with router
+    /// let req = Request::new().method(Method::Delete).uri("/users");
+    /// let res = router.handle(req);
+    /// assert_eq!(res.status, Status::MethodNotAllowed);
+    /// assert_eq!(res.headers.get(Header::Allow), Some("GET"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn not_allowed<H>(mut self, handler: H) -> Self
+    where
+        H: Handler + 'static,
+    {
+        self.not_allowed = Some(Box::new(handler));
+        self
+    }
+
+    /// Returns an iterator over all routes of the router.
+    ///
+    /// Routes are returned as pairs of methods and route templates, grouped by
+    /// consecutive routes and ordered by method within each group. Templates
+    /// are returned as registered, i.e., relative to the router's base path,
+    /// and routes of nested routers aren't included, as nested routers are
+    /// middlewares, which are only converted when building the router.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Method, Request, Response};
+    /// use zense::router::{Params, Router};
+    ///
+    /// // Create router and add routes
+    /// let router = Router::default()
+    ///     .get("/users", |_: Request, _: Params| Response::new())
+    ///     .post("/users", |_: Request, _: Params| Response::new())
+    ///     .get("/users/{id}", |_: Request, _: Params| Response::new());
+    ///
+    /// // Obtain routes
+    /// let routes = router.routes().collect::<Vec<_>>();
+    /// assert_eq!(routes, [
+    ///     (&Method::Get, "/users"),
+    ///     (&Method::Get, "/users/{id}"),
+    ///     (&Method::Post, "/users"),
+    /// ]);
+    /// ```
+    pub fn routes(&self) -> impl Iterator<Item = (&Method, &str)> {
+        let iter = self.builders.iter().filter_map(|item| match item {
+            Builder::Stack(_) => None,
+            Builder::Routes(builder) => Some(builder.iter()),
+        });
+
+        // Flatten routes of all route builders
+        iter.flatten()
+    }
+
     /// Adds a route to the router.
     fn route<P, A>(mut self, method: Method, path: P, action: A) -> Self
     where
@@ -411,12 +669,37 @@ impl TryIntoMiddleware for Router {
 
         // Join the parent scope with the scope derived from the router's base
         // path, which is then used for constructing routes and stacks
-        let scope = scope.join(path);
+        let mut scope = scope.join(path);
+
+        // Create a matcher for all routes of each method, which is shared via
+        // the scope, so middlewares can determine the matched route, and with
+        // the fallback, so it can determine whether a path matches routes of
+        // other methods. Errors can be ignored, as routes are validated and
+        // checked during their conversion below.
+        let mut matchers = BTreeMap::<Method, Matcher>::new();
+        let base = scope.route.clone().unwrap_or_default();
+        for (method, path) in self.routes() {
+            if let Ok(route) = Route::from_str(path) {
+                let matcher = matchers.entry(*method).or_default();
+                let _ = matcher.add(base.append(route), ());
+            }
+        }
+        scope.routes = Arc::new(matchers);
+
+        // Create fallback, if any handler is set, which is added last
+        let fallback = (self.fallback.is_some() || self.not_allowed.is_some())
+            .then(|| Fallback {
+                handler: self.fallback,
+                not_allowed: self.not_allowed,
+                matchers: Arc::clone(&scope.routes),
+            })
+            .map(|fallback| Ok(Box::new(fallback) as Box<dyn Middleware>));
 
         // Transform builders into middlewares - routers can host builders for
         // stacks and routes, both of which are converted into middlewares, and
         // then collected into a stack that can be converted into a handler.
         // Routes are validated and checked during conversion.
+        let auto_options = self.auto_options;
         let iter = self.builders.into_iter().map(|item| match item {
             // Convert stack into middleware
             Builder::Stack(builder) => builder
@@ -425,12 +708,13 @@ impl TryIntoMiddleware for Router {
 
             // Convert routes into middleware
             Builder::Routes(builder) => builder
+                .auto_options(auto_options)
                 .try_into_middleware(&scope)
                 .map(|middleware| Box::new(middleware) as Box<dyn Middleware>),
         });
 
-        // Collect middlewares into a stack
-        iter.collect()
+        // Collect middlewares and fallback into a stack
+        iter.chain(fallback).collect()
     }
 }
 
@@ -489,6 +773,9 @@ impl Default for Router {
         Self {
             builders: Vec::default(),
             path: String::from("/"),
+            auto_options: true,
+            fallback: None,
+            not_allowed: None,
         }
     }
 }
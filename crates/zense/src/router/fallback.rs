@@ -0,0 +1,84 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Fallback.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::handler::{Handler, Matcher};
+use crate::http::{Header, Method, Request, Response};
+use crate::middleware::Middleware;
+
+use super::routes::canonicalize;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Fallback.
+///
+/// The fallback is added as the last middleware of a router, which means it's
+/// only reached if none of the routes matched. If the path matches routes of
+/// other methods, the request is answered by the handler for disallowed
+/// methods, if any, and otherwise by the fallback handler, if any.
+#[derive(Debug)]
+pub struct Fallback {
+    /// Fallback handler.
+    pub handler: Option<Box<dyn Handler>>,
+    /// Handler for disallowed methods.
+    pub not_allowed: Option<Box<dyn Handler>>,
+    /// Map methods to matchers.
+    pub matchers: Arc<BTreeMap<Method, Matcher>>,
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Middleware for Fallback {
+    /// Processes the given request.
+    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+        if let Some(handler) = &self.not_allowed {
+            let path = canonicalize(&req.uri.path);
+            let methods = self
+                .matchers
+                .iter()
+                .filter(|(_, matcher)| matcher.resolve(path).is_some())
+                .map(|(method, _)| method.to_string())
+                .collect::<Vec<_>>();
+
+            // Answer with handler for disallowed methods, listing all methods
+            // that are allowed, if the path matches routes of other methods
+            if !methods.is_empty() {
+                let res = handler.handle(req);
+                return res.header(Header::Allow, methods.join(", "));
+            }
+        }
+
+        // Answer with fallback handler, or forward to next handler
+        match &self.handler {
+            Some(handler) => handler.handle(req),
+            None => next.handle(req),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Host router.
+
+use std::collections::HashMap;
+
+use crate::handler::{Handler, Result, Scope, Stack, TryIntoHandler};
+use crate::http::{Header, Request, Response};
+use crate::middleware::{Middleware, TryIntoMiddleware};
+
+use super::Router;
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Host router.
+///
+/// Host routers dispatch requests to routers based on the [`Header::Host`]
+/// header, which allows for virtual hosting, i.e., serving multiple hosts from
+/// a single server. Hosts are compared case-insensitively and without port.
+/// Requests for hosts that are not registered are passed to the fallback
+/// router, if any, or to the next handler, which answers with "404 Not Found".
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use zense::handler::{Handler, TryIntoHandler};
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Request, Response, Status};
+/// use zense::router::{HostRouter, Params, Router};
+///
+/// // Create host router with two hosts
+/// let router = HostRouter::new()
+///     .add("api.example.com", Router::default()
+///         .get("/users", |_: Request, _: Params| Response::text("api"))
+///     )
+///     .add("www.example.com", Router::default()
+///         .get("/", |_: Request, _: Params| Response::text("www"))
+///     )
+///     .try_into_handler()?;
+///
+/// // Handle request for first host
+/// let req = Request::new()
+///     .uri("/users")
+///     .header(Header::Host, "api.example.com:8080");
+/// assert_eq!(router.handle(req).body, b"api");
+///
+/// // Handle request for second host
+/// let req = Request::new()
+///     .uri("/")
+///     .header(Header::Host, "WWW.example.com");
+/// assert_eq!(router.handle(req).body, b"www");
+///
+/// // Handle request for route of first host on second host
+/// let req = Request::new()
+///     .uri("/users")
+///     .header(Header::Host, "www.example.com");
+/// assert_eq!(router.handle(req).status, Status::NotFound);
+///
+/// // Handle request for unknown host
+/// let req = Request::new()
+///     .uri("/")
+///     .header(Header::Host, "example.org");
+/// assert_eq!(router.handle(req).status, Status::NotFound);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct HostRouter {
+    /// List of hosts and routers.
+    hosts: Vec<(String, Router)>,
+    /// Fallback router.
+    fallback: Option<Router>,
+}
+
+/// Host router (after conversion).
+#[derive(Debug)]
+pub struct Hosts {
+    /// Map hosts to stacks.
+    hosts: HashMap<String, Stack>,
+    /// Fallback stack.
+    fallback: Option<Stack>,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl HostRouter {
+    /// Creates a host router.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::router::HostRouter;
+    ///
+    /// // Create host router
+    /// let router = HostRouter::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a router for the given host.
+    ///
+    /// The host is given without port, e.g., `api.example.com`. If a router
+    /// was already added for the host, it's replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zense::http::{Request, Response};
+    /// use zense::router::{HostRouter, Params, Router};
+    ///
+    /// // Create host router and add router
+    /// let router = HostRouter::new()
+    ///     .add("api.example.com", Router::default()
+    ///         .get("/", |_: Request, _: Params| Response::new())
+    ///     );
+    /// ```
+    #[must_use]
+    pub fn add<H>(mut self, host: H, router: Router) -> Self
+    where
+        H: AsRef<str>,
+    {
+        self.hosts.push((hostname(host.as_ref()), router));
+        self
+    }
+
+    /// Sets the fallback router, used for hosts that are not registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::handler::{Handler, TryIntoHandler};
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Header, Request, Response};
+    /// use zense::router::{HostRouter, Params, Router};
+    ///
+    /// // Create host router with fallback router
+    /// let router = HostRouter::new()
+    ///     .fallback(Router::default()
+    ///         .get("/", |_: Request, _: Params| Response::text("default"))
+    ///     )
+    ///     .try_into_handler()?;
+    ///
+    /// // Handle request for unknown host
+    /// let req = Request::new().header(Header::Host, "example.org");
+    /// assert_eq!(router.handle(req).body, b"default");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn fallback(mut self, router: Router) -> Self {
+        self.fallback = Some(router);
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl TryIntoMiddleware for HostRouter {
+    type Output = Hosts;
+
+    /// Attempts to convert the host router into a middleware.
+    ///
+    /// # Errors
+    ///
+    /// In case conversion of any router fails, an [`Error`][] is returned.
+    ///
+    /// [`Error`]: crate::handler::Error
+    fn try_into_middleware(self, scope: &Scope) -> Result<Self::Output> {
+        let mut hosts = HashMap::with_capacity(self.hosts.len());
+        for (host, router) in self.hosts {
+            hosts.insert(host, router.try_into_middleware(scope)?);
+        }
+
+        // Convert fallback router, if any, and return middleware
+        let fallback = self
+            .fallback
+            .map(|router| router.try_into_middleware(scope))
+            .transpose()?;
+        Ok(Hosts { hosts, fallback })
+    }
+}
+
+impl TryIntoHandler for HostRouter {
+    type Output = Stack;
+
+    /// Attempts to convert the host router into a handler.
+    ///
+    /// # Errors
+    ///
+    /// In case conversion of any router fails, an [`Error`][] is returned.
+    ///
+    /// [`Error`]: crate::handler::Error
+    fn try_into_handler(self) -> Result<Self::Output> {
+        let scope = Scope::default();
+        let hosts = self.try_into_middleware(&scope)?;
+        Ok(Stack::from_iter([Box::new(hosts) as Box<dyn Middleware>]))
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+impl Middleware for Hosts {
+    /// Processes the given request.
+    fn process(&self, req: Request, next: &dyn Handler) -> Response {
+        let stack = req
+            .headers
+            .get(Header::Host)
+            .and_then(|value| self.hosts.get(&hostname(value)))
+            .or(self.fallback.as_ref());
+
+        // Pass request to matching stack, or forward to next handler
+        match stack {
+            Some(stack) => stack.process(req, next),
+            None => next.handle(req),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Normalizes the given host, removing the port and a trailing dot.
+fn hostname(value: &str) -> String {
+    let value = value.trim();
+
+    // IPv6 addresses are enclosed in brackets, and contain colons
+    let host = if value.starts_with('[') {
+        value.find(']').map_or(value, |index| &value[..=index])
+    } else {
+        value.split(':').next().unwrap_or(value)
+    };
+
+    // Remove trailing dot of fully qualified names
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
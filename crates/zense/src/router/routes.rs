@@ -23,11 +23,12 @@
 //! Routes.
 
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
 use crate::handler::matcher::{Match, Matcher};
 use crate::handler::Handler;
+use crate::http::{Header, Method, Request, Response, Status};
 use crate::middleware::Middleware;
-use crate::http::{Method, Request, Response};
 
 use super::action::Action;
 
@@ -45,10 +46,20 @@ pub(crate) use builder::Builder;
 /// structure, implemented as part of the [`matchit`] crate. Each set of routes
 /// is scoped to a specific request method, which is used to determine what to
 /// check for when a request is received.
+///
+/// As routes of a router might be split into multiple groups, e.g., when a
+/// middleware is added in between, `OPTIONS` requests are answered with the
+/// methods of all routes of the router, which are shared via the [`Scope`][].
+///
+/// [`Scope`]: crate::handler::Scope
 #[derive(Debug)]
 pub struct Routes {
     /// Map methods to matchers.
     matchers: BTreeMap<Method, Matcher<Box<dyn Action>>>,
+    /// Map methods to matchers for all routes of the router.
+    allowed: Arc<BTreeMap<Method, Matcher>>,
+    /// Whether to answer `OPTIONS` requests automatically.
+    auto_options: bool,
 }
 
 // ----------------------------------------------------------------------------
@@ -61,6 +72,18 @@ impl Routes {
     pub fn builder() -> Builder {
         Builder::new()
     }
+
+    /// Returns whether a request is answered automatically.
+    ///
+    /// This is the case for `OPTIONS` requests, if automatic answers are
+    /// enabled, and no route of the router for `OPTIONS` matches the path, as
+    /// explicit routes take precedence, even if they're in a later group.
+    fn is_auto_options(&self, method: Method, path: &str) -> bool {
+        let options = self.allowed.get(&Method::Options);
+        self.auto_options
+            && method == Method::Options
+            && !options.is_some_and(|routes| routes.resolve(path).is_some())
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -79,25 +102,65 @@ impl Middleware for Routes {
             // If path is borrowed, which is the normal case for parsing, this
             // will only clone the reference, not the contents of the string
             let path = req.uri.path.clone();
+            let path = canonicalize(&path);
 
-            // Next, we canonicalize the path by removing the trailing slash if
-            // it's not the root path, as the path might have been normalized.
-            // This is because the matcher doesn't support optional trailing
-            // slashes, so routes are never allowed to end with a slash.
-            let path = if path == "/" {
-                path.as_ref()
-            } else {
-                path.trim_end_matches('/')
-            };
-
-            // Finally, we resolve the path against the matcher, and invoke the
+            // Next, we resolve the path against the matcher, and invoke the
             // corresponding action if it matches a registered route
             if let Some(Match { data: action, params }) = routes.resolve(path) {
                 return action.handle(req, params);
             }
         }
 
+        // If no route for OPTIONS matched, list the methods of all routes of
+        // the router that match the path, and answer the request, if automatic
+        // answers are enabled, and no other group has a route for OPTIONS
+        let path = canonicalize(&req.uri.path);
+        if self.is_auto_options(req.method, path) {
+            let methods = self
+                .allowed
+                .iter()
+                .filter(|(_, routes)| routes.resolve(path).is_some())
+                .map(|(method, _)| method.to_string())
+                .collect::<Vec<_>>();
+
+            // Only answer if at least one method matched
+            if !methods.is_empty() {
+                return Response::new()
+                    .status(Status::NoContent)
+                    .header(Header::Allow, methods.join(", "));
+            }
+        }
+
         // Forward to next handler
         next.handle(req)
     }
+
+    /// Returns whether a route matches the given request.
+    fn matches(&self, req: &Request) -> bool {
+        let path = canonicalize(&req.uri.path);
+        if self.is_auto_options(req.method, path) {
+            let mut iter = self.allowed.values();
+            iter.any(|routes| routes.resolve(path).is_some())
+        } else {
+            let routes = self.matchers.get(&req.method);
+            routes.is_some_and(|routes| routes.resolve(path).is_some())
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Canonicalizes the given path by removing the trailing slash.
+///
+/// The path might have been normalized, and the matcher doesn't support
+/// optional trailing slashes, which is why routes are never allowed to end
+/// with a slash. The root path is returned as is.
+pub(crate) fn canonicalize(path: &str) -> &str {
+    if path == "/" {
+        path
+    } else {
+        path.trim_end_matches('/')
+    }
 }
@@ -24,6 +24,7 @@
 
 use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use crate::handler::{Error, Matcher, Result, Scope};
 use crate::http::Method;
@@ -42,6 +43,8 @@ use super::Routes;
 pub struct Builder {
     /// Map methods to routes.
     routes: BTreeMap<Method, Vec<(String, Box<dyn Action>)>>,
+    /// Whether to answer `OPTIONS` requests automatically.
+    auto_options: bool,
 }
 
 // ----------------------------------------------------------------------------
@@ -53,7 +56,10 @@ impl Builder {
     #[allow(clippy::new_without_default)]
     #[must_use]
     pub fn new() -> Self {
-        Self { routes: BTreeMap::new() }
+        Self {
+            routes: BTreeMap::new(),
+            auto_options: false,
+        }
     }
 
     /// Adds a route to the routes.
@@ -72,6 +78,20 @@ impl Builder {
             .or_default()
             .push((path.into(), Box::new(action)));
     }
+
+    /// Configures whether to answer `OPTIONS` requests automatically.
+    #[must_use]
+    pub fn auto_options(mut self, enabled: bool) -> Self {
+        self.auto_options = enabled;
+        self
+    }
+
+    /// Returns an iterator over all registered methods and routes.
+    pub fn iter(&self) -> impl Iterator<Item = (&Method, &str)> {
+        self.routes.iter().flat_map(|(method, items)| {
+            items.iter().map(move |(path, _)| (method, path.as_str()))
+        })
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -109,6 +129,10 @@ impl TryIntoMiddleware for Builder {
 
         // Collect methods and routes into an ordered map
         iter.collect::<Result<BTreeMap<_, _>>>()
-            .map(|routes| Routes { matchers: routes })
+            .map(|routes| Routes {
+                matchers: routes,
+                allowed: Arc::clone(&scope.routes),
+                auto_options: self.auto_options,
+            })
     }
 }
@@ -0,0 +1,1085 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Server.
+
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, Shutdown};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::handler::{Handler, TryIntoHandler};
+use crate::http::request::{Config, Error};
+use crate::http::response::{Headers, IntoResponse, ResponseExt};
+use crate::http::{Header, Method, Request, Response, Status, Version};
+
+// ----------------------------------------------------------------------------
+// Structs
+// ----------------------------------------------------------------------------
+
+/// Server.
+///
+/// This is a minimal, blocking server on top of [`TcpListener`], which allows
+/// to use this crate standalone, e.g., for development or internal services.
+/// Connections are accepted on the calling thread, and handed to a fixed pool
+/// of worker threads, each of which serves one connection at a time. Requests
+/// are read with [`Request::from_reader_buffered`], so pipelined requests are
+/// supported, and connections are kept alive, as determined by the client via
+/// [`Request::keep_alive`], until they're idle for too long, or the maximum
+/// number of requests per connection is reached, both of which are limits
+/// that can be configured. The last response signals that the connection is
+/// closed, which handlers can also do with [`Response::close_connection`].
+/// Clients that send [`Header::Expect`] with `100-continue` are answered with
+/// "100 Continue" before the body is read, unless it's rejected right away,
+/// e.g., because it exceeds the maximum body length.
+/// HTTP/1.0 clients only keep connections alive when responses signal it, so
+/// responses to them list `keep-alive` in the [`Header::Connection`] header
+/// for as long as the connection is kept alive.
+///
+/// Requests that can't be read are answered with "400 Bad Request", or a more
+/// specific status, e.g., when limits are exceeded, after which the connection
+/// is closed. Handlers that panic are answered with "500 Internal Server
+/// Error", closing the connection, while the worker continues serving other
+/// connections.
+///
+/// Each worker creates its own handler with the factory passed to
+/// [`Server::serve`], so handlers don't need to be shared between threads.
+/// State that should be shared by all workers, e.g., [`Metrics`][], must be
+/// created outside of the factory, and cloned into each handler.
+///
+/// Note that the number of worker threads is the maximum number of concurrent
+/// connections, as I/O is blocking. Once a request has started, it must be
+/// read entirely within the request timeout, so slow clients can't occupy a
+/// worker indefinitely. When all workers are busy, and a new connection is
+/// accepted, the connection that has waited the longest for its next request
+/// is closed, so keep-alive connections don't prevent new connections from
+/// being served.
+///
+/// [`Metrics`]: crate::middleware::metrics::Metrics
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::io::{Read, Write};
+/// use std::net::TcpStream;
+/// use std::num::NonZeroUsize;
+/// use std::thread;
+/// use zense::http::response::ResponseExt;
+/// use zense::http::{Header, Request, Response};
+/// use zense::router::{Params, Router};
+/// use zense::server::Server;
+///
+/// // Create server bound to an ephemeral port with a single worker
+/// let server = Server::bind("127.0.0.1:0")?
+///     .threads(NonZeroUsize::MIN);
+/// let addr = server.local_addr()?;
+///
+/// // Serve requests in the background with a router, which has routes that
+/// // echo the path, one that uses chunked transfer encoding, and one that
+/// // panics
+/// thread::spawn(move || {
+///     server.serve(|| {
+///         Router::default()
+///             .get("/{drink}", |req: Request, _: Params| {
+///                 Response::text(req.uri.path.into_owned())
+///             })
+///             .head("/{drink}", |req: Request, _: Params| {
+///                 Response::text(req.uri.path.into_owned())
+///             })
+///             .get("/pour", |_: Request, _: Params| {
+///                 Response::new()
+///                     .header(Header::TransferEncoding, "chunked")
+///                     .body("tea")
+///             })
+///             .get("/spill", |_: Request, _: Params| -> Response {
+///                 panic!("spilled")
+///             })
+///     })
+/// });
+///
+/// // Issue two pipelined requests on the same connection, the second of
+/// // which closes it, and read responses until the connection is closed
+/// let mut stream = TcpStream::connect(addr)?;
+/// stream.write_all(concat!(
+///     "GET /coffee HTTP/1.1\r\n\r\n",
+///     "GET /tea HTTP/1.1\r\nConnection: close\r\n\r\n",
+/// ).as_bytes())?;
+/// let mut res = String::new();
+/// stream.read_to_string(&mut res)?;
+///
+/// // Ensure both requests were answered in order
+/// let (first, second) = res.split_once("/coffee").unwrap();
+/// assert!(first.starts_with("HTTP/1.1 200 OK\r\n"));
+/// assert!(second.starts_with("HTTP/1.1 200 OK\r\n"));
+/// assert!(second.contains("Connection: close\r\n"));
+/// assert!(second.ends_with("\r\n\r\n/tea"));
+///
+/// // Issue a HEAD request, followed by a pipelined GET request, and ensure
+/// // the body of the first response is dropped, but its length is retained
+/// let mut stream = TcpStream::connect(addr)?;
+/// stream.write_all(concat!(
+///     "HEAD /coffee HTTP/1.1\r\n\r\n",
+///     "GET /tea HTTP/1.1\r\nConnection: close\r\n\r\n",
+/// ).as_bytes())?;
+/// let mut res = String::new();
+/// stream.read_to_string(&mut res)?;
+/// let (first, second) = res.split_once("\r\n\r\n").unwrap();
+/// assert!(first.contains("Content-Length: 7\r\n"));
+/// assert!(second.starts_with("HTTP/1.1 200 OK\r\n"));
+/// assert!(second.ends_with("\r\n\r\n/tea"));
+///
+/// // Issue an HTTP/1.0 request for a chunked response, and ensure the body
+/// // is sent with a content length, as HTTP/1.0 doesn't define chunking
+/// let mut stream = TcpStream::connect(addr)?;
+/// stream.write_all(b"GET /pour HTTP/1.0\r\n\r\n")?;
+/// let mut res = String::new();
+/// stream.read_to_string(&mut res)?;
+/// assert!(res.starts_with("HTTP/1.0 200 OK\r\n"));
+/// assert!(!res.contains("Transfer-Encoding"));
+/// assert!(res.ends_with("Content-Length: 3\r\n\r\ntea"));
+///
+/// // Issue two pipelined HTTP/1.0 requests, the first of which asks to keep
+/// // the connection alive, and ensure both responses signal the outcome
+/// let mut stream = TcpStream::connect(addr)?;
+/// stream.write_all(concat!(
+///     "GET /coffee HTTP/1.0\r\nConnection: keep-alive\r\n\r\n",
+///     "GET /tea HTTP/1.0\r\n\r\n",
+/// ).as_bytes())?;
+/// let mut res = String::new();
+/// stream.read_to_string(&mut res)?;
+/// let (first, second) = res.split_once("/coffee").unwrap();
+/// assert!(first.starts_with("HTTP/1.0 200 OK\r\n"));
+/// assert!(first.contains("Connection: keep-alive\r\n"));
+/// assert!(second.starts_with("HTTP/1.0 200 OK\r\n"));
+/// assert!(second.contains("Connection: close\r\n"));
+/// assert!(second.ends_with("\r\n\r\n/tea"));
+///
+/// // Issue a request that expects to continue, and ensure the client is told
+/// // to send the body before the final response is written
+/// let mut stream = TcpStream::connect(addr)?;
+/// stream.write_all(concat!(
+///     "GET /coffee HTTP/1.1\r\nContent-Length: 4\r\n",
+///     "Expect: 100-continue\r\nConnection: close\r\n\r\n",
+/// ).as_bytes())?;
+/// let mut interim = [0; 25];
+/// stream.read_exact(&mut interim)?;
+/// assert_eq!(&interim, b"HTTP/1.1 100 Continue\r\n\r\n");
+/// stream.write_all(b"milk")?;
+/// let mut res = String::new();
+/// stream.read_to_string(&mut res)?;
+/// assert!(res.starts_with("HTTP/1.1 200 OK\r\n"));
+///
+/// // Issue a request that expects to continue with an oversized body, and
+/// // ensure it's rejected without being told to continue
+/// let mut stream = TcpStream::connect(addr)?;
+/// stream.write_all(concat!(
+///     "GET /coffee HTTP/1.1\r\nContent-Length: 1073741824\r\n",
+///     "Expect: 100-continue\r\n\r\n",
+/// ).as_bytes())?;
+/// let mut res = String::new();
+/// stream.read_to_string(&mut res)?;
+/// assert!(res.starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+///
+/// // Issue request that panics, which closes the connection
+/// let mut stream = TcpStream::connect(addr)?;
+/// stream.write_all(b"GET /spill HTTP/1.1\r\n\r\n")?;
+/// let mut res = String::new();
+/// stream.read_to_string(&mut res)?;
+/// assert!(res.starts_with("HTTP/1.1 500 Internal Server Error\r\n"));
+///
+/// // Ensure the worker is still serving connections
+/// let mut stream = TcpStream::connect(addr)?;
+/// stream.write_all(b"GET /milk HTTP/1.1\r\nConnection: close\r\n\r\n")?;
+/// let mut res = String::new();
+/// stream.read_to_string(&mut res)?;
+/// assert!(res.ends_with("\r\n\r\n/milk"));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Files are read and written in chunks, so they're never loaded into memory:
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::io::{Read, Write};
+/// use std::net::TcpStream;
+/// use std::{env, fs, thread};
+/// use zense::http::response::{ResponseExt, CHUNK_SIZE};
+/// use zense::http::{Request, Response};
+/// use zense::router::{Params, Router};
+/// use zense::server::Server;
+///
+/// // Create file spanning multiple chunks
+/// let data = (0..CHUNK_SIZE * 3 + 7).map(|n| n as u8).collect::<Vec<_>>();
+/// let path = env::temp_dir().join("zense-server-file.bin");
+/// fs::write(&path, &data)?;
+///
+/// // Create server bound to an ephemeral port
+/// let server = Server::bind("127.0.0.1:0")?;
+/// let addr = server.local_addr()?;
+///
+/// // Serve requests in the background with a router serving the file
+/// let file = path.clone();
+/// thread::spawn(move || {
+///     server.serve(move || {
+///         let file = file.clone();
+///         Router::default().get("/beans", move |_: Request, _: Params| {
+///             Response::file(&file)
+///         })
+///     })
+/// });
+///
+/// // Issue request for the file, and ensure it's delivered entirely
+/// let mut stream = TcpStream::connect(addr)?;
+/// stream.write_all(b"GET /beans HTTP/1.1\r\nConnection: close\r\n\r\n")?;
+/// let mut res = Vec::new();
+/// stream.read_to_end(&mut res)?;
+/// let head = format!("Content-Length: {}\r\n", data.len());
+/// let n = res.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+/// assert!(String::from_utf8_lossy(&res[..n + 2]).contains(&head));
+/// assert_eq!(&res[n + 4..], data);
+/// # fs::remove_file(&path)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Server {
+    /// Listener.
+    listener: TcpListener,
+    /// Number of worker threads.
+    threads: NonZeroUsize,
+    /// Connection limits.
+    limits: Limits,
+    /// Shared state.
+    state: Arc<State>,
+}
+
+/// Handle for shutting down a server.
+///
+/// Handles are obtained with [`Server::shutdown_handle`] before the server is
+/// started, and can be cloned and sent to other threads. Triggering shutdown
+/// more than once has no further effect.
+#[derive(Clone, Debug)]
+pub struct ShutdownHandle {
+    /// Address to connect to for waking up the server.
+    addr: SocketAddr,
+    /// Shared state.
+    state: Arc<State>,
+}
+
+/// Connection limits.
+#[derive(Clone, Copy, Debug)]
+struct Limits {
+    /// Time after which idle connections are closed.
+    timeout: Duration,
+    /// Time in which a request must be read, once started.
+    request_timeout: Duration,
+    /// Maximum number of requests per connection.
+    max_requests: Option<NonZeroUsize>,
+    /// Maximum length of request bodies.
+    max_body_len: usize,
+    /// Maximum size of request heads.
+    max_header_bytes: usize,
+}
+
+/// Shared state.
+#[derive(Debug, Default)]
+struct State {
+    /// Whether the server is shutting down.
+    stopping: AtomicBool,
+    /// Connections waiting for the next request, ordered by age.
+    idle: Mutex<BTreeMap<u64, TcpStream>>,
+    /// Counter for connection identifiers.
+    counter: AtomicU64,
+    /// Number of waiting workers, minus the number of queued connections.
+    available: AtomicIsize,
+}
+
+/// Reader enforcing the timeouts of a connection.
+///
+/// Until the first byte of a request is received, reads time out after the
+/// connection is idle for too long. Afterwards, the deadline for reading the
+/// request is enforced across all reads, which prevents clients from sending
+/// requests byte by byte, and the connection is not considered idle anymore.
+#[derive(Debug)]
+struct Reader<'a> {
+    /// Connection.
+    stream: &'a TcpStream,
+    /// Connection identifier.
+    id: u64,
+    /// Shared state.
+    state: &'a State,
+    /// Connection limits.
+    limits: Limits,
+    /// Point in time by which the request must be read.
+    deadline: Option<Instant>,
+}
+
+// ----------------------------------------------------------------------------
+// Implementations
+// ----------------------------------------------------------------------------
+
+impl Server {
+    /// Creates a server bound to the given address.
+    ///
+    /// The number of worker threads defaults to the available parallelism,
+    /// idle connections are closed after 5 seconds, requests must be read in
+    /// 30 seconds, the number of requests per connection is not limited, and
+    /// request bodies are limited to 1mb.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an [`io::Error`], if binding fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::server::Server;
+    ///
+    /// // Create server bound to an ephemeral port
+    /// let server = Server::bind("127.0.0.1:0")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bind<A>(addr: A) -> io::Result<Self>
+    where
+        A: ToSocketAddrs,
+    {
+        let listener = TcpListener::bind(addr)?;
+        let threads =
+            thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+        Ok(Self {
+            listener,
+            threads,
+            limits: Limits {
+                timeout: Duration::from_secs(5),
+                request_timeout: Duration::from_secs(30),
+                max_requests: None,
+                max_body_len: 1024 * 1024,
+                max_header_bytes: 8 * 1024,
+            },
+            state: Arc::default(),
+        })
+    }
+
+    /// Sets the number of worker threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::num::NonZeroUsize;
+    /// use zense::server::Server;
+    ///
+    /// // Create server and set number of worker threads
+    /// let server = Server::bind("127.0.0.1:0")?
+    ///     .threads(NonZeroUsize::new(4).unwrap());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn threads(mut self, threads: NonZeroUsize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Sets the time after which idle connections are closed.
+    ///
+    /// Connections are idle while waiting for the next request. Regardless of
+    /// this timeout, idle connections are closed early, when all workers are
+    /// busy and a new connection is accepted, starting with the connection
+    /// that has waited the longest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::io::{Read, Write};
+    /// use std::net::TcpStream;
+    /// use std::num::NonZeroUsize;
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use zense::http::{Request, Response};
+    /// use zense::server::Server;
+    ///
+    /// // Create server with a single worker and set idle timeout
+    /// let server = Server::bind("127.0.0.1:0")?
+    ///     .threads(NonZeroUsize::MIN)
+    ///     .keep_alive_timeout(Duration::from_secs(30));
+    /// let addr = server.local_addr()?;
+    ///
+    /// // Serve requests in the background
+    /// thread::spawn(move || {
+    ///     server.serve(|| |_: Request| Response::new())
+    /// });
+    ///
+    /// // Issue request, and read response, keeping the connection alive
+    /// let mut idle = TcpStream::connect(addr)?;
+    /// idle.write_all(b"GET / HTTP/1.1\r\n\r\n")?;
+    /// let mut res = Vec::new();
+    /// while !res.ends_with(b"\r\n\r\n") {
+    ///     let mut byte = [0];
+    ///     idle.read_exact(&mut byte)?;
+    ///     res.push(byte[0]);
+    /// }
+    ///
+    /// // Issue request on another connection, which is served, as the idle
+    /// // connection is closed to free the worker
+    /// let mut stream = TcpStream::connect(addr)?;
+    /// stream.write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")?;
+    /// let mut res = String::new();
+    /// stream.read_to_string(&mut res)?;
+    /// assert!(res.starts_with("HTTP/1.1 200 OK\r\n"));
+    /// assert_eq!(idle.read(&mut [0])?, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn keep_alive_timeout(mut self, timeout: Duration) -> Self {
+        self.limits.timeout = timeout;
+        self
+    }
+
+    /// Sets the time in which a request must be read, once started.
+    ///
+    /// The deadline starts with the first byte of the request, and covers the
+    /// head and body, regardless of how many reads are necessary. Connections
+    /// that miss the deadline are closed without response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::io::{Read, Write};
+    /// use std::net::TcpStream;
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use zense::http::{Request, Response};
+    /// use zense::server::Server;
+    ///
+    /// // Create server and set request timeout
+    /// let server = Server::bind("127.0.0.1:0")?
+    ///     .request_timeout(Duration::from_millis(100));
+    /// let addr = server.local_addr()?;
+    ///
+    /// // Serve requests in the background
+    /// thread::spawn(move || server.serve(|| |_: Request| Response::new()));
+    ///
+    /// // Issue request, but never finish its head, and ensure the connection
+    /// // is closed once the deadline has passed
+    /// let mut stream = TcpStream::connect(addr)?;
+    /// stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    /// stream.write_all(b"GET /coffee HTTP/1.1\r\n")?;
+    /// let mut res = String::new();
+    /// stream.read_to_string(&mut res)?;
+    /// assert!(res.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.limits.request_timeout = timeout;
+        self
+    }
+
+    /// Sets the maximum number of requests per connection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::num::NonZeroUsize;
+    /// use zense::server::Server;
+    ///
+    /// // Create server and set maximum number of requests per connection
+    /// let server = Server::bind("127.0.0.1:0")?
+    ///     .max_requests(NonZeroUsize::new(100).unwrap());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn max_requests(mut self, max_requests: NonZeroUsize) -> Self {
+        self.limits.max_requests = Some(max_requests);
+        self
+    }
+
+    /// Sets the maximum length of request bodies.
+    ///
+    /// Requests with bodies exceeding this length are answered with "413
+    /// Content Too Large", after which the connection is closed. The length
+    /// announced by the client is checked before the body is read, and chunked
+    /// bodies are checked while they're decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::server::Server;
+    ///
+    /// // Create server and set maximum length of request bodies
+    /// let server = Server::bind("127.0.0.1:0")?
+    ///     .max_body_len(16 * 1024 * 1024);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn max_body_len(mut self, max_body_len: usize) -> Self {
+        self.limits.max_body_len = max_body_len;
+        self
+    }
+
+    /// Sets the maximum size of request heads in bytes.
+    ///
+    /// The request head consists of the request line and the header fields,
+    /// and requests with heads exceeding this size are answered with "431
+    /// Request Header Fields Too Large", after which the connection is closed.
+    /// The size is checked while the head is read, and defaults to 8kb.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::io::{Read, Write};
+    /// use std::net::TcpStream;
+    /// use std::thread;
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Request, Response};
+    /// use zense::server::Server;
+    ///
+    /// // Create server and set maximum size of request heads
+    /// let server = Server::bind("127.0.0.1:0")?
+    ///     .max_header_bytes(256);
+    /// let addr = server.local_addr()?;
+    ///
+    /// // Serve requests in the background
+    /// thread::spawn(move || {
+    ///     server.serve(|| |_: Request| Response::text("ok"))
+    /// });
+    ///
+    /// // Issue request with oversized head, and ensure it's rejected
+    /// let cookie = "a".repeat(256);
+    /// let mut stream = TcpStream::connect(addr)?;
+    /// write!(stream, "GET / HTTP/1.1\r\nCookie: {cookie}\r\n\r\n")?;
+    /// let mut res = String::new();
+    /// stream.read_to_string(&mut res)?;
+    /// assert!(res.starts_with("HTTP/1.1 431 Request Header Fields Too Large"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn max_header_bytes(mut self, max_header_bytes: usize) -> Self {
+        self.limits.max_header_bytes = max_header_bytes;
+        self
+    }
+
+    /// Returns the local address the server is bound to.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an [`io::Error`], if the address can't be obtained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::server::Server;
+    ///
+    /// // Create server and obtain local address
+    /// let server = Server::bind("127.0.0.1:0")?;
+    /// let addr = server.local_addr()?;
+    /// assert_ne!(addr.port(), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Returns a handle for shutting down the server.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an [`io::Error`], if the local address can't be
+    /// obtained, which is necessary for waking up the server on shutdown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use zense::server::Server;
+    ///
+    /// // Create server and obtain shutdown handle
+    /// let server = Server::bind("127.0.0.1:0")?;
+    /// let handle = server.shutdown_handle()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shutdown_handle(&self) -> io::Result<ShutdownHandle> {
+        let mut addr = self.listener.local_addr()?;
+
+        // Servers bound to all interfaces are reachable via the loopback
+        // interface, which we need to connect to for waking up the server
+        if addr.ip().is_unspecified() {
+            addr.set_ip(match addr.ip() {
+                IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            });
+        }
+
+        // Return shutdown handle
+        let state = Arc::clone(&self.state);
+        Ok(ShutdownHandle { addr, state })
+    }
+
+    /// Serves requests with handlers created by the given factory.
+    ///
+    /// The factory is invoked once by each worker thread, which converts its
+    /// result into the handler it serves connections with, so handlers don't
+    /// need to be [`Send`] and [`Sync`], only the factory does. This method
+    /// blocks the calling thread, accepting connections until shutdown is
+    /// triggered via a [`ShutdownHandle`], if ever. Errors when accepting a
+    /// connection are ignored, as they're usually transient, e.g., when a
+    /// client aborts before the connection is set up.
+    ///
+    /// On shutdown, the server stops accepting connections, and connections
+    /// that are waiting for the next request are closed immediately, while
+    /// requests that are currently handled are answered, signaling that the
+    /// connection is closed. This method returns once all workers are done.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an [`io::Error`], if spawning a worker fails, or if
+    /// the conversion into a handler fails, in which case the server is shut
+    /// down before it accepts any connection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::io::{Read, Write};
+    /// use std::net::TcpStream;
+    /// use std::sync::mpsc;
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use zense::http::response::ResponseExt;
+    /// use zense::http::{Request, Response};
+    /// use zense::server::Server;
+    ///
+    /// // Create server and obtain shutdown handle
+    /// let server = Server::bind("127.0.0.1:0")?;
+    /// let addr = server.local_addr()?;
+    /// let handle = server.shutdown_handle()?;
+    ///
+    /// // Serve requests in the background with a slow handler
+    /// let (sender, receiver) = mpsc::channel();
+    /// let serving = thread::spawn(move || {
+    ///     server.serve(move || {
+    ///         let sender = sender.clone();
+    ///         move |_: Request| {
+    ///             sender.send(()).unwrap();
+    ///             thread::sleep(Duration::from_millis(100));
+    ///             Response::text("done")
+    ///         }
+    ///     })
+    /// });
+    ///
+    /// // Issue request, and trigger shutdown while it's handled
+    /// let mut stream = TcpStream::connect(addr)?;
+    /// stream.write_all(b"GET / HTTP/1.1\r\n\r\n")?;
+    /// receiver.recv()?;
+    /// handle.shutdown();
+    ///
+    /// // Wait for server to return, and ensure request was answered
+    /// serving.join().unwrap()?;
+    /// let mut res = String::new();
+    /// stream.read_to_string(&mut res)?;
+    /// assert!(res.contains("Connection: close\r\n"));
+    /// assert!(res.ends_with("\r\n\r\ndone"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn serve<F, T>(self, factory: F) -> io::Result<()>
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: TryIntoHandler,
+    {
+        let factory = Arc::new(factory);
+        let (sender, receiver) = mpsc::channel::<TcpStream>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        // Spawn worker threads, each of which creates its own handler, and
+        // which share the receiving end of the channel, so each connection is
+        // served by the next idle worker
+        let (ready, results) = mpsc::channel();
+        let mut workers = Vec::with_capacity(self.threads.get());
+        for _ in 0..self.threads.get() {
+            let factory = Arc::clone(&factory);
+            let receiver = Arc::clone(&receiver);
+            let state = Arc::clone(&self.state);
+            let limits = self.limits;
+            let ready = ready.clone();
+            let worker = thread::Builder::new()
+                .name(String::from("zense-worker"))
+                .spawn(move || match factory().try_into_handler() {
+                    // Drop the sender before serving, as waiting for the
+                    // results of all workers ends once all are dropped
+                    Ok(handler) => {
+                        let _ = ready.send(Ok(()));
+                        drop(ready);
+                        work(&handler, &receiver, &state, limits);
+                    }
+                    Err(err) => {
+                        let _ = ready.send(Err(err));
+                    }
+                })?;
+            workers.push(worker);
+        }
+
+        // Wait for all workers to create their handlers, and if any of them
+        // fails, close the channel, so the others stop, and return the error
+        drop(ready);
+        if let Some(Err(err)) = results.iter().find(Result::is_err) {
+            drop(sender);
+            for worker in workers {
+                let _ = worker.join();
+            }
+            return Err(io::Error::other(err));
+        }
+
+        // Accept connections, and pass them to the workers, until shutdown
+        // is triggered, which wakes us up by connecting to the server
+        for stream in self.listener.incoming().flatten() {
+            if self.state.is_stopping() {
+                break;
+            }
+
+            // Reserve a waiting worker for the connection, or if there's none,
+            // close the connection that has waited the longest for its next
+            // request, so its worker picks up the connection
+            if !self.state.reserve() {
+                if let Some((_, stream)) = self.state.idle().pop_first() {
+                    let _ = stream.shutdown(Shutdown::Both);
+                }
+            }
+            let _ = sender.send(stream);
+        }
+
+        // Close channel, so workers stop after serving their connections, and
+        // wait for them to finish
+        drop(sender);
+        for worker in workers {
+            let _ = worker.join();
+        }
+        Ok(())
+    }
+}
+
+impl ShutdownHandle {
+    /// Triggers shutdown of the server.
+    ///
+    /// This method returns immediately, i.e., it doesn't wait for the server
+    /// to finish, which is signaled by [`Server::serve`] returning.
+    pub fn shutdown(&self) {
+        if self.state.stopping.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        // Close connections waiting for the next request - connections that
+        // become idle afterwards check whether the server is shutting down
+        for stream in self.state.idle().values() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+
+        // Wake up the server, which is blocked waiting for connections
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+impl State {
+    /// Returns whether the server is shutting down.
+    fn is_stopping(&self) -> bool {
+        self.stopping.load(Ordering::SeqCst)
+    }
+
+    /// Returns the connections waiting for the next request.
+    fn idle(&self) -> MutexGuard<'_, BTreeMap<u64, TcpStream>> {
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Reserves a waiting worker, returning whether one was available.
+    ///
+    /// If no worker is available, the reservation is still recorded, so it's
+    /// fulfilled by the next worker that finishes serving its connection.
+    fn reserve(&self) -> bool {
+        self.available.fetch_sub(1, Ordering::SeqCst) > 0
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Trait implementations
+// ----------------------------------------------------------------------------
+
+impl Read for Reader<'_> {
+    /// Reads from the connection, enforcing its timeouts.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let timeout = match self.deadline {
+            None => self.limits.timeout,
+            Some(deadline) => {
+                let remaining =
+                    deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Err(io::ErrorKind::TimedOut.into());
+                }
+                remaining
+            }
+        };
+
+        // Read from connection, and start the deadline for reading the
+        // request with the first byte, after which it's not idle anymore
+        self.stream.set_read_timeout(Some(timeout))?;
+        let mut stream = self.stream;
+        let n = stream.read(buf)?;
+        if self.deadline.is_none() {
+            self.deadline = Some(Instant::now() + self.limits.request_timeout);
+            self.state.idle().remove(&self.id);
+        }
+        Ok(n)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Functions
+// ----------------------------------------------------------------------------
+
+/// Serves connections from the given receiver, until the channel is closed.
+fn work(
+    handler: &dyn Handler, receiver: &Mutex<Receiver<TcpStream>>,
+    state: &State, limits: Limits,
+) {
+    loop {
+        state.available.fetch_add(1, Ordering::SeqCst);
+        let stream = {
+            let receiver =
+                receiver.lock().unwrap_or_else(PoisonError::into_inner);
+            receiver.recv()
+        };
+
+        // Serve connection, ignoring errors, as there's nobody to report
+        // them to, and the connection is closed afterwards anyway
+        match stream {
+            Ok(stream) => {
+                let _ = serve(handler, stream, state, limits);
+            }
+            Err(_) => break,
+        }
+    }
+}
+
+/// Serves requests on the given connection, until it should be closed.
+fn serve(
+    handler: &dyn Handler, mut stream: TcpStream, state: &State, limits: Limits,
+) -> io::Result<()> {
+    let peer = stream.peer_addr()?.ip();
+    let config = Config::new()
+        .max_body_len(limits.max_body_len)
+        .max_header_bytes(limits.max_header_bytes);
+
+    // Register connection as idle while waiting for the next request, so it
+    // can be closed when shutdown is triggered or all workers are busy, and
+    // check for shutdown after registering - pipelined requests have already
+    // started, so the deadline for reading them starts immediately
+    let id = state.counter.fetch_add(1, Ordering::Relaxed);
+    let read = |stream: &mut TcpStream, buffer: &mut Vec<u8>| {
+        let mut deadline = None;
+        if buffer.is_empty() {
+            state.idle().insert(id, stream.try_clone()?);
+        } else {
+            deadline = Some(Instant::now() + limits.request_timeout);
+        }
+
+        // Read request, unless the server is shutting down, and tell clients
+        // that wait for it before sending the body to go ahead - the hook is
+        // only invoked when the body is going to be read
+        let req = if state.is_stopping() {
+            Err(Error::Incomplete)
+        } else {
+            let stream = &*stream;
+            let reader = Reader {
+                stream,
+                id,
+                state,
+                limits,
+                deadline,
+            };
+            let hook = |req: &Request| {
+                let http11 = req.version == Version::Http11;
+                if http11 && req.expects_continue() {
+                    Response::continue_().write_to(stream)?;
+                }
+                Ok(())
+            };
+            Request::from_reader_buffered_with_hook(
+                reader, buffer, &config, hook,
+            )
+        };
+        state.idle().remove(&id);
+        Ok::<_, io::Error>(req)
+    };
+
+    // Read requests, retaining bytes of pipelined requests in the buffer
+    let mut buffer = Vec::new();
+    let mut count = 0;
+    loop {
+        count += 1;
+        let mut req = match read(&mut stream, &mut buffer)? {
+            Ok(req) => req,
+
+            // Close connection when the client closed it, it was idle, or
+            // the server is shutting down
+            Err(Error::Incomplete | Error::Io(_)) => return Ok(()),
+
+            // Answer requests that can't be read, and close connection
+            Err(err) => {
+                let res = err.into_response();
+                return write(&mut stream, &res.close_connection());
+            }
+        };
+
+        // Determine whether the connection should be kept alive, which is the
+        // case if the client asks for it, and the limit is not reached yet
+        let limit = limits.max_requests.map_or(usize::MAX, NonZeroUsize::get);
+        let keep_alive = req.keep_alive() && count < limit;
+
+        // Handle request, and answer panics with a generic response, closing
+        // the connection, as we can't know in which state the handler left it
+        req.peer = Some(peer);
+        let version = req.version;
+        let head = req.method == Method::Head;
+        let res = panic::catch_unwind(AssertUnwindSafe(|| handler.handle(req)));
+        let Ok(mut res) = res else {
+            let mut res = Response::from_status(Status::InternalServerError);
+            res.version = version;
+            if head {
+                strip_body(&mut res);
+            }
+            return write(&mut stream, &res.close_connection());
+        };
+
+        // Align version of response with request, sending the body with a
+        // content length to HTTP/1.0 clients, which don't know chunking, and
+        // drop the body of responses to HEAD requests, which isn't expected
+        res.version = version;
+        if version == Version::Http10 {
+            strip_chunked(&mut res);
+        }
+        if head {
+            strip_body(&mut res);
+        }
+
+        // Write response, and close connection, unless it should be kept
+        // alive, the handler didn't signal to close it, and the server is
+        // not shutting down - HTTP/1.0 clients must be told explicitly
+        if keep_alive && !closes(&res) && !state.is_stopping() {
+            if version == Version::Http10 {
+                keep_connection(&mut res);
+            }
+            write(&mut stream, &res)?;
+        } else {
+            return write(&mut stream, &res.close_connection());
+        }
+    }
+}
+
+/// Drops the body of the given response, retaining its length.
+///
+/// The content length is set to the length of the body, unless present, so
+/// the client learns the length of the body it would have received. Chunked
+/// transfer encoding and trailers are dropped as well, since writing them
+/// would put the terminating chunk on the wire, corrupting the framing of the
+/// next response on the connection.
+fn strip_body(res: &mut Response) {
+    let len = res.file.take().map_or(0, |file| file.len());
+    let len = res.body.len() as u64 + len;
+    if !res.headers.contains(Header::ContentLength) && len > 0 {
+        res.headers.put(Header::ContentLength, len.to_string());
+    }
+    strip_chunked(res);
+    res.body.clear();
+}
+
+/// Drops chunked transfer encoding and trailers from the given response.
+///
+/// Without transfer encoding, the body is written with a content length, as
+/// computed by [`Response::write_to`], unless already present. Trailers are
+/// dropped, as there's no place for them after a fixed-length body.
+fn strip_chunked(res: &mut Response) {
+    res.headers.remove(Header::TransferEncoding);
+    res.headers.remove(Header::Trailer);
+    res.trailers = Headers::new();
+}
+
+/// Returns whether the given response signals to close the connection.
+fn closes(res: &Response) -> bool {
+    res.headers.contains_token(Header::Connection, "close")
+}
+
+/// Signals that the connection is kept alive after the given response.
+///
+/// HTTP/1.0 clients close the connection after each response, unless it lists
+/// `keep-alive` in the [`Header::Connection`] header, which is added to the
+/// options set by the handler, if any.
+fn keep_connection(res: &mut Response) {
+    if !res.headers.contains_token(Header::Connection, "keep-alive") {
+        let value = match res.headers.get(Header::Connection) {
+            Some(value) => format!("{value}, keep-alive"),
+            None => String::from("keep-alive"),
+        };
+        res.headers.put(Header::Connection, value);
+    }
+}
+
+/// Writes the given response to the given connection.
+fn write(stream: &mut TcpStream, res: &Response) -> io::Result<()> {
+    res.write_to(&mut *stream)?;
+    stream.flush()
+}
@@ -0,0 +1,58 @@
+// Copyright (c) 2024 Zensical <contributors@zensical.org>
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NON-INFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+// ----------------------------------------------------------------------------
+
+//! Fuzz target for parsing requests.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zense::http::request::Config;
+use zense::http::Request;
+
+// ----------------------------------------------------------------------------
+// Fuzz targets
+// ----------------------------------------------------------------------------
+
+fuzz_target!(|data: &[u8]| {
+    // Parse request, and exercise all methods that interpret headers, which
+    // must never panic, regardless of the input
+    if let Ok(req) = Request::from_bytes(data) {
+        let _ = req.to_bytes();
+        let _ = req.to_string();
+        let _ = req.keep_alive();
+        let _ = req.expects_continue();
+        let _ = req.upgrade_target();
+        let _ = req.content_type();
+        let _ = req.accepts();
+        let _ = req.forwarded();
+        let _ = req.multipart();
+    }
+
+    // Parse request with framing, and from a reader
+    let _ = Request::from_bytes_partial(data);
+    let _ = Request::from_reader(data);
+
+    // Parse request from a reader with limits, which are small enough to be
+    // exceeded by chunk data, chunk size lines and trailers
+    let config = Config::new().max_header_bytes(256).max_body_len(16);
+    let _ = Request::from_reader_buffered_with(data, &mut Vec::new(), &config);
+});